@@ -1,27 +1,164 @@
 use exfiltrate_internal::command::Response;
+use exfiltrate_internal::cookie;
+use exfiltrate_internal::rendezvous::{self, Rendezvous};
 use exfiltrate_internal::rpc::{CommandResponse, RPC};
-use exfiltrate_internal::wire::{BACKOFF_DURATION, InFlightMessage, send_socket_rpc};
-use std::net::TcpStream;
-use std::sync::{Arc, LazyLock, Mutex};
+use exfiltrate_internal::wire::{ADDR, BACKOFF_DURATION, InFlightMessage, WireTransport, send_socket_rpc};
+use std::collections::HashMap;
+use std::sync::mpsc::{Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Condvar, LazyLock, Mutex};
+use std::time::Duration;
 
-/// Manages the TCP connection to the remote application.
+/// Name of the environment variable overriding the connection scheme (`tcp`,
+/// `ws`, or `quic`) used to reach the address [`locate_server`] found; see
+/// [`connection_url`]. Unset means plain TCP, the default for a server
+/// published via the rendezvous file.
+const TRANSPORT_ENV_VAR: &str = "EXFILTRATE_TRANSPORT";
+
+/// Default deadline for [`Client::pop_msg`], used when no explicit timeout is
+/// given via [`Client::pop_msg_with_timeout`].
+const DEFAULT_POP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Manages the wire connection to the remote application.
 ///
-/// Handles sending RPC commands and receiving responses, including
-/// reassembling multi-part messages (attachments) and reporting progress
-/// for large transfers.
+/// A single background `wire_client_recv` thread owns the socket and is the
+/// only thing that ever reads it. Every other thread calling [`Client::send_rpc`]
+/// or [`Client::pop_msg`] just writes its request and then waits on its own
+/// channel -- see [`ClientLock::pending`] -- so the background thread's
+/// per-`reply_id` demultiplexing of the server's broadcast replies hands each
+/// response straight to the call that's waiting for it, with no busy polling
+/// and no response getting lost to the wrong caller.
 pub struct Client {
     last_reply_id: std::sync::atomic::AtomicU32,
     lock: Arc<Mutex<ClientLock>>,
+    /// Notified whenever [`ClientLock::terminal`] is set; see
+    /// [`Client::wait_for_exit`]. Shared with the `wire_client_recv` thread.
+    terminal_ready: Arc<Condvar>,
+}
+
+/// One item of a streamed command response, delivered to whoever called
+/// [`Client::subscribe_stream`] for its `reply_id`; see [`RPC::CommandStreamItem`].
+#[derive(Debug)]
+pub struct StreamItem {
+    /// This item's position in the sequence, starting at `0`.
+    pub seq: u32,
+    /// This item's payload.
+    pub response: Response,
+    /// Whether this is the last item for this subscription -- after this
+    /// one, the [`Receiver`] returned by [`Client::subscribe_stream`] will
+    /// yield no further items.
+    pub is_final: bool,
 }
 
 struct ClientLock {
-    stream: TcpStream,
+    stream: Box<dyn WireTransport>,
     in_flight_message: exfiltrate_internal::wire::InFlightMessage,
+    /// The sending half of a one-shot channel per `reply_id` currently
+    /// awaiting a response, registered by [`Client::next_reply_id`] *before*
+    /// the corresponding RPC is written to the wire. Consulted only by the
+    /// `wire_client_recv` thread, which removes and fires the matching
+    /// entry as soon as it demultiplexes a [`CommandResponse`] off the
+    /// socket -- registering this early closes the race where the response
+    /// comes back before the caller gets around to calling [`Client::pop_msg`].
+    pending: HashMap<u32, Sender<CommandResponse>>,
+    /// The receiving half of each [`Self::pending`] channel, claimed (removed)
+    /// by [`Client::pop_msg_with_timeout`] once the caller is ready to block
+    /// on its reply.
+    receivers: HashMap<u32, Receiver<CommandResponse>>,
+    /// The sending half of a per-`reply_id` channel for a command that
+    /// answers with [`RPC::CommandStreamItem`]s rather than a single
+    /// [`CommandResponse`], registered by [`Client::subscribe_stream`].
+    /// Removed once the item with `is_final` set arrives, so the
+    /// [`Receiver`] on the other end (see [`StreamItem`]) ends its iteration
+    /// there rather than blocking forever on a sender no one will use again.
+    streams: HashMap<u32, Sender<StreamItem>>,
+    /// Set by the `wire_client_recv` thread, with the reason, when reading
+    /// the connection fails or the server rejects the handshake, instead of
+    /// that thread calling `std::process::exit` itself. `None` while the
+    /// connection is still up. The thread never sets `Ok(())`: the transport
+    /// reports a peer's clean close the same as "no data yet", so a clean
+    /// close leaves this `None`. See [`Client::wait_for_exit`].
+    terminal: Option<Result<(), String>>,
+}
+
+/// Locates the running server and its handshake cookie.
+///
+/// Prefers the rendezvous file, since the server may have bound an ephemeral
+/// port or fallen back from the default address. Falls back to [`ADDR`] with
+/// no cookie (triggering an auth failure rather than a silent hang) only when
+/// no rendezvous file exists yet, which covers the brief startup race before
+/// the very first server on a host has written one.
+fn locate_server() -> Result<Rendezvous, String> {
+    match rendezvous::with_lock(rendezvous::read) {
+        Ok(info) if rendezvous::is_reachable(info.addr) => Ok(info),
+        Ok(_) => Err(format!(
+            "Could not find a running exfiltrate server (checked the rendezvous file and {}); \
+             is the debugged application running?",
+            ADDR
+        )),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Rendezvous {
+            addr: ADDR.parse().expect("ADDR is a valid socket address"),
+            cookie: [0u8; cookie::COOKIE_LEN],
+        }),
+        Err(e) => Err(format!(
+            "Could not find a running exfiltrate server (checked the rendezvous file and {}): {}",
+            ADDR, e
+        )),
+    }
+}
+
+/// Builds the connection URL [`exfiltrate_internal::wire::connect`] dials:
+/// `<scheme>://<addr>`, where `addr` is wherever [`locate_server`] found the
+/// server listening and `scheme` is `tcp` unless [`TRANSPORT_ENV_VAR`]
+/// requests `ws` or `quic` instead (to attach across whatever transport the
+/// debugged application's proxy is bridging rather than a raw TCP port).
+fn connection_url(info: &Rendezvous) -> String {
+    let scheme = std::env::var(TRANSPORT_ENV_VAR).unwrap_or_else(|_| "tcp".to_string());
+    format!("{}://{}", scheme, info.addr)
+}
+
+/// Answers the server's [`RPC::Challenge`] with an [`RPC::Hello`] proving
+/// knowledge of `cookie`, blocking (via the usual non-blocking-read/backoff
+/// pattern) until the challenge arrives.
+///
+/// Returns `Err("handshake rejected")`-shaped messages on `RPC::AuthError` or
+/// any other unexpected reply, mirroring the distinct `ConnectionRefused`/
+/// `PermissionDenied` cases in [`Client::new`], so the CLI can tell the user their cookie
+/// is wrong rather than that the app isn't running.
+fn perform_handshake(
+    stream: &mut dyn WireTransport,
+    cookie: &exfiltrate_internal::cookie::Cookie,
+) -> Result<(), String> {
+    let mut in_flight_message = InFlightMessage::new();
+    loop {
+        match in_flight_message
+            .read_stream(stream)
+            .map_err(|e| e.to_string())?
+        {
+            exfiltrate_internal::wire::ReadStatus::WouldBlock => {
+                std::thread::sleep(BACKOFF_DURATION);
+            }
+            exfiltrate_internal::wire::ReadStatus::Progress => continue,
+            exfiltrate_internal::wire::ReadStatus::Completed(pop) => {
+                return match rmp_serde::from_slice::<RPC>(&pop).ok() {
+                    Some(RPC::Challenge { nonce }) => {
+                        let proof = cookie::prove(cookie, &nonce);
+                        send_socket_rpc(RPC::Hello { proof }, stream).map_err(|e| e.to_string())
+                    }
+                    Some(RPC::AuthError { reason }) => {
+                        Err(format!("handshake rejected: {}", reason))
+                    }
+                    _ => Err("handshake rejected: unexpected reply from server".to_string()),
+                };
+            }
+            _ => return Err("handshake rejected: unexpected reply from server".to_string()),
+        }
+    }
 }
 
 impl Client {
     fn new() -> Result<Self, String> {
-        let stream = match TcpStream::connect(exfiltrate_internal::wire::ADDR) {
+        let info = locate_server()?;
+        let mut stream = match exfiltrate_internal::wire::connect(&connection_url(&info)) {
             Ok(s) => s,
             Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
                 return Err(
@@ -30,36 +167,57 @@ impl Client {
             }
             Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
                 return Err(
-                    "Permission denied opening a TCP connection; maybe you're in a sandbox?"
-                        .to_owned(),
+                    "Permission denied opening a connection; maybe you're in a sandbox?".to_owned(),
                 );
             }
             Err(e) => return Err(e.to_string()),
         };
+        perform_handshake(&mut *stream, &info.cookie)?;
         let lock = Arc::new(Mutex::new(ClientLock {
             stream,
             in_flight_message: InFlightMessage::new(),
+            pending: HashMap::new(),
+            receivers: HashMap::new(),
+            streams: HashMap::new(),
+            terminal: None,
         }));
         let move_lock = lock.clone();
+        let terminal_ready = Arc::new(Condvar::new());
+        let move_terminal_ready = terminal_ready.clone();
         std::thread::Builder::new()
             .name("wire_client_recv".to_string())
             .spawn(move || {
-                let mut in_flight_message = InFlightMessage::new();
                 let mut last_print = std::time::Instant::now();
                 loop {
-                    let msg = in_flight_message.read_stream(&mut move_lock.lock().unwrap().stream);
-                    match msg {
+                    let mut lock = move_lock.lock().unwrap();
+                    let lock_ref = &mut *lock;
+                    let r = lock_ref
+                        .in_flight_message
+                        .read_stream(&mut lock_ref.stream);
+                    match r {
                         Err(e) => {
-                            eprintln!("Error reading from stream: {}", e);
-                            std::process::exit(1);
+                            lock.terminal = Some(Err(e.to_string()));
+                            // Drop every waiting sender so blocked `pop_msg`
+                            // and `subscribe_stream` callers see the disconnect.
+                            lock.pending.clear();
+                            lock.streams.clear();
+                            drop(lock);
+                            move_terminal_ready.notify_all();
+                            return;
                         }
                         Ok(exfiltrate_internal::wire::ReadStatus::WouldBlock) => {
+                            // Release the lock while we wait so `send_rpc`
+                            // (or a future reconnect) can use the stream
+                            // meanwhile, rather than this thread -- the only
+                            // one that ever reads the socket -- pinning it
+                            // for the whole backoff.
+                            drop(lock);
                             std::thread::sleep(BACKOFF_DURATION);
                             continue;
                         }
                         Ok(exfiltrate_internal::wire::ReadStatus::Progress) => {
                             //report progress
-                            let msg = &mut move_lock.lock().unwrap().in_flight_message;
+                            let msg = &mut lock.in_flight_message;
                             if let Some(expected) = msg.expected_length()
                                 && expected > 100_000
                                 && last_print.elapsed().as_millis() > 100
@@ -82,32 +240,105 @@ impl Client {
                             let rpc =
                                 rmp_serde::from_slice::<RPC>(&msg).expect("Invalid RPC message");
                             match rpc {
+                                RPC::AuthError { reason } => {
+                                    lock.terminal =
+                                        Some(Err(format!("Handshake rejected by server: {}", reason)));
+                                    lock.pending.clear();
+                                    lock.streams.clear();
+                                    drop(lock);
+                                    move_terminal_ready.notify_all();
+                                    return;
+                                }
                                 RPC::Command(_) => {
-                                    todo!("Not expecting a command in reply!")
+                                    eprintln!("wire_client_recv: unexpected Command variant received");
                                 }
-                                RPC::CommandResponse(response) => {
-                                    if response.success {
-                                        match response.response {
-                                            Response::String(s) => {
-                                                eprintln!("{}", s);
-                                                std::process::exit(0);
-                                            }
-
-                                            _ => {
-                                                todo!("Not implemented this response type yet")
+                                RPC::CommandResponse(mut response) => {
+                                    if response.num_attachments > 0 {
+                                        let mut attachments = Vec::new();
+                                        for _ in 0..response.num_attachments {
+                                            loop {
+                                                let lock_ref = &mut *lock;
+                                                let r = lock_ref
+                                                    .in_flight_message
+                                                    .read_stream(&mut lock_ref.stream);
+                                                match r {
+                                                    Ok(exfiltrate_internal::wire::ReadStatus::Completed(data)) => {
+                                                        attachments.push(data);
+                                                        break;
+                                                    }
+                                                    Ok(exfiltrate_internal::wire::ReadStatus::WouldBlock) => {
+                                                        drop(lock);
+                                                        std::thread::sleep(BACKOFF_DURATION);
+                                                        lock = move_lock.lock().unwrap();
+                                                    }
+                                                    Ok(exfiltrate_internal::wire::ReadStatus::Progress) => {}
+                                                    Ok(_) => {
+                                                        eprintln!("Unknown ReadStatus variant received");
+                                                    }
+                                                    Err(e) => {
+                                                        lock.terminal = Some(Err(e.to_string()));
+                                                        lock.pending.clear();
+                                                        lock.streams.clear();
+                                                        drop(lock);
+                                                        move_terminal_ready.notify_all();
+                                                        return;
+                                                    }
+                                                }
                                             }
                                         }
+                                        response.response.merge_data(attachments);
+                                    }
+                                    // Deliver the response straight to whichever
+                                    // `pop_msg` call is (or will be) waiting on
+                                    // this `reply_id`; a missing entry means no
+                                    // one registered one (or it already timed
+                                    // out and dropped its receiver), and the
+                                    // response is simply discarded.
+                                    if let Some(sender) = lock.pending.remove(&response.reply_id) {
+                                        let _ = sender.send(response);
                                     } else {
-                                        match response.response {
-                                            Response::String(s) => {
-                                                eprintln!("Error: {}", s);
-                                                std::process::exit(2);
-                                            }
-                                            _ => {
-                                                todo!("Not implemented this response type yet")
-                                            }
+                                        eprintln!(
+                                            "wire_client_recv: no pending request for reply_id {}",
+                                            response.reply_id
+                                        );
+                                    }
+                                    drop(lock);
+                                    continue;
+                                }
+                                RPC::CommandStreamItem {
+                                    reply_id,
+                                    seq,
+                                    response,
+                                    is_final,
+                                } => {
+                                    // Unlike `pending`, a stream's entry stays
+                                    // registered across every non-final item
+                                    // -- only remove it once `is_final` says
+                                    // no more are coming, so the `Receiver`
+                                    // on the other end sees the sender drop
+                                    // and ends its iteration there.
+                                    let sender = if is_final {
+                                        lock.streams.remove(&reply_id)
+                                    } else {
+                                        lock.streams.get(&reply_id).cloned()
+                                    };
+                                    match sender {
+                                        Some(sender) => {
+                                            let _ = sender.send(StreamItem {
+                                                seq,
+                                                response,
+                                                is_final,
+                                            });
+                                        }
+                                        None => {
+                                            eprintln!(
+                                                "wire_client_recv: no stream subscriber for reply_id {}",
+                                                reply_id
+                                            );
                                         }
                                     }
+                                    drop(lock);
+                                    continue;
                                 }
                                 _ => {
                                     eprintln!("Unknown RPC variant received");
@@ -124,141 +355,144 @@ impl Client {
         Ok(Client {
             last_reply_id: 0.into(),
             lock,
+            terminal_ready,
         })
     }
 
     /// Sends an RPC command to the remote application.
     ///
-    /// Serializes the RPC message and writes it to the TCP stream.
+    /// Serializes the RPC message and writes it to the wire transport.
     pub fn send_rpc(&self, rpc: exfiltrate_internal::rpc::RPC) -> Result<(), std::io::Error> {
         send_socket_rpc(rpc, &mut self.lock.lock().unwrap().stream)?;
         Ok(())
     }
+
+    /// Sends several command invocations in a single frame and waits for all
+    /// their responses, saving a round trip each compared to issuing them
+    /// one at a time.
+    ///
+    /// If `sequence` is `false`, the server may run the invocations
+    /// concurrently; responses still come back in `invocations` order. If
+    /// `true`, the server runs them strictly in order and short-circuits on
+    /// the first failure, filling in a synthetic failed response for every
+    /// invocation it skipped as a result.
+    ///
+    /// Responses are matched up by `reply_id` through the same
+    /// [`Client::pop_msg`] correlation a plain [`Client::send_rpc`] +
+    /// [`Client::pop_msg`] pair uses (each invocation's `reply_id` must
+    /// already have been registered via [`Client::next_reply_id`]), so a
+    /// batch composes fine with other concurrent callers sharing this
+    /// `Client`.
+    pub fn send_batch(
+        &self,
+        invocations: &[exfiltrate_internal::rpc::CommandInvocation],
+        sequence: bool,
+    ) -> Result<Vec<CommandResponse>, std::io::Error> {
+        self.send_rpc(RPC::Batch {
+            invocations: invocations.to_vec(),
+            sequence,
+        })?;
+        invocations
+            .iter()
+            .map(|invocation| self.pop_msg(invocation.reply_id))
+            .collect()
+    }
+
     /// Waits for and retrieves a specific response message.
     ///
-    /// Blocks until a response with the matching `reply_id` is received.
-    /// Handles:
-    /// *   Reading from the stream.
-    /// *   Reassembling multi-part attachments.
-    /// *   Reporting progress for large transfers to stderr.
-    /// *   Filtering out unrelated messages (TODO: currently panics or drops them).
+    /// Equivalent to [`Client::pop_msg_with_timeout`] with [`DEFAULT_POP_TIMEOUT`].
     pub fn pop_msg(&self, reply_id: u32) -> Result<CommandResponse, std::io::Error> {
-        let mut lock = self.lock.lock().unwrap();
-        let mut last_print = std::time::Instant::now();
-        let start_time = std::time::Instant::now();
-        let mut waiting_message_printed = false;
-        loop {
-            //this is needed to destructure two fields
-            let lock_ref = &mut *lock;
-            let stream = &mut lock_ref.stream;
-            let msg = &mut lock_ref.in_flight_message;
-
-            if !waiting_message_printed
-                && msg.expected_length().is_none()
-                && start_time.elapsed().as_secs() >= 5
-            {
-                eprintln!("Waiting for reply...");
-                waiting_message_printed = true;
-            }
+        self.pop_msg_with_timeout(reply_id, DEFAULT_POP_TIMEOUT)
+    }
 
-            let r = msg.read_stream(stream)?;
-            match r {
-                exfiltrate_internal::wire::ReadStatus::Completed(message) => {
-                    //parse to RPC
-                    let rpc_msg: RPC = rmp_serde::from_slice(&message).unwrap();
-                    match rpc_msg {
-                        RPC::CommandResponse(mut command) => {
-                            if command.reply_id == reply_id {
-                                if command.num_attachments > 0 {
-                                    let mut attachments = Vec::new();
-                                    for _ in 0..command.num_attachments {
-                                        loop {
-                                            // We need to keep reading until we get the attachment
-                                            let r = msg.read_stream(stream)?;
-                                            match r {
-                                                exfiltrate_internal::wire::ReadStatus::Completed(data) => {
-                                                    attachments.push(data);
-                                                    break;
-                                                }
-                                                exfiltrate_internal::wire::ReadStatus::WouldBlock => {
-                                                    std::thread::sleep(BACKOFF_DURATION);
-                                                }
-                                                exfiltrate_internal::wire::ReadStatus::Progress => {
-                                                     // Reuse the progress reporting logic from the outer loop if possible,
-                                                     // or just ignore for now as attachments are parts of the "response"
-                                                     // actually, for large files, these attachments ARE the large part.
-                                                     // So we should probably report progress.
-                                                     // But let's keep it simple for now to ensure correctness.
-                                                     // The outer loop's progress reporting relies on `msg.expected_length()`.
-                                                     // `read_stream` updates `msg` state.
-                                                     // So we can copy the progress logic here.
-                                                    if let Some(expected) = msg.expected_length()
-                                                        && expected > 100_000
-                                                        && last_print.elapsed().as_millis() > 100
-                                                    {
-                                                        let current = msg.current_length();
-                                                        use std::io::Write;
-                                                        eprint!(
-                                                            "\rReceived attachment part {} / {} bytes ({}%)",
-                                                            current,
-                                                            expected,
-                                                            (current * 100) / (expected as usize)
-                                                        );
-                                                        std::io::stderr().flush().unwrap();
-                                                        last_print = std::time::Instant::now();
-                                                    }
-                                                }
-                                                _ => {
-                                                    eprintln!("Unknown ReadStatus variant received");
-                                                }
-                                            }
-                                        }
-                                    }
-                                    command.response.merge_data(attachments);
-                                }
-                                return Ok(command);
-                            } else {
-                                todo!("Need to buffer other messages somewhere")
-                            }
-                        }
-                        _ => {
-                            todo!("Other RPC messages not currently handled")
-                        }
-                    }
-                }
-                exfiltrate_internal::wire::ReadStatus::Progress => {
-                    //report progress
-                    let msg = &mut lock_ref.in_flight_message;
-                    if let Some(expected) = msg.expected_length()
-                        && expected > 100_000
-                        && last_print.elapsed().as_millis() > 100
-                    {
-                        let current = msg.current_length();
-                        use std::io::Write;
-                        eprint!(
-                            "\rReceived {} / {} bytes ({}%)",
-                            current,
-                            expected,
-                            (current * 100) / (expected as usize)
-                        );
-                        std::io::stderr().flush().unwrap();
-                        last_print = std::time::Instant::now();
-                    }
-                    continue;
-                }
-                exfiltrate_internal::wire::ReadStatus::WouldBlock => {
-                    std::thread::sleep(BACKOFF_DURATION);
-                }
-                _ => {
-                    eprintln!("Unknown ReadStatus variant received");
-                }
+    /// Waits for and retrieves the response registered for `reply_id` by
+    /// [`Client::next_reply_id`], giving up after `timeout` has elapsed.
+    ///
+    /// This call itself never touches the socket -- the background
+    /// `wire_client_recv` thread spawned in [`Client::new`] is the only
+    /// reader, and delivers the response straight down this `reply_id`'s
+    /// channel as soon as it demultiplexes it off the wire. On timeout, the
+    /// still-registered sender is dropped so a response that arrives later
+    /// is silently discarded instead of being handed to a caller who already
+    /// gave up.
+    pub fn pop_msg_with_timeout(
+        &self,
+        reply_id: u32,
+        timeout: Duration,
+    ) -> Result<CommandResponse, std::io::Error> {
+        let receiver = self
+            .lock
+            .lock()
+            .unwrap()
+            .receivers
+            .remove(&reply_id)
+            .expect("pop_msg called for a reply_id that next_reply_id never registered");
+        match receiver.recv_timeout(timeout) {
+            Ok(response) => Ok(response),
+            Err(RecvTimeoutError::Timeout) => {
+                self.lock.lock().unwrap().pending.remove(&reply_id);
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::TimedOut,
+                    format!("timed out waiting for reply {}", reply_id),
+                ))
             }
+            Err(RecvTimeoutError::Disconnected) => Err(std::io::Error::new(
+                std::io::ErrorKind::BrokenPipe,
+                format!("connection closed before reply {} arrived", reply_id),
+            )),
         }
     }
-    /// Generates a unique ID for the next RPC request.
+
+    /// Allocates a fresh `reply_id` and registers the channel
+    /// [`Client::pop_msg`] will later claim to wait for its response.
+    ///
+    /// Registers *before* returning, not when the caller gets around to
+    /// calling `pop_msg`, so the `wire_client_recv` thread has somewhere to
+    /// deliver the response even if it reads it back before the caller's RPC
+    /// has finished being sent.
     pub(crate) fn next_reply_id(&self) -> u32 {
-        self.last_reply_id
-            .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
+        let reply_id = self
+            .last_reply_id
+            .fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let mut lock = self.lock.lock().unwrap();
+        lock.pending.insert(reply_id, sender);
+        lock.receivers.insert(reply_id, receiver);
+        reply_id
+    }
+
+    /// Registers a [`StreamItem`] channel for `reply_id` and returns its
+    /// receiving half as an iterator: each call to `.next()` blocks for the
+    /// next item and the iterator ends once the item with `is_final` set has
+    /// been delivered (or reading the connection fails, whichever comes
+    /// first).
+    ///
+    /// `reply_id` must already have been allocated by [`Client::next_reply_id`],
+    /// the same precondition [`Client::pop_msg`] has -- call this instead of
+    /// `pop_msg` for a command expected to answer with [`RPC::CommandStreamItem`]s.
+    pub fn subscribe_stream(&self, reply_id: u32) -> std::sync::mpsc::IntoIter<StreamItem> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.lock.lock().unwrap().streams.insert(reply_id, sender);
+        receiver.into_iter()
+    }
+
+    /// Blocks until the `wire_client_recv` thread ends the connection, then
+    /// returns why: `Err` with the read error or handshake rejection that
+    /// ended it. `Ok(())` is never returned, since a clean close by the
+    /// server is not detected (see [`ClientLock::terminal`]) and does not end
+    /// the wait.
+    ///
+    /// Lets `main` decide the process exit code instead of the background
+    /// thread calling `std::process::exit` itself, which made the `Client`
+    /// impossible to embed or shut down cleanly.
+    pub fn wait_for_exit(&self) -> Result<(), String> {
+        let mut lock = self.lock.lock().unwrap();
+        loop {
+            if let Some(result) = &lock.terminal {
+                return result.clone();
+            }
+            lock = self.terminal_ready.wait(lock).unwrap();
+        }
     }
 }
 
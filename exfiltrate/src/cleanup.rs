@@ -0,0 +1,28 @@
+//! Pre-termination cleanup hooks, drained by `terminate`/`abort` before they
+//! exit.
+//!
+//! Other subsystems register closures here (temp files to remove, sockets
+//! to close, spawned children to reap) so those resources are released
+//! deterministically instead of abandoned when the process exits abruptly.
+
+use std::sync::Mutex;
+
+/// The registered hooks, in registration order.
+static ON_TERMINATE: Mutex<Vec<Box<dyn FnOnce() + Send>>> = Mutex::new(Vec::new());
+
+/// Registers `hook` to run once, just before `terminate` or `abort` ends
+/// the process -- see `exfiltrate::register_on_terminate`.
+pub(crate) fn register<F: FnOnce() + Send + 'static>(hook: F) {
+    ON_TERMINATE.lock().unwrap().push(Box::new(hook));
+}
+
+/// Drains every registered hook and runs each in registration order. Called
+/// by `Terminate`/`Abort` on the delayed-termination thread, after the
+/// `Response` has had a chance to flush back to the client but before the
+/// process actually exits.
+pub(crate) fn run_hooks() {
+    let hooks = std::mem::take(&mut *ON_TERMINATE.lock().unwrap());
+    for hook in hooks {
+        hook();
+    }
+}
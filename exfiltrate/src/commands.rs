@@ -1,6 +1,7 @@
 use crate::command::Command;
 use wasm_safe_mutex::rwlock::RwLock;
 
+mod abort;
 mod help;
 mod list;
 mod terminate;
@@ -10,7 +11,7 @@ mod terminate;
 /// This list is populated by `register_commands` and `exfiltrate::add_command`.
 pub(crate) static COMMANDS: RwLock<Vec<Box<dyn Command>>> = RwLock::new(vec![]);
 
-/// Registers the built-in commands (help, list, terminate).
+/// Registers the built-in commands (help, list, terminate, abort).
 ///
 /// This is called automatically by `exfiltrate::begin()`.
 pub(crate) fn register_commands() {
@@ -19,4 +20,6 @@ pub(crate) fn register_commands() {
     lock.push(Box::new(list::List));
     #[cfg(not(target_arch = "wasm32"))]
     lock.push(Box::new(terminate::Terminate));
+    #[cfg(not(target_arch = "wasm32"))]
+    lock.push(Box::new(abort::Abort));
 }
@@ -0,0 +1,43 @@
+#![cfg(not(target_arch = "wasm32"))]
+use exfiltrate_internal::command::{Command, Response};
+
+/// The `abort` command.
+///
+/// A sibling to [`crate::commands::terminate::Terminate`] that triggers
+/// `std::process::abort()` instead of a clean exit, so an attached debugger,
+/// crash reporter, or core-dump tooling catches the termination. Only
+/// available on native targets.
+pub struct Abort;
+
+impl Command for Abort {
+    fn name(&self) -> &'static str {
+        "abort"
+    }
+
+    fn short_description(&self) -> &'static str {
+        "Aborts the program being debugged, producing a core dump.  Use this when you need a post-mortem instead of a clean exit."
+    }
+
+    fn full_description(&self) -> &'static str {
+        "Aborts the program being debugged, producing a core dump.
+
+Unlike `terminate`, which exits cleanly with a status code, this command calls
+the C `abort()` function.  This raises SIGABRT (on Unix) so an attached
+debugger, crash reporter, or core-dump tooling catches the termination, which
+is useful when the operator wants a post-mortem rather than a clean exit.
+
+Usage: exfiltrate abort"
+    }
+
+    fn execute(&self, _args: Vec<String>) -> Result<Response, Response> {
+        std::thread::Builder::new()
+            .name("abort".to_owned())
+            .spawn(|| {
+                std::thread::sleep(std::time::Duration::from_millis(50));
+                crate::cleanup::run_hooks();
+                std::process::abort();
+            })
+            .unwrap();
+        Ok("Abort successful.".into())
+    }
+}
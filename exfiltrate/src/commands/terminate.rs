@@ -1,10 +1,54 @@
 #![cfg(not(target_arch = "wasm32"))]
 use exfiltrate_internal::command::{Command, Response};
 
+/// Symbolic exit code names from the sysexits.h family accepted by
+/// [`parse_exit_code`], alongside their numeric value.
+const SYSEXITS: &[(&str, u8)] = &[
+    ("usage", 64),
+    ("dataerr", 65),
+    ("noinput", 66),
+    ("nouser", 67),
+    ("nohost", 68),
+    ("unavailable", 69),
+    ("software", 70),
+    ("oserr", 71),
+    ("osfile", 72),
+    ("cantcreat", 73),
+    ("ioerr", 74),
+    ("tempfail", 75),
+    ("protocol", 76),
+    ("noperm", 77),
+    ("config", 78),
+];
+
+/// Parses the optional exit-code argument to [`Terminate::execute`]: a
+/// decimal integer in `0..=255`, or one of the symbolic names in
+/// [`SYSEXITS`]. Defaults to `70` (EX_SOFTWARE) if no argument was given.
+fn parse_exit_code(args: &[String]) -> Result<u8, Response> {
+    let Some(arg) = args.first() else {
+        return Ok(70 /* EX_SOFTWARE */);
+    };
+    if let Ok(code) = arg.parse::<u8>() {
+        return Ok(code);
+    }
+    if let Some((_, code)) = SYSEXITS.iter().find(|(name, _)| *name == arg) {
+        return Ok(*code);
+    }
+    let names = SYSEXITS
+        .iter()
+        .map(|(name, code)| format!("{name}={code}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    Err(format!(
+        "Invalid exit code {arg:?}. Expected a decimal integer in 0..=255, or one of: {names}"
+    )
+    .into())
+}
+
 /// The `terminate` command.
 ///
-/// Exits the application with status code 70 (EX_SOFTWARE).
-/// Only available on native targets.
+/// Exits the application with status code 70 (EX_SOFTWARE), or another
+/// code named by its argument. Only available on native targets.
 pub struct Terminate;
 
 impl Command for Terminate {
@@ -26,15 +70,31 @@ A common debugging workflow is:
 
 However step 3 may be difficult in a sandbox, or require PID tracking, etc.
 
-This command will remotely exit the program we are debugging, with exit code 70 (EX_SOFTWARE)."
+This command will remotely exit the program we are debugging, with exit code 70 (EX_SOFTWARE) by default.
+
+Usage: exfiltrate terminate [CODE] [--tree]
+
+CODE may be a decimal integer in 0..=255, or a sysexits.h-style name such as
+usage, dataerr, noinput, software, or tempfail.
+
+--tree additionally tears down every child process the debugged program
+spawned, instead of leaving them orphaned."
     }
 
-    fn execute(&self, _args: Vec<String>) -> Result<Response, Response> {
+    fn execute(&self, args: Vec<String>) -> Result<Response, Response> {
+        let tree = args.iter().any(|arg| arg == "--tree");
+        let rest: Vec<String> = args.into_iter().filter(|arg| arg != "--tree").collect();
+        let code = parse_exit_code(&rest)?;
         std::thread::Builder::new()
             .name("terminate".to_owned())
-            .spawn(|| {
+            .spawn(move || {
                 std::thread::sleep(std::time::Duration::from_millis(50));
-                std::process::exit(70 /* EX_SOFTWARE */);
+                crate::cleanup::run_hooks();
+                if tree {
+                    crate::process_tree::kill_tree(code as i32);
+                } else {
+                    std::process::exit(code as i32);
+                }
             })
             .unwrap();
         Ok("Termination successful.".into())
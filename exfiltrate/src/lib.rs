@@ -124,17 +124,22 @@
 //! For detailed examples of all response types, run `exfiltrate help custom_commands` in the CLI.
 
 #[cfg(feature = "logwise")]
-mod logwise;
+pub mod logwise;
 
 /// Re-export of the [`rgb`](https://docs.rs/rgb) crate for image pixel types.
 ///
 /// Use [`rgb::RGBA8`] when constructing [`ImageInfo`](command::ImageInfo) responses.
 pub use rgb;
 
+mod cleanup;
 mod commands;
+pub mod middleware;
+#[cfg(not(target_arch = "wasm32"))]
+mod process_tree;
 mod wire;
 
 use crate::commands::register_commands;
+use crate::middleware::register_middleware;
 use exfiltrate_internal::command::Command;
 
 /// Initializes the exfiltrate debugging server.
@@ -156,7 +161,10 @@ pub fn begin() {
     {
         logwise::begin_log_capture();
     }
+    #[cfg(not(target_arch = "wasm32"))]
+    process_tree::init();
     register_commands();
+    register_middleware();
     use std::ops::Deref;
     _ = crate::wire::server::SERVER.deref();
 }
@@ -189,6 +197,60 @@ pub fn add_command<C: Command>(command: C) {
         .push(Box::new(command));
 }
 
+/// Registers a middleware at the end of the command execution chain.
+///
+/// Middlewares run in registration order, outermost first: the first
+/// registered middleware is the first to see the invocation and the last to
+/// see the response. Built-in middlewares (timing, audit log, retry, panic
+/// guard) are
+/// registered first by `begin()`, so custom middlewares added here run
+/// inside them.
+///
+/// # Example
+///
+/// ```rust
+/// use exfiltrate::middleware::{CommandInvocation, CommandResponse, Middleware};
+///
+/// struct LogNames;
+/// impl Middleware for LogNames {
+///     fn handle(
+///         &self,
+///         invocation: &CommandInvocation,
+///         next: &dyn Fn(&CommandInvocation) -> CommandResponse,
+///     ) -> CommandResponse {
+///         eprintln!("about to run {}", invocation.name);
+///         next(invocation)
+///     }
+/// }
+///
+/// exfiltrate::add_middleware(LogNames);
+/// ```
+pub fn add_middleware<M: middleware::Middleware>(middleware: M) {
+    crate::middleware::MIDDLEWARE
+        .lock_sync_write()
+        .push(Box::new(middleware));
+}
+
+/// Registers `hook` to run once, just before `terminate` or `abort` ends
+/// the process.
+///
+/// Use this to release resources (temp files, sockets, spawned children)
+/// deterministically instead of leaving them for the OS to clean up after
+/// an abrupt exit. Hooks run in registration order on the delayed
+/// termination thread, after the command's `Response` has had a chance to
+/// flush back to the client but before the process actually exits.
+///
+/// # Example
+///
+/// ```rust
+/// exfiltrate::register_on_terminate(|| {
+///     eprintln!("cleaning up before exit");
+/// });
+/// ```
+pub fn register_on_terminate<F: FnOnce() + Send + 'static>(hook: F) {
+    crate::cleanup::register(hook);
+}
+
 /// Re-exports of types needed to implement custom commands.
 pub mod command {
     pub use exfiltrate_internal::command::{Command, FileInfo, ImageInfo, Response};
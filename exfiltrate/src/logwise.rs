@@ -2,24 +2,170 @@
 //!
 //! This module provides integration with the `logwise` logging framework, allowing
 //! exfiltrate to capture and retrieve log records from the running application.
+//!
+//! Captured records are held in a bounded ring buffer (see [`set_log_capacity`])
+//! and dropped before they're even stored if they don't pass a directive-based
+//! level/target filter (see [`set_log_filter`]), so a long-running program
+//! doesn't grow its log buffer without bound.
 
 use exfiltrate_internal::command::{Command, FileInfo, Response};
 use logwise::LogRecord;
+use std::collections::VecDeque;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, LazyLock};
 use wasm_safe_mutex::Mutex;
+use wasm_safe_mutex::rwlock::RwLock;
+
+/// A minimum severity a log record must reach to pass a filter.
+///
+/// Ordered by increasing verbosity, matching the conventional
+/// `error < warn < info < debug < trace` levels used by the
+/// `logwise::{error,warn,info,debug}_sync!` macros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LevelFilter {
+    /// Drop every record, regardless of level.
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LevelFilter {
+    /// Parses a level name (`"off"`, `"error"`, `"warn"`/`"warning"`, `"info"`,
+    /// `"debug"`, or `"trace"`), case-insensitively.
+    fn parse(s: &str) -> Option<LevelFilter> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(LevelFilter::Off),
+            "error" => Some(LevelFilter::Error),
+            "warn" | "warning" => Some(LevelFilter::Warn),
+            "info" => Some(LevelFilter::Info),
+            "debug" => Some(LevelFilter::Debug),
+            "trace" => Some(LevelFilter::Trace),
+            _ => None,
+        }
+    }
+
+    /// The level of `record`, defaulting to [`LevelFilter::Info`] if the
+    /// record's level doesn't match one of the recognized names.
+    fn of(record: &LogRecord) -> LevelFilter {
+        LevelFilter::parse(&record.level().to_string()).unwrap_or(LevelFilter::Info)
+    }
+}
+
+/// A directive-based level/target filter, in the style of `RUST_LOG`.
+///
+/// A spec is a comma-separated list of rules: a bare level (`"warn"`) sets
+/// the default level, and a `target=level` rule (`"exfiltrate::wire=debug"`)
+/// overrides the default for any record whose target starts with that
+/// prefix. The most specific (longest) matching prefix wins.
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    default: LevelFilter,
+    directives: Vec<(String, LevelFilter)>,
+}
+
+impl LogFilter {
+    /// Parses a directive spec. Unrecognized rules are ignored; a spec with
+    /// no bare level defaults to [`LevelFilter::Info`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use exfiltrate::logwise::LogFilter;
+    ///
+    /// // Show warnings and errors everywhere, but debug logs from `noisy`.
+    /// let _filter = LogFilter::parse("warn,noisy=debug");
+    /// ```
+    pub fn parse(spec: &str) -> LogFilter {
+        let mut default = LevelFilter::Info;
+        let mut directives = Vec::new();
+        for rule in spec.split(',').map(str::trim).filter(|r| !r.is_empty()) {
+            match rule.split_once('=') {
+                Some((target, level)) => {
+                    if let Some(level) = LevelFilter::parse(level) {
+                        directives.push((target.to_string(), level));
+                    }
+                }
+                None => {
+                    if let Some(level) = LevelFilter::parse(rule) {
+                        default = level;
+                    }
+                }
+            }
+        }
+        // The most specific (longest) target prefix should win ties.
+        directives.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+        LogFilter { default, directives }
+    }
+
+    /// Whether `record` passes this filter.
+    fn allows(&self, record: &LogRecord) -> bool {
+        let target = record.target().to_string();
+        let threshold = self
+            .directives
+            .iter()
+            .find(|(prefix, _)| target.starts_with(prefix.as_str()))
+            .map(|(_, level)| *level)
+            .unwrap_or(self.default);
+        LevelFilter::of(record) <= threshold
+    }
+}
+
+/// Default capacity of the in-memory log ring buffer.
+const DEFAULT_LOG_CAPACITY: usize = 10_000;
+
+static LOG_CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_LOG_CAPACITY);
+
+/// Sets the maximum number of log records retained in memory.
+///
+/// Once the buffer reaches this size, the oldest record is dropped each time
+/// a new one is captured. Can be called at any time, including after
+/// [`begin_log_capture`].
+pub fn set_log_capacity(capacity: usize) {
+    LOG_CAPACITY.store(capacity.max(1), Ordering::Release);
+}
+
+static FILTER: LazyLock<RwLock<LogFilter>> = LazyLock::new(|| RwLock::new(LogFilter::parse("info")));
+
+/// Reconfigures the directive-based level/target filter used to decide which
+/// log records are retained. See [`LogFilter::parse`] for the directive
+/// syntax.
+///
+/// Can be called at any time, including after [`begin_log_capture`]; it only
+/// affects records captured from this point on.
+pub fn set_log_filter(spec: &str) {
+    *FILTER.lock_sync_write() = LogFilter::parse(spec);
+}
 
 #[derive(Debug)]
 struct ExfiltrateLogger {
-    records: Mutex<Vec<LogRecord>>,
+    records: Mutex<VecDeque<LogRecord>>,
 }
 
 impl ExfiltrateLogger {
     const fn new() -> ExfiltrateLogger {
         ExfiltrateLogger {
-            records: Mutex::new(Vec::new()),
+            records: Mutex::new(VecDeque::new()),
         }
     }
+
+    /// Filters and stores `record`, evicting the oldest record if the ring
+    /// buffer is over capacity.
+    fn push(&self, record: LogRecord) {
+        if !FILTER.lock_sync_read().allows(&record) {
+            return;
+        }
+        self.records.with_mut_sync(|records| {
+            records.push_back(record);
+            let capacity = LOG_CAPACITY.load(Ordering::Acquire);
+            while records.len() > capacity {
+                records.pop_front();
+            }
+        });
+    }
 }
 
 static LOGGER: LazyLock<Arc<ExfiltrateLogger>> =
@@ -27,14 +173,14 @@ static LOGGER: LazyLock<Arc<ExfiltrateLogger>> =
 
 impl logwise::Logger for ExfiltrateLogger {
     fn finish_log_record(&self, record: LogRecord) {
-        self.records.with_mut_sync(|e| e.push(record));
+        self.push(record);
     }
 
     fn finish_log_record_async<'s>(
         &'s self,
         record: LogRecord,
     ) -> Pin<Box<dyn Future<Output = ()> + Send + 's>> {
-        Box::pin(self.records.with_mut_async(|e| e.push(record)))
+        Box::pin(async move { self.push(record) })
     }
 
     fn prepare_to_die(&self) {}
@@ -42,8 +188,11 @@ impl logwise::Logger for ExfiltrateLogger {
 
 /// Starts capturing logs from the `logwise` crate.
 ///
-/// Adds a global logger that stores log records in memory.
-/// These logs can then be retrieved via the `logwise_logs` command.
+/// Adds a global logger that stores log records in a bounded, filterable
+/// in-memory ring buffer (default capacity: 10,000 records; default filter:
+/// `info` and above). These logs can then be retrieved via the
+/// `logwise_logs` command. Use [`set_log_filter`] and [`set_log_capacity`]
+/// to change the defaults, before or after calling this function.
 pub fn begin_log_capture() {
     logwise::add_global_logger(LOGGER.clone());
     crate::add_command(LogwiseCapture);
@@ -51,7 +200,8 @@ pub fn begin_log_capture() {
 
 /// The `logwise_logs` command.
 ///
-/// Retrieves all captured log records.
+/// Retrieves captured log records, optionally narrowed by level, target
+/// prefix, a text search, and/or a tail count.
 pub struct LogwiseCapture;
 
 impl Command for LogwiseCapture {
@@ -69,25 +219,95 @@ impl Command for LogwiseCapture {
 
 In some cases, logs may be difficult to access.  For example we may be debugging WASM code, running in a browser, or a remote computer.
 
-Log files may be very large.  Consider examining only part of them with your tools, or searching them with grep.
+Log files may be very large.  Consider examining only part of them with your tools, or searching them with grep, or use the arguments below to narrow the results before they're returned.
 
 Often, on wasm, only the main thread's logs are printed.  So if you are reading stdout, you are missing many logs that are being written by other threads.  So the output from other sources may be HIGHLY misleading.
 
 
 Using this command ensures you get all the logwise logs from all threads, that are prior to `exfiltrate::begin`.  (Logs prior to this call are not captured; so users are instructed to make this call early in their program).
 
+Note that the in-memory log buffer is itself bounded and pre-filtered; see `exfiltrate::logwise::set_log_capacity` and `exfiltrate::logwise::set_log_filter` for how to widen what's retained in the first place.
+
+Usage: logwise_logs [--level LEVEL] [--target PREFIX] [--contains TEXT] [--tail N]
+
+    --level LEVEL     Only show records at this severity or more severe (off, error, warn, info, debug, trace).
+    --target PREFIX   Only show records whose target starts with PREFIX.
+    --contains TEXT   Only show records whose rendered line contains TEXT.
+    --tail N          Only show the last N matching records.
+
 For more information on using logwise, try building the latest documentation for it.  Alternatively, some resources are
         * https://sealedabstract.com/code/logwise
         * https://docs.rs/logwise/latest/logwise/
 "
     }
 
-    fn execute(&self, _args: Vec<String>) -> Result<Response, Response> {
+    fn execute(&self, args: Vec<String>) -> Result<Response, Response> {
+        let mut level = None;
+        let mut target_prefix = None;
+        let mut contains = None;
+        let mut tail = None;
+
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--level" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Response::from("Missing value for --level".to_string()))?;
+                    level = Some(
+                        LevelFilter::parse(&value)
+                            .ok_or_else(|| Response::from(format!("Unknown level: {}", value)))?,
+                    );
+                }
+                "--target" => {
+                    target_prefix = Some(args.next().ok_or_else(|| {
+                        Response::from("Missing value for --target".to_string())
+                    })?);
+                }
+                "--contains" => {
+                    contains = Some(args.next().ok_or_else(|| {
+                        Response::from("Missing value for --contains".to_string())
+                    })?);
+                }
+                "--tail" => {
+                    let value = args
+                        .next()
+                        .ok_or_else(|| Response::from("Missing value for --tail".to_string()))?;
+                    tail = Some(
+                        value
+                            .parse::<usize>()
+                            .map_err(|_| Response::from(format!("Invalid --tail value: {}", value)))?,
+                    );
+                }
+                other => return Err(format!("Unknown argument: {}", other).into()),
+            }
+        }
+
         let logger = &LOGGER;
-        let clone_all_logs = logger.records.with_sync(|logs| logs.clone());
+        let mut records = logger
+            .records
+            .with_sync(|logs| logs.iter().cloned().collect::<Vec<_>>());
+
+        if let Some(level) = level {
+            records.retain(|record| LevelFilter::of(record) <= level);
+        }
+        if let Some(prefix) = &target_prefix {
+            records.retain(|record| record.target().to_string().starts_with(prefix.as_str()));
+        }
+
+        let mut lines: Vec<String> = records.iter().map(|record| record.to_string()).collect();
+        if let Some(text) = &contains {
+            lines.retain(|line| line.contains(text.as_str()));
+        }
+        if let Some(n) = tail {
+            if lines.len() > n {
+                lines.drain(0..lines.len() - n);
+            }
+        }
+
         let mut str = String::new();
-        for log in clone_all_logs {
-            str.push_str(&log.to_string());
+        for line in lines {
+            str.push_str(&line);
             str.push('\n');
         }
         let response = Response::Files(vec![FileInfo::new(
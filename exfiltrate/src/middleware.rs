@@ -0,0 +1,261 @@
+//! Middleware chain wrapping command execution.
+//!
+//! Every invocation dispatched through `do_command` (reached from both the
+//! native TCP server and [`crate::wire::server::Server::dispatch_local`])
+//! runs through the registered middleware chain before reaching the matching
+//! [`Command`](exfiltrate_internal::command::Command). Middlewares are
+//! registered in order with [`add_middleware`] and wrap the invocation much
+//! like a `reqwest`-style middleware: each one receives the invocation and a
+//! `next` closure representing the rest of the chain, and decides
+//! whether, how many times, and with what side effects to call it.
+
+use exfiltrate_internal::command::Response;
+use std::sync::LazyLock;
+use std::time::Duration;
+use wasm_safe_mutex::rwlock::RwLock;
+
+/// Re-export of [`exfiltrate_internal::rpc::CommandInvocation`] for use by
+/// middleware implementations.
+pub use exfiltrate_internal::rpc::CommandInvocation;
+/// Re-export of [`exfiltrate_internal::rpc::CommandResponse`] for use by
+/// middleware implementations.
+pub use exfiltrate_internal::rpc::CommandResponse;
+
+/// A layer that wraps command execution.
+///
+/// Implementations can run code before and after calling `next`, skip
+/// calling it entirely (e.g. to short-circuit with a cached response), or
+/// call it more than once (e.g. to retry a transient failure).
+pub trait Middleware: 'static + Send + Sync {
+    /// Handles `invocation`, calling `next` to continue the chain.
+    fn handle(
+        &self,
+        invocation: &CommandInvocation,
+        next: &dyn Fn(&CommandInvocation) -> CommandResponse,
+    ) -> CommandResponse;
+}
+
+/// The global, ordered chain of registered middlewares.
+///
+/// This list is populated by `register_middleware` and
+/// `exfiltrate::add_middleware`.
+pub(crate) static MIDDLEWARE: RwLock<Vec<Box<dyn Middleware>>> = RwLock::new(vec![]);
+
+/// Registers the built-in middlewares (timing, audit log, retry, panic
+/// guard).
+///
+/// This is called automatically by `exfiltrate::begin()`. Order matters:
+/// middlewares run outermost-first, so [`TimingMiddleware`] and
+/// [`AuditLogMiddleware`] both measure the full set of retry attempts as a
+/// single invocation, and [`PanicGuardMiddleware`] sits innermost so it
+/// catches a panic from any individual attempt [`RetryMiddleware`] makes.
+pub(crate) fn register_middleware() {
+    let mut lock = MIDDLEWARE.lock_sync_write();
+    lock.push(Box::new(TimingMiddleware));
+    lock.push(Box::new(AuditLogMiddleware));
+    lock.push(Box::new(RetryMiddleware::new(3)));
+    lock.push(Box::new(PanicGuardMiddleware));
+}
+
+/// Where [`AuditLogMiddleware`] writes its audit trail.
+///
+/// Defaults to [`AuditSink::Stderr`]; redirect it with [`set_audit_sink`].
+#[derive(Debug, Clone)]
+pub enum AuditSink {
+    /// Write audit records to stderr.
+    Stderr,
+    /// Append audit records to the file at this path, opening (and
+    /// creating) it fresh on every record so a rotated or deleted log file
+    /// doesn't wedge logging.
+    File(std::path::PathBuf),
+}
+
+/// The sink [`AuditLogMiddleware`] currently writes to.
+static AUDIT_SINK: LazyLock<RwLock<AuditSink>> = LazyLock::new(|| RwLock::new(AuditSink::Stderr));
+
+/// Configures where [`AuditLogMiddleware`] writes its audit trail.
+pub fn set_audit_sink(sink: AuditSink) {
+    *AUDIT_SINK.lock_sync_write() = sink;
+}
+
+/// Records a blackbox-style audit trail of every executed command: a
+/// `command` line when an invocation starts (with its name and args) and a
+/// matching `commandfinish` line when it completes (with elapsed time and
+/// outcome), so a session can be reconstructed after the debugged program
+/// -- possibly killed by `terminate` -- is gone.
+///
+/// Writes compact `key=value` lines to the sink configured with
+/// [`set_audit_sink`] (stderr by default). Registered by default; see
+/// [`register_middleware`].
+pub struct AuditLogMiddleware;
+
+impl Middleware for AuditLogMiddleware {
+    fn handle(
+        &self,
+        invocation: &CommandInvocation,
+        next: &dyn Fn(&CommandInvocation) -> CommandResponse,
+    ) -> CommandResponse {
+        write_audit_record(&format!(
+            "event=command name={:?} args={:?}",
+            invocation.name, invocation.args
+        ));
+        let start = std::time::Instant::now();
+        let response = next(invocation);
+        write_audit_record(&format!(
+            "event=commandfinish name={:?} elapsed={:?} outcome={}",
+            invocation.name,
+            start.elapsed(),
+            if response.success { "ok" } else { "err" }
+        ));
+        response
+    }
+}
+
+/// Appends `line` to the configured [`AuditSink`], logging (rather than
+/// panicking on) a sink that can't be written to -- a blocked audit log
+/// shouldn't also block command execution.
+fn write_audit_record(line: &str) {
+    match &*AUDIT_SINK.lock_sync_read() {
+        AuditSink::Stderr => eprintln!("audit: {line}"),
+        AuditSink::File(path) => {
+            use std::io::Write;
+            match std::fs::OpenOptions::new().create(true).append(true).open(path) {
+                Ok(mut file) => {
+                    if let Err(e) = writeln!(file, "{line}") {
+                        eprintln!("audit: failed to write to {}: {e}", path.display());
+                    }
+                }
+                Err(e) => eprintln!("audit: failed to open {}: {e}", path.display()),
+            }
+        }
+    }
+}
+
+/// Runs `invocation` through the full registered middleware chain, finally
+/// calling `terminal` if every middleware calls `next`.
+pub(crate) fn run_chain(
+    invocation: CommandInvocation,
+    terminal: impl Fn(&CommandInvocation) -> CommandResponse,
+) -> CommandResponse {
+    fn run(
+        chain: &[Box<dyn Middleware>],
+        invocation: &CommandInvocation,
+        terminal: &dyn Fn(&CommandInvocation) -> CommandResponse,
+    ) -> CommandResponse {
+        match chain.split_first() {
+            Some((first, rest)) => {
+                let next = move |inv: &CommandInvocation| run(rest, inv, terminal);
+                first.handle(invocation, &next)
+            }
+            None => terminal(invocation),
+        }
+    }
+    let chain = MIDDLEWARE.lock_sync_read();
+    run(&chain, &invocation, &terminal)
+}
+
+/// Logs how long each invocation took to `stderr`.
+///
+/// Registered by default; see [`register_middleware`].
+pub struct TimingMiddleware;
+
+impl Middleware for TimingMiddleware {
+    fn handle(
+        &self,
+        invocation: &CommandInvocation,
+        next: &dyn Fn(&CommandInvocation) -> CommandResponse,
+    ) -> CommandResponse {
+        let start = std::time::Instant::now();
+        let response = next(invocation);
+        eprintln!(
+            "middleware: {} took {:?}",
+            invocation.name,
+            start.elapsed()
+        );
+        response
+    }
+}
+
+/// Retries a failed invocation with exponential backoff, up to a configured
+/// number of attempts.
+///
+/// "Transient" is approximated as [`CommandResponse::success`] being
+/// `false`; there's no separate error classification in [`Response`], so a
+/// command that deterministically fails will simply be retried and fail the
+/// same way each time, at the cost of the added latency.
+///
+/// Registered by default with `max_retries = 3`; see [`register_middleware`].
+pub struct RetryMiddleware {
+    max_retries: u32,
+}
+
+/// The delay before the first retry; each subsequent retry doubles it.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(10);
+
+impl RetryMiddleware {
+    /// Creates a middleware that retries a failing invocation up to
+    /// `max_retries` additional times (so `max_retries = 3` means up to 4
+    /// attempts total).
+    pub fn new(max_retries: u32) -> Self {
+        RetryMiddleware { max_retries }
+    }
+}
+
+impl Middleware for RetryMiddleware {
+    fn handle(
+        &self,
+        invocation: &CommandInvocation,
+        next: &dyn Fn(&CommandInvocation) -> CommandResponse,
+    ) -> CommandResponse {
+        let mut attempt = 0;
+        loop {
+            let response = next(invocation);
+            if response.success || attempt >= self.max_retries {
+                return response;
+            }
+            let delay = RETRY_BASE_DELAY * 2u32.pow(attempt);
+            eprintln!(
+                "middleware: {} failed (attempt {}), retrying in {:?}",
+                invocation.name,
+                attempt + 1,
+                delay
+            );
+            std::thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+}
+
+/// Catches a panic from the rest of the chain and turns it into a failed
+/// [`CommandResponse`] instead of unwinding into the connection handler
+/// thread (which would otherwise tear it down and silently drop the reply).
+///
+/// Registered by default, innermost in the chain; see
+/// [`register_middleware`].
+pub struct PanicGuardMiddleware;
+
+impl Middleware for PanicGuardMiddleware {
+    fn handle(
+        &self,
+        invocation: &CommandInvocation,
+        next: &dyn Fn(&CommandInvocation) -> CommandResponse,
+    ) -> CommandResponse {
+        let reply_id = invocation.reply_id;
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| next(invocation))) {
+            Ok(response) => response,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "command panicked".to_string());
+                eprintln!("middleware: {} panicked: {}", invocation.name, message);
+                CommandResponse::new(
+                    false,
+                    Response::String(format!("command panicked: {}", message)),
+                    reply_id,
+                )
+            }
+        }
+    }
+}
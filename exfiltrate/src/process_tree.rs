@@ -0,0 +1,272 @@
+#![cfg(not(target_arch = "wasm32"))]
+//! Whole-process-tree termination for `terminate --tree`.
+//!
+//! `terminate` on its own only exits the current process; any child
+//! processes the debugged program spawned survive it. [`init`] places the
+//! process somewhere an OS can tear down its whole tree in one call -- its
+//! own process group on Unix (so [`kill_tree`] can `killpg` it), or a Job
+//! Object with `JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE` on Windows (so
+//! [`kill_tree`] can terminate every other process in the job) -- and
+//! [`kill_tree`] tears it down.
+
+/// Puts the process in its own process group (Unix) or assigns it to a
+/// kill-on-close Job Object (Windows), so [`kill_tree`] can later tear down
+/// every descendant at once. Called once by `exfiltrate::begin()`.
+pub(crate) fn init() {
+    #[cfg(unix)]
+    unix::init();
+    #[cfg(windows)]
+    windows::init();
+}
+
+/// Terminates every other process in the tree [`init`] set up and exits
+/// *this* process with `code`. Both platform implementations are careful to
+/// leave this process out of the OS-level teardown -- `terminate --tree
+/// 65` should still exit `65`, not whatever status killing this process
+/// out from under itself would report -- so `std::process::exit(code)`
+/// below is always this process's one true exit path. This also degrades
+/// gracefully if the tree-kill itself fails (e.g. `init` never ran, or
+/// permissions were denied): `terminate --tree` still exits with `code`,
+/// just without having reaped any children.
+pub(crate) fn kill_tree(code: i32) -> ! {
+    #[cfg(unix)]
+    unix::kill_tree();
+    #[cfg(windows)]
+    windows::kill_tree();
+    std::process::exit(code);
+}
+
+#[cfg(unix)]
+mod unix {
+    /// Moves this process into a new process group of its own (`setpgid(0,
+    /// 0)`), so a later `killpg` only reaches it and the children it spawns
+    /// -- not unrelated processes sharing the original group -- and blocks
+    /// `SIGTERM` for this (the main) thread.
+    ///
+    /// A per-thread `pthread_sigmask` can't exclude *this process* from a
+    /// later `killpg(0, SIGTERM)` -- it only protects whichever thread calls
+    /// it, and this library spawns plenty of others (the wire-server accept
+    /// thread, per-connection handler threads) that would still be open to a
+    /// default-disposition `SIGTERM` reaching them instead, racing
+    /// `process_tree::kill_tree`'s `std::process::exit(code)` to end the
+    /// process first. Blocking it here, before any of those threads exist,
+    /// relies on POSIX's rule that a new thread inherits its creator's
+    /// signal mask: as long as `init` runs before anything else spawns a
+    /// thread (see `exfiltrate::begin`), every thread in the process ends up
+    /// with `SIGTERM` blocked, so [`kill_tree`]'s `killpg` can't terminate
+    /// this process out from under itself no matter which of its threads the
+    /// kernel would otherwise have delivered the signal to.
+    pub(super) fn init() {
+        let result = unsafe { libc::setpgid(0, 0) };
+        if result != 0 {
+            eprintln!(
+                "process_tree: setpgid failed: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+        unsafe {
+            let mut block_set: libc::sigset_t = std::mem::zeroed();
+            libc::sigemptyset(&mut block_set);
+            libc::sigaddset(&mut block_set, libc::SIGTERM);
+            if libc::pthread_sigmask(libc::SIG_BLOCK, &block_set, std::ptr::null_mut()) != 0 {
+                eprintln!(
+                    "process_tree: pthread_sigmask failed: {}",
+                    std::io::Error::last_os_error()
+                );
+            }
+        }
+    }
+
+    /// Sends `SIGTERM` to every process in this process's group -- which,
+    /// since [`init`] put this process in a group of its own, is every
+    /// child it (or a descendant) spawned, *plus this process itself*. Every
+    /// thread in this process has had `SIGTERM` blocked since [`init`] ran
+    /// (see its doc comment), so the self-directed copy stays pending and is
+    /// simply dropped when the process exits, rather than terminating the
+    /// process out from under `process_tree::kill_tree`'s own
+    /// `std::process::exit(code)`.
+    pub(super) fn kill_tree() {
+        if unsafe { libc::killpg(0, libc::SIGTERM) } != 0 {
+            eprintln!(
+                "process_tree: killpg failed: {}",
+                std::io::Error::last_os_error()
+            );
+        }
+    }
+}
+
+#[cfg(windows)]
+mod windows {
+    use std::sync::OnceLock;
+
+    /// The Job Object handle [`init`] creates, stored as a raw `isize` (a
+    /// `HANDLE` isn't `Sync`) since nothing here dereferences it as a
+    /// pointer -- it's only ever passed back to the Win32 API that issued
+    /// it.
+    static JOB_HANDLE: OnceLock<isize> = OnceLock::new();
+
+    const JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE: u32 = 0x2000;
+
+    #[repr(C)]
+    struct JobObjectBasicLimitInformation {
+        per_process_user_time_limit: i64,
+        per_job_user_time_limit: i64,
+        limit_flags: u32,
+        minimum_working_set_size: usize,
+        maximum_working_set_size: usize,
+        active_process_limit: u32,
+        affinity: usize,
+        priority_class: u32,
+        scheduling_class: u32,
+    }
+
+    #[repr(C)]
+    struct JobObjectExtendedLimitInformation {
+        basic_limit_information: JobObjectBasicLimitInformation,
+        io_info: [u64; 6],
+        process_memory_limit: usize,
+        job_memory_limit: usize,
+        peak_process_memory_used: usize,
+        peak_job_memory_used: usize,
+    }
+
+    const JOB_OBJECT_EXTENDED_LIMIT_INFORMATION_CLASS: u32 = 9;
+    const JOB_OBJECT_BASIC_PROCESS_ID_LIST_CLASS: u32 = 3;
+    const PROCESS_TERMINATE: u32 = 0x0001;
+
+    /// Upper bound on how many process IDs [`kill_tree`] reads back from the
+    /// job in one `QueryInformationJobObject` call. A debugging session
+    /// spawning more descendants than this is vanishingly unlikely; any
+    /// beyond it are simply not reaped.
+    const MAX_TRACKED_PROCESSES: usize = 1024;
+
+    #[repr(C)]
+    struct JobObjectBasicProcessIdList {
+        number_of_assigned_processes: u32,
+        number_of_process_ids_in_list: u32,
+        process_id_list: [usize; MAX_TRACKED_PROCESSES],
+    }
+
+    #[link(name = "kernel32")]
+    unsafe extern "system" {
+        fn CreateJobObjectW(attrs: *const core::ffi::c_void, name: *const u16) -> isize;
+        fn AssignProcessToJobObject(job: isize, process: isize) -> i32;
+        fn SetInformationJobObject(
+            job: isize,
+            class: u32,
+            info: *const core::ffi::c_void,
+            len: u32,
+        ) -> i32;
+        fn QueryInformationJobObject(
+            job: isize,
+            class: u32,
+            info: *mut core::ffi::c_void,
+            len: u32,
+            return_len: *mut u32,
+        ) -> i32;
+        fn OpenProcess(access: u32, inherit_handle: i32, process_id: u32) -> isize;
+        fn TerminateProcess(process: isize, exit_code: u32) -> i32;
+        fn GetCurrentProcess() -> isize;
+        fn GetCurrentProcessId() -> u32;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+
+    /// Creates a Job Object with `JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE` and
+    /// assigns this process to it, so every process later spawned as (or
+    /// by) a descendant of this one is torn down by [`kill_tree`].
+    pub(super) fn init() {
+        unsafe {
+            let job = CreateJobObjectW(std::ptr::null(), std::ptr::null());
+            if job == 0 {
+                eprintln!(
+                    "process_tree: CreateJobObjectW failed: {}",
+                    std::io::Error::last_os_error()
+                );
+                return;
+            }
+            let mut info: JobObjectExtendedLimitInformation = std::mem::zeroed();
+            info.basic_limit_information.limit_flags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+            let ok = SetInformationJobObject(
+                job,
+                JOB_OBJECT_EXTENDED_LIMIT_INFORMATION_CLASS,
+                &info as *const _ as *const core::ffi::c_void,
+                std::mem::size_of::<JobObjectExtendedLimitInformation>() as u32,
+            );
+            if ok == 0 {
+                eprintln!(
+                    "process_tree: SetInformationJobObject failed: {}",
+                    std::io::Error::last_os_error()
+                );
+                CloseHandle(job);
+                return;
+            }
+            if AssignProcessToJobObject(job, GetCurrentProcess()) == 0 {
+                eprintln!(
+                    "process_tree: AssignProcessToJobObject failed: {}",
+                    std::io::Error::last_os_error()
+                );
+                CloseHandle(job);
+                return;
+            }
+            let _ = JOB_HANDLE.set(job);
+        }
+    }
+
+    /// Terminates every *other* process assigned to the Job Object [`init`]
+    /// created.
+    ///
+    /// This deliberately doesn't call `TerminateJobObject`: it kills every
+    /// process in the job, including this one, with a hardcoded exit code,
+    /// which would both end this process before
+    /// `process_tree::kill_tree`'s `std::process::exit(code)` runs and
+    /// discard the caller's requested exit code. Instead this walks the
+    /// job's process ID list and terminates each one except
+    /// `GetCurrentProcessId()`, leaving this process's own exit under its
+    /// caller's control.
+    pub(super) fn kill_tree() {
+        let Some(&job) = JOB_HANDLE.get() else {
+            eprintln!("process_tree: no job object to terminate (init never ran or failed)");
+            return;
+        };
+        unsafe {
+            let mut list: JobObjectBasicProcessIdList = std::mem::zeroed();
+            let ok = QueryInformationJobObject(
+                job,
+                JOB_OBJECT_BASIC_PROCESS_ID_LIST_CLASS,
+                &mut list as *mut _ as *mut core::ffi::c_void,
+                std::mem::size_of::<JobObjectBasicProcessIdList>() as u32,
+                std::ptr::null_mut(),
+            );
+            if ok == 0 {
+                eprintln!(
+                    "process_tree: QueryInformationJobObject failed: {}",
+                    std::io::Error::last_os_error()
+                );
+                return;
+            }
+            let current_pid = GetCurrentProcessId() as usize;
+            let count =
+                (list.number_of_process_ids_in_list as usize).min(MAX_TRACKED_PROCESSES);
+            for &pid in &list.process_id_list[..count] {
+                if pid == current_pid {
+                    continue;
+                }
+                let handle = OpenProcess(PROCESS_TERMINATE, 0, pid as u32);
+                if handle == 0 {
+                    eprintln!(
+                        "process_tree: OpenProcess({pid}) failed: {}",
+                        std::io::Error::last_os_error()
+                    );
+                    continue;
+                }
+                if TerminateProcess(handle, 1) == 0 {
+                    eprintln!(
+                        "process_tree: TerminateProcess({pid}) failed: {}",
+                        std::io::Error::last_os_error()
+                    );
+                }
+                CloseHandle(handle);
+            }
+        }
+    }
+}
@@ -2,47 +2,360 @@
 mod wasm32;
 
 use crate::commands::COMMANDS;
+use exfiltrate_internal::command::Response;
+use exfiltrate_internal::cookie::{self, Cookie};
+use exfiltrate_internal::rendezvous::{self, Rendezvous};
 use exfiltrate_internal::rpc::{CommandInvocation, CommandResponse, RPC};
-use exfiltrate_internal::wire::{ADDR, BACKOFF_DURATION, InFlightMessage, send_socket_rpc};
-use std::net::{TcpListener, TcpStream};
-use std::sync::LazyLock;
+use exfiltrate_internal::secure::{SecureChannel, SecureInFlightMessage, Token};
+use exfiltrate_internal::wire::{ADDR, BACKOFF_DURATION, InFlightMessage, ReadStatus, send_socket_rpc};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::thread::JoinHandle;
+
+/// Default ceiling on concurrent `do_stream` connection handler threads; see
+/// [`set_max_connections`].
+#[cfg(not(target_arch = "wasm32"))]
+const DEFAULT_MAX_CONNECTIONS: usize = 16;
+
+/// The configured ceiling on concurrent connection handler threads.
+///
+/// Read once, when [`Server::new_tcp`] starts the accept loop; changing it
+/// afterwards has no effect on an already-running server. Override with
+/// [`set_max_connections`] before calling [`crate::begin`].
+#[cfg(not(target_arch = "wasm32"))]
+static MAX_CONNECTIONS: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_CONNECTIONS);
+
+/// Overrides the maximum number of connections the server will handle
+/// concurrently (default [`DEFAULT_MAX_CONNECTIONS`]).
+///
+/// A misbehaving or rapidly reconnecting client would otherwise cause
+/// `do_stream` to spawn an unbounded number of threads. Must be called
+/// before [`crate::begin`] starts the server; the accept loop reads this
+/// value only once, at startup.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_max_connections(max: usize) {
+    MAX_CONNECTIONS.store(max, Ordering::Release);
+}
+
+/// The pre-shared token new connections must prove knowledge of to upgrade
+/// to an encrypted [`SecureChannel`], or `None` (the default) to accept only
+/// the cleartext cookie handshake [`authenticate`] performs.
+///
+/// Read once per connection, right after [`authenticate`] succeeds; see
+/// [`set_secure_token`].
+#[cfg(not(target_arch = "wasm32"))]
+static SECURE_TOKEN: Mutex<Option<Token>> = Mutex::new(None);
+
+/// Enables the encrypted transport (see [`exfiltrate_internal::secure`]) and
+/// sets the pre-shared token clients must present to use it.
+///
+/// Every connection still authenticates with [`authenticate`] first. Once a
+/// token is set, it must *then* also complete the secure handshake before
+/// any command is accepted; a client that only speaks the cleartext cookie
+/// handshake fails it and is dropped (see [`do_stream`]). Must be called
+/// before [`crate::begin`] starts the server, like [`set_max_connections`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_secure_token(token: Token) {
+    *SECURE_TOKEN.lock().unwrap() = Some(token);
+}
+
+/// A counting semaphore bounding the number of concurrent connection handler
+/// threads spawned by the accept loop.
+///
+/// Implemented with the same poll-and-backoff style as the rest of this
+/// module's non-blocking I/O (see [`BACKOFF_DURATION`]) rather than a
+/// condvar, so it composes with the shutdown flag: [`ConnectionLimiter::acquire`]
+/// rechecks `shutdown` on every retry.
+#[cfg(not(target_arch = "wasm32"))]
+struct ConnectionLimiter {
+    max: usize,
+    active: Arc<AtomicUsize>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ConnectionLimiter {
+    fn new(max: usize) -> Self {
+        ConnectionLimiter {
+            max,
+            active: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Blocks until a permit is available, then returns a guard that
+    /// releases it on drop (including on every early-return or panic path
+    /// in the connection handler). Returns `None` if `shutdown` is signaled
+    /// before a permit frees up.
+    fn acquire(&self, shutdown: &AtomicBool) -> Option<ConnectionPermit> {
+        loop {
+            if shutdown.load(Ordering::Acquire) {
+                return None;
+            }
+            let current = self.active.load(Ordering::Acquire);
+            if current < self.max
+                && self
+                    .active
+                    .compare_exchange(current, current + 1, Ordering::AcqRel, Ordering::Acquire)
+                    .is_ok()
+            {
+                return Some(ConnectionPermit {
+                    active: self.active.clone(),
+                });
+            }
+            std::thread::sleep(BACKOFF_DURATION);
+        }
+    }
+}
+
+/// A held permit from a [`ConnectionLimiter`]. Releases it back to the
+/// limiter when dropped.
+#[cfg(not(target_arch = "wasm32"))]
+struct ConnectionPermit {
+    active: Arc<AtomicUsize>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for ConnectionPermit {
+    fn drop(&mut self) {
+        self.active.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// The handshake cookie for this server process.
+///
+/// Generated once on startup and published via the rendezvous file (see
+/// [`rendezvous`]); every accepted connection must prove it knows this value
+/// in response to a per-connection challenge (see [`authenticate`]) before
+/// `do_command` becomes reachable.
+#[cfg(not(target_arch = "wasm32"))]
+static COOKIE: LazyLock<Cookie> = LazyLock::new(cookie::generate);
+
+/// How this server instance ended up reachable.
+#[cfg(not(target_arch = "wasm32"))]
+enum ServerMode {
+    /// Listening for TCP connections at the recorded address.
+    Tcp,
+    /// Binding failed entirely (e.g. a sandboxed process with no socket
+    /// access). Commands can only be reached via [`Server::dispatch_local`].
+    Loopback,
+}
 
 /// The exfiltrate server.
 ///
 /// Listens for connections from the CLI and executes commands.
 /// The implementation differs based on the target architecture:
-/// *   **Native**: Opens a TCP listener on 127.0.0.1:1337.
+/// *   **Native**: Opens a TCP listener, preferring 127.0.0.1:1337 but falling
+///     back to an ephemeral port (or to loopback-only mode in a sandbox). See
+///     [`Server::new_tcp`].
 /// *   **WASM**: Connects to the proxy via WebSocket on 127.0.0.1:1338.
-pub struct Server {}
+pub struct Server {
+    #[cfg(not(target_arch = "wasm32"))]
+    mode: ServerMode,
+    /// Set by [`Server::shutdown`] to tell the accept loop and every
+    /// `do_stream` thread to drain and exit.
+    #[cfg(not(target_arch = "wasm32"))]
+    shutdown: Arc<AtomicBool>,
+    /// The bound address, if any, used to unblock the accept loop's blocking
+    /// `accept()` call by connecting to ourselves.
+    #[cfg(not(target_arch = "wasm32"))]
+    local_addr: Option<SocketAddr>,
+    /// Handle for the accept loop thread, taken and joined by `shutdown`.
+    #[cfg(not(target_arch = "wasm32"))]
+    accept_thread: Mutex<Option<JoinHandle<()>>>,
+    /// Handles for every in-flight `do_stream` connection thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    connections: Arc<Mutex<Vec<JoinHandle<()>>>>,
+}
+
+/// Performs the challenge/response handshake on a freshly accepted
+/// connection.
+///
+/// Sends an [`RPC::Challenge`] with a fresh nonce, then blocks (via the usual
+/// non-blocking-read/backoff pattern) until the client answers with an
+/// `RPC::Hello` whose `proof` verifies against [`COOKIE`] for that nonce (see
+/// [`cookie::verify`]). On success, returns `true` and leaves
+/// `in_flight_message` ready to read the next frame. On any mismatch, missing
+/// proof, or unparseable frame, replies with `RPC::AuthError` and returns
+/// `false` so the caller can drop the stream. Also returns `false` (silently)
+/// if `shutdown` is set while waiting, so a stream accepted just before a
+/// drain doesn't block it.
+#[cfg(not(target_arch = "wasm32"))]
+fn authenticate(
+    in_flight_message: &mut InFlightMessage,
+    stream: &mut TcpStream,
+    shutdown: &AtomicBool,
+) -> bool {
+    let nonce = cookie::generate_nonce();
+    if send_socket_rpc(RPC::Challenge { nonce }, stream).is_err() {
+        return false;
+    }
+    loop {
+        if shutdown.load(Ordering::Acquire) {
+            return false;
+        }
+        match in_flight_message.read_stream(stream) {
+            Err(e) => {
+                eprintln!("Error reading handshake: {:?}", e);
+                return false;
+            }
+            Ok(exfiltrate_internal::wire::ReadStatus::WouldBlock) => {
+                std::thread::sleep(BACKOFF_DURATION);
+            }
+            Ok(exfiltrate_internal::wire::ReadStatus::Progress) => {
+                continue;
+            }
+            Ok(exfiltrate_internal::wire::ReadStatus::Completed(pop)) => {
+                let rpc = rmp_serde::from_slice::<RPC>(&pop).ok();
+                return match rpc {
+                    Some(RPC::Hello { proof }) if cookie::verify(&COOKIE, &nonce, &proof) => true,
+                    _ => {
+                        let _ = send_socket_rpc(
+                            RPC::AuthError {
+                                reason: "missing or invalid handshake proof".to_string(),
+                            },
+                            stream,
+                        );
+                        false
+                    }
+                };
+            }
+            Ok(_) => {
+                eprintln!("Unknown ReadStatus variant received");
+                return false;
+            }
+        }
+    }
+}
 
+/// Either side of the command loop after [`authenticate`] succeeds:
+/// cleartext, or upgraded to a [`SecureChannel`] if [`SECURE_TOKEN`] is
+/// configured. Lets [`do_stream`]'s loop send an [`RPC`] without caring which
+/// one it ended up with.
 #[cfg(not(target_arch = "wasm32"))]
-fn do_stream(mut stream: TcpStream) {
+enum Channel {
+    Cleartext(TcpStream),
+    Secure(SecureChannel<TcpStream>),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Channel {
+    fn send_rpc(&mut self, rpc: RPC) -> std::io::Result<()> {
+        match self {
+            Channel::Cleartext(stream) => send_socket_rpc(rpc, stream),
+            Channel::Secure(channel) => channel.send(&rmp_serde::to_vec(&rpc).unwrap()),
+        }
+    }
+
+    /// Sends one item of a streamed response to `reply_id` (see
+    /// [`RPC::CommandStreamItem`]).
+    ///
+    /// This is the wire-level primitive a `watch`/`tail`-style command would
+    /// call as it produces each item; no built-in [`crate::commands::COMMANDS`]
+    /// entry does yet, since [`crate::command::Command::execute`] only
+    /// returns a single [`CommandResponse`] and has no way to reach the
+    /// connection its invocation arrived on.
+    #[allow(dead_code)]
+    fn send_stream_item(
+        &mut self,
+        reply_id: u32,
+        seq: u32,
+        response: Response,
+        is_final: bool,
+    ) -> std::io::Result<()> {
+        self.send_rpc(RPC::CommandStreamItem {
+            reply_id,
+            seq,
+            response,
+            is_final,
+        })
+    }
+}
+
+/// The [`Channel`] counterpart's frame buffer: an [`InFlightMessage`] for
+/// [`Channel::Cleartext`], or a [`SecureInFlightMessage`] for
+/// [`Channel::Secure`].
+#[cfg(not(target_arch = "wasm32"))]
+enum FrameReader {
+    Cleartext(InFlightMessage),
+    Secure(SecureInFlightMessage),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FrameReader {
+    fn read_stream(&mut self, channel: &mut Channel) -> std::io::Result<ReadStatus> {
+        match (self, channel) {
+            (FrameReader::Cleartext(reader), Channel::Cleartext(stream)) => {
+                reader.read_stream(stream)
+            }
+            (FrameReader::Secure(reader), Channel::Secure(channel)) => {
+                reader.read_stream(channel)
+            }
+            _ => unreachable!("a FrameReader is only ever paired with its matching Channel variant"),
+        }
+    }
+}
+
+/// Spawns the per-connection thread for a freshly accepted stream.
+///
+/// `shutdown` is checked on every idle tick (both during the handshake and
+/// the main command loop) so that a drain in progress ends each connection's
+/// thread as soon as it next goes idle, rather than waiting indefinitely for
+/// the peer to close the stream. `permit` is held for the lifetime of the
+/// thread and released back to the [`ConnectionLimiter`] on drop, so every
+/// exit path (normal return or panic) frees the slot.
+///
+/// After the cleartext cookie handshake, if [`set_secure_token`] has
+/// configured a [`Token`], every connection must also complete the
+/// [`SecureChannel`] handshake before any command is accepted -- a client
+/// that doesn't send the expected key-exchange frame next simply fails that
+/// handshake and gets dropped, the same way a bad cookie proof does.
+#[cfg(not(target_arch = "wasm32"))]
+fn do_stream(mut stream: TcpStream, shutdown: Arc<AtomicBool>, permit: ConnectionPermit) -> JoinHandle<()> {
     std::thread::Builder::new()
         .name("exfiltrate::server do_stream".to_string())
         .spawn(move || {
+            let _permit = permit;
             let mut in_flight_message = InFlightMessage::new();
+            if !authenticate(&mut in_flight_message, &mut stream, &shutdown) {
+                return;
+            }
+
+            let secure_token = *SECURE_TOKEN.lock().unwrap();
+            let (mut channel, mut frame_reader) = match secure_token {
+                Some(token) => match SecureChannel::handshake_server(stream, &token) {
+                    Ok(channel) => (Channel::Secure(channel), FrameReader::Secure(SecureInFlightMessage::new())),
+                    Err(e) => {
+                        eprintln!("Error completing secure handshake: {:?}", e);
+                        return;
+                    }
+                },
+                None => (Channel::Cleartext(stream), FrameReader::Cleartext(in_flight_message)),
+            };
+
             loop {
-                let msg = in_flight_message.read_stream(&mut stream);
+                if shutdown.load(Ordering::Acquire) {
+                    return;
+                }
+                let msg = frame_reader.read_stream(&mut channel);
                 match msg {
                     Err(e) => {
                         eprintln!("Error reading inflight message: {:?}", e);
                         return;
                     }
-                    Ok(exfiltrate_internal::wire::ReadStatus::WouldBlock) => {
+                    Ok(ReadStatus::WouldBlock) => {
                         std::thread::sleep(BACKOFF_DURATION);
                     }
-                    Ok(exfiltrate_internal::wire::ReadStatus::Progress) => {
+                    Ok(ReadStatus::Progress) => {
                         continue;
                     }
-                    Ok(exfiltrate_internal::wire::ReadStatus::Completed(pop)) => {
+                    Ok(ReadStatus::Completed(pop)) => {
                         let rpc = rmp_serde::from_slice::<RPC>(&pop).unwrap();
                         match rpc {
                             RPC::Command(command) => {
                                 let response = do_command(command);
                                 let reply_id = response.reply_id;
                                 //serialize to json
-                                let result =
-                                    send_socket_rpc(RPC::CommandResponse(response), &mut stream);
+                                let result = channel.send_rpc(RPC::CommandResponse(response));
                                 match result {
                                     Ok(()) => {}
                                     Err(e) => {
@@ -53,6 +366,21 @@ fn do_stream(mut stream: TcpStream) {
                             RPC::CommandResponse(_response) => {
                                 todo!("Server-side CommandResponse not yet handled")
                             }
+                            RPC::Batch {
+                                invocations,
+                                sequence,
+                            } => {
+                                for response in do_batch(invocations, sequence) {
+                                    let reply_id = response.reply_id;
+                                    let result = channel.send_rpc(RPC::CommandResponse(response));
+                                    if let Err(e) = result {
+                                        eprintln!(
+                                            "Error replying to batched command {} {}",
+                                            reply_id, e
+                                        );
+                                    }
+                                }
+                            }
                             _ => {
                                 eprintln!("Unknown RPC variant received");
                             }
@@ -64,13 +392,60 @@ fn do_stream(mut stream: TcpStream) {
                 }
             }
         })
-        .unwrap();
+        .unwrap()
+}
+
+/// Runs `invocation` through the registered [`crate::middleware`] chain,
+/// which ultimately calls [`do_command_imp`].
+fn do_command(invocation: CommandInvocation) -> CommandResponse {
+    crate::middleware::run_chain(invocation, do_command_imp)
 }
 
-fn do_command(command: CommandInvocation) -> CommandResponse {
+/// Runs every invocation in an [`RPC::Batch`] and returns one
+/// [`CommandResponse`] per invocation, in request order.
+///
+/// When `sequence` is `false`, every invocation runs concurrently on its own
+/// scoped thread (none of them touch the connection, so there's nothing to
+/// synchronize). When `sequence` is `true`, they run one at a time on the
+/// calling thread, and the first failure short-circuits the rest: each
+/// invocation after the failure gets a synthetic failed response instead of
+/// actually running, so the caller still gets exactly one response per
+/// invocation it sent.
+fn do_batch(invocations: Vec<CommandInvocation>, sequence: bool) -> Vec<CommandResponse> {
+    if !sequence {
+        return std::thread::scope(|scope| {
+            invocations
+                .into_iter()
+                .map(|invocation| scope.spawn(move || do_command(invocation)))
+                .collect::<Vec<_>>()
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+    }
+
+    let mut responses = Vec::with_capacity(invocations.len());
+    let mut failed = false;
+    for invocation in invocations {
+        if failed {
+            responses.push(CommandResponse::new(
+                false,
+                "skipped: a previous command in this batch failed".to_string().into(),
+                invocation.reply_id,
+            ));
+            continue;
+        }
+        let response = do_command(invocation);
+        failed = !response.success;
+        responses.push(response);
+    }
+    responses
+}
+
+fn do_command_imp(command: &CommandInvocation) -> CommandResponse {
     for matcher in COMMANDS.lock_sync_read().iter() {
         if matcher.name() == command.name {
-            let r = matcher.execute(command.args);
+            let r = matcher.execute(command.args.clone());
             match r {
                 Ok(response) => return CommandResponse::new(true, response, command.reply_id),
                 Err(response) => return CommandResponse::new(false, response, command.reply_id),
@@ -98,27 +473,83 @@ impl Server {
         }
     }
 
+    /// Binds the server socket, publishes it for discovery, and starts accepting.
+    ///
+    /// 1.  Tries to bind [`ADDR`]. If that port is already taken (e.g. a
+    ///     second debugged process on the same host), falls back to an
+    ///     ephemeral port instead of failing.
+    /// 2.  If binding fails entirely (for example, a sandboxed mobile
+    ///     environment that disallows listening sockets), falls back to
+    ///     loopback-only mode rather than panicking: commands are still
+    ///     reachable in-process via [`Server::dispatch_local`].
+    /// 3.  On success, writes the bound address and handshake cookie to the
+    ///     rendezvous file (under [`rendezvous::with_lock`]) so the CLI (and
+    ///     any other client) can find us without hard-coding `ADDR`.
+    ///
+    /// The accept loop and every spawned `do_stream` thread share a single
+    /// shutdown flag; see [`Server::shutdown`]. Concurrent connections are
+    /// capped by [`MAX_CONNECTIONS`] (see [`set_max_connections`]); once that
+    /// many are active, the accept loop blocks rather than spawning more.
     #[cfg(not(target_arch = "wasm32"))]
     fn new_tcp() -> Server {
-        let listener = match TcpListener::bind(ADDR) {
-            Ok(listener) => listener,
-            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
-                panic!(
-                    "Permission denied to open the exfiltrate server socket.  You may be running in a sandbox."
-                )
+        LazyLock::force(&COOKIE);
+        let listener = rendezvous::with_lock(|| match TcpListener::bind(ADDR) {
+            Ok(listener) => Ok(Some(listener)),
+            Err(e) if e.kind() == std::io::ErrorKind::AddrInUse => {
+                eprintln!("{} is already in use; binding an ephemeral port instead", ADDR);
+                Ok(Some(TcpListener::bind("127.0.0.1:0")?))
             }
-            Err(e) => {
-                panic!("Can't open socket: {:?}", e);
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                eprintln!(
+                    "Permission denied to open the exfiltrate server socket (you may be running in a sandbox); \
+                     falling back to an in-process-only transport."
+                );
+                Ok(None)
             }
+            Err(e) => Err(e),
+        })
+        .unwrap_or_else(|e| panic!("Can't open socket: {:?}", e));
+
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let connections = Arc::new(Mutex::new(Vec::new()));
+
+        let Some(listener) = listener else {
+            return Server {
+                mode: ServerMode::Loopback,
+                shutdown,
+                local_addr: None,
+                accept_thread: Mutex::new(None),
+                connections,
+            };
         };
-        eprintln!("Listening on {}", ADDR);
-        std::thread::Builder::new()
+
+        let addr = listener.local_addr().expect("bound listener has no local address");
+        if let Err(e) = rendezvous::with_lock(|| {
+            rendezvous::write(&Rendezvous {
+                addr,
+                cookie: *COOKIE,
+            })
+        }) {
+            eprintln!("Failed to write exfiltrate rendezvous file: {}", e);
+        }
+        eprintln!("Listening on {}", addr);
+        let accept_shutdown = shutdown.clone();
+        let accept_connections = connections.clone();
+        let limiter = ConnectionLimiter::new(MAX_CONNECTIONS.load(Ordering::Acquire));
+        let accept_handle = std::thread::Builder::new()
             .name("exfiltrate::listen".to_string())
             .spawn(move || {
                 for stream in listener.incoming() {
+                    if accept_shutdown.load(Ordering::Acquire) {
+                        return;
+                    }
                     match stream {
                         Ok(stream) => {
-                            do_stream(stream);
+                            let Some(permit) = limiter.acquire(&accept_shutdown) else {
+                                return;
+                            };
+                            let handle = do_stream(stream, accept_shutdown.clone(), permit);
+                            accept_connections.lock().unwrap().push(handle);
                         }
                         Err(e) => {
                             panic!("{}", e);
@@ -127,7 +558,54 @@ impl Server {
                 }
             })
             .unwrap();
-        Server {}
+        Server {
+            mode: ServerMode::Tcp,
+            shutdown,
+            local_addr: Some(addr),
+            accept_thread: Mutex::new(Some(accept_handle)),
+            connections,
+        }
+    }
+
+    /// Signals every background thread owned by this server to drain and
+    /// exit, then blocks until they have.
+    ///
+    /// Sets the shared shutdown flag, then (if we're actually listening on
+    /// TCP) connects to ourselves to unblock the accept loop's blocking
+    /// `accept()` call, joins the accept thread, and finally joins every
+    /// in-flight `do_stream` connection thread. Safe to call more than once
+    /// or on a [`ServerMode::Loopback`] instance, which has nothing to join.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Release);
+        if let Some(addr) = self.local_addr {
+            let _ = TcpStream::connect(addr);
+        }
+        if let Some(handle) = self.accept_thread.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+        for handle in self.connections.lock().unwrap().drain(..) {
+            let _ = handle.join();
+        }
+    }
+
+    /// Directly executes a command against this server's registry, bypassing
+    /// the network entirely.
+    ///
+    /// This is the only way to reach commands when [`Server::is_loopback`] is
+    /// `true`, but it works identically (and is just as cheap) when the
+    /// server is also listening on TCP.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn dispatch_local(&self, name: String, args: Vec<String>) -> CommandResponse {
+        do_command(CommandInvocation::new(name, args, 0))
+    }
+
+    /// Returns `true` if this server could not open a TCP listener (e.g.
+    /// because the process is sandboxed) and is reachable only via
+    /// [`Server::dispatch_local`].
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn is_loopback(&self) -> bool {
+        matches!(self.mode, ServerMode::Loopback)
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -136,3 +614,10 @@ impl Server {
         Server {}
     }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
@@ -15,6 +15,7 @@ use exfiltrate_internal::rpc::{CommandInvocation, RPC};
 use local_commands::list::List;
 use rand::Rng;
 use rand::distr::Alphanumeric;
+use serde::Serialize;
 use std::io::Write;
 use std::path::PathBuf;
 use webp::PixelLayout;
@@ -22,14 +23,94 @@ use wire::client::CLIENT;
 
 mod help;
 mod local_commands;
+mod shell;
 mod wire;
 
+/// Whether a command's result is rendered as human-readable text (the
+/// default) or as a single structured JSON object on stdout.
+///
+/// JSON mode exists for scripts and editor integrations that need to consume
+/// `exfiltrate`'s output reliably -- the free-form text below is meant for a
+/// human terminal and its exact wording isn't a stable contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// The result of dispatching one command, in a shape either [`OutputFormat`]
+/// can render.
+///
+/// `reply_id` is `0` for local commands (e.g. `help`, `list`), which execute
+/// in-process and never get one of the wire protocol's
+/// [`CommandInvocation::reply_id`]s assigned.
+#[derive(Debug, Serialize)]
+struct CommandOutcome {
+    reply_id: u32,
+    success: bool,
+    #[serde(flatten)]
+    payload: OutcomePayload,
+}
+
+/// The typed payload of a [`CommandOutcome`], distinguishing the four
+/// [`Response`] variants the CLI renders (plus the error case) so JSON
+/// consumers don't have to parse free-form text to tell them apart.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum OutcomePayload {
+    Error {
+        message: String,
+    },
+    String {
+        text: String,
+    },
+    Files {
+        files: Vec<WrittenFile>,
+    },
+    Images {
+        images: Vec<WrittenImage>,
+    },
+    Bytes {
+        bytes: usize,
+        path: String,
+    },
+}
+
+/// A [`exfiltrate_internal::command::FileInfo`] after being written to disk.
+#[derive(Debug, Serialize)]
+struct WrittenFile {
+    proposed_extension: String,
+    bytes: usize,
+    path: String,
+    remark: Option<String>,
+}
+
+/// A [`exfiltrate_internal::command::ImageInfo`] after being encoded to WebP
+/// and written to disk.
+#[derive(Debug, Serialize)]
+struct WrittenImage {
+    width: u32,
+    height: u32,
+    path: String,
+    remark: Option<String>,
+}
+
+impl CommandOutcome {
+    fn error(reply_id: u32, message: String) -> Self {
+        CommandOutcome {
+            reply_id,
+            success: false,
+            payload: OutcomePayload::Error { message },
+        }
+    }
+}
+
 /// Entry point for the CLI.
 ///
 /// Parses arguments and delegates to `dispatch` or `help`.
 fn main() {
     let exe_args = std::env::args().collect::<Vec<String>>();
-    let args = exe_args[1..].to_vec();
+    let (format, args) = take_format_flag(exe_args[1..].to_vec());
     if args.is_empty()
         || args[0] == "-h"
         || args[0] == "--help"
@@ -38,16 +119,50 @@ fn main() {
         help();
         return;
     }
-    //try to dispatch a command
-    match dispatch(args) {
-        Ok(result) => {
-            println!("{}", result);
+    if args[0] == "shell" {
+        shell::run();
+        return;
+    }
+    let outcome = dispatch(args);
+    let success = outcome.success;
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string(&outcome).unwrap());
         }
-        Err(e) => {
-            eprintln!("{}", e);
-            std::process::exit(1);
+        OutputFormat::Text => match &outcome.payload {
+            OutcomePayload::Error { message } => eprintln!("{}", message),
+            _ => println!("{}", render_text(&outcome.payload)),
+        },
+    }
+    if !success {
+        std::process::exit(1);
+    }
+}
+
+/// Pulls a `--format json` or `--format=json` flag out of `args`,
+/// wherever it appears, returning the requested [`OutputFormat`] and the
+/// remaining arguments in their original order.
+///
+/// Any other `--format` value (or no flag at all) selects [`OutputFormat::Text`],
+/// the existing free-form behavior.
+fn take_format_flag(args: Vec<String>) -> (OutputFormat, Vec<String>) {
+    let mut format = OutputFormat::Text;
+    let mut remaining = Vec::with_capacity(args.len());
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--format" {
+            if iter.next().as_deref() == Some("json") {
+                format = OutputFormat::Json;
+            }
+        } else if let Some(value) = arg.strip_prefix("--format=") {
+            if value == "json" {
+                format = OutputFormat::Json;
+            }
+        } else {
+            remaining.push(arg);
         }
     }
+    (format, remaining)
 }
 
 /// Dispatches a command to either a local handler or the remote application.
@@ -56,120 +171,36 @@ fn main() {
 /// 2.  If no local match, attempts to connect to the remote application via `CLIENT`.
 /// 3.  Sends the command via RPC and waits for a response.
 /// 4.  Handles the response (saving files/images or returning text).
-fn dispatch(args: Vec<String>) -> Result<String, String> {
+fn dispatch(args: Vec<String>) -> CommandOutcome {
     //first, try local commands
     let command_name = args[0].to_string();
     let forwarded_args = args[1..].to_vec();
     for command in local_commands::COMMANDS.iter() {
         if command.name() == args[0] {
-            let r = command.execute(forwarded_args);
-            match r {
-                Ok(result) => {
-                    return Ok(result.to_string());
-                }
-                Err(e) => {
-                    return Err(e.to_string());
-                }
-            }
+            return match command.execute(forwarded_args) {
+                Ok(response) => render_response(0, response),
+                Err(response) => CommandOutcome::error(0, response.to_string()),
+            };
         }
     }
     //now try remote commands
     match CLIENT.as_ref() {
-        Err(e) => Err(e.to_string()),
+        Err(e) => CommandOutcome::error(0, e.to_string()),
         Ok(client) => {
             let reply_id = client.next_reply_id();
             let command_invocation = CommandInvocation::new(command_name, forwarded_args, reply_id);
             let r = client.send_rpc(RPC::Command(command_invocation));
             match r {
-                Err(e) => Err(e.to_string()),
-
+                Err(e) => CommandOutcome::error(reply_id, e.to_string()),
                 Ok(_) => {
                     let reply = client.pop_msg(reply_id);
                     match reply {
-                        Err(e) => Err(e.to_string()),
-
+                        Err(e) => CommandOutcome::error(reply_id, e.to_string()),
                         Ok(r) => {
                             if !r.success {
-                                Err(r.response.to_string())
+                                CommandOutcome::error(reply_id, r.response.to_string())
                             } else {
-                                match r.response {
-                                    Response::String(s) => Ok(s),
-                                    Response::Files(files) => {
-                                        let mut output = String::new();
-                                        for f in files {
-                                            if let Some(remark) = &f.remark {
-                                                output.push_str(remark);
-                                                output.push('\n');
-                                            }
-                                            // create a random filename
-                                            let rand_string: String = rand::rng()
-                                                .sample_iter(&Alphanumeric)
-                                                .take(5)
-                                                .map(char::from)
-                                                .collect();
-                                            let mut path = PathBuf::from(".");
-                                            path.push(format!(
-                                                "{}.{}",
-                                                rand_string,
-                                                f.proposed_extension.trim_start_matches('.')
-                                            ));
-                                            let mut file = std::fs::File::create(&path).unwrap();
-                                            let write_result = file.write_all(&f.contents);
-                                            match write_result {
-                                                Ok(..) => {}
-                                                Err(e) => {
-                                                    return Err(e.to_string());
-                                                }
-                                            }
-                                            output.push_str(&format!(
-                                                "Wrote {bytes} bytes to {path}\n",
-                                                bytes = f.contents.len(),
-                                                path = path.to_str().unwrap()
-                                            ));
-                                        }
-                                        Ok(output)
-                                    }
-                                    Response::Images(images) => {
-                                        let mut output = String::new();
-                                        for info in images {
-                                            if let Some(remark) = &info.remark {
-                                                output.push_str(remark);
-                                                output.push('\n');
-                                            }
-                                            // create a random filename
-                                            let rand_string: String = rand::rng()
-                                                .sample_iter(&Alphanumeric)
-                                                .take(5)
-                                                .map(char::from)
-                                                .collect();
-                                            let mut path = PathBuf::from(".");
-                                            path.push(format!("{}.{}", rand_string, "webp"));
-                                            let mut file = std::fs::File::create(&path).unwrap();
-                                            let data: &[u8] = bytemuck::cast_slice(&info.data);
-                                            let time = std::time::Instant::now();
-                                            let encode = webp::Encoder::new(
-                                                data,
-                                                PixelLayout::Rgba,
-                                                info.width,
-                                                info.height,
-                                            );
-                                            let r = encode.encode_lossless();
-                                            eprintln!(
-                                                "Encoded in {} ms to {} bytes",
-                                                time.elapsed().as_millis(),
-                                                r.len()
-                                            );
-                                            file.write_all(&r).unwrap();
-                                            output.push_str("Wrote image to ");
-                                            output.push_str(path.as_os_str().to_str().unwrap());
-                                            output.push('\n');
-                                        }
-                                        Ok(output)
-                                    }
-                                    _ => {
-                                        todo!()
-                                    }
-                                }
+                                render_response(reply_id, r.response)
                             }
                         }
                     }
@@ -179,6 +210,137 @@ fn dispatch(args: Vec<String>) -> Result<String, String> {
     }
 }
 
+/// Turns a successful [`Response`] into a [`CommandOutcome`], writing any
+/// files or images it carries to randomly named paths in the current
+/// directory along the way.
+fn render_response(reply_id: u32, response: Response) -> CommandOutcome {
+    match response {
+        Response::String(text) => CommandOutcome {
+            reply_id,
+            success: true,
+            payload: OutcomePayload::String { text },
+        },
+        Response::Files(files) => {
+            let mut written = Vec::with_capacity(files.len());
+            for f in files {
+                let path = random_path(f.proposed_extension.trim_start_matches('.'));
+                match std::fs::File::create(&path).and_then(|mut file| file.write_all(&f.contents))
+                {
+                    Ok(()) => written.push(WrittenFile {
+                        proposed_extension: f.proposed_extension,
+                        bytes: f.contents.len(),
+                        path: path.to_str().unwrap().to_string(),
+                        remark: f.remark,
+                    }),
+                    Err(e) => return CommandOutcome::error(reply_id, e.to_string()),
+                }
+            }
+            CommandOutcome {
+                reply_id,
+                success: true,
+                payload: OutcomePayload::Files { files: written },
+            }
+        }
+        Response::Images(images) => {
+            let mut written = Vec::with_capacity(images.len());
+            for info in images {
+                let path = random_path("webp");
+                let data: &[u8] = bytemuck::cast_slice(&info.data);
+                let time = std::time::Instant::now();
+                let encode = webp::Encoder::new(data, PixelLayout::Rgba, info.width, info.height);
+                let encoded = encode.encode_lossless();
+                eprintln!(
+                    "Encoded in {} ms to {} bytes",
+                    time.elapsed().as_millis(),
+                    encoded.len()
+                );
+                if let Err(e) = std::fs::File::create(&path).and_then(|mut file| file.write_all(&encoded)) {
+                    return CommandOutcome::error(reply_id, e.to_string());
+                }
+                written.push(WrittenImage {
+                    width: info.width,
+                    height: info.height,
+                    path: path.to_str().unwrap().to_string(),
+                    remark: info.remark,
+                });
+            }
+            CommandOutcome {
+                reply_id,
+                success: true,
+                payload: OutcomePayload::Images { images: written },
+            }
+        }
+        Response::Bytes(bytes) => {
+            let path = random_path("bin");
+            if let Err(e) = std::fs::File::create(&path).and_then(|mut file| file.write_all(&bytes)) {
+                return CommandOutcome::error(reply_id, e.to_string());
+            }
+            CommandOutcome {
+                reply_id,
+                success: true,
+                payload: OutcomePayload::Bytes {
+                    bytes: bytes.len(),
+                    path: path.to_str().unwrap().to_string(),
+                },
+            }
+        }
+        // `exfiltrate_internal::command::Response` is `#[non_exhaustive]`,
+        // so this arm is required even though the four variants above are
+        // everything it currently declares: a future variant lands here
+        // until the CLI grows a dedicated arm for it, rather than being a
+        // compile error upstream.
+        _ => CommandOutcome::error(reply_id, "Unsupported response type".to_string()),
+    }
+}
+
+/// Renders a successful [`CommandOutcome`]'s payload the way the CLI always
+/// has: free-form text meant for a human terminal, not a stable format.
+fn render_text(payload: &OutcomePayload) -> String {
+    match payload {
+        OutcomePayload::Error { message } => message.clone(),
+        OutcomePayload::String { text } => text.clone(),
+        OutcomePayload::Files { files } => {
+            let mut output = String::new();
+            for f in files {
+                if let Some(remark) = &f.remark {
+                    output.push_str(remark);
+                    output.push('\n');
+                }
+                output.push_str(&format!("Wrote {} bytes to {}\n", f.bytes, f.path));
+            }
+            output
+        }
+        OutcomePayload::Images { images } => {
+            let mut output = String::new();
+            for info in images {
+                if let Some(remark) = &info.remark {
+                    output.push_str(remark);
+                    output.push('\n');
+                }
+                output.push_str("Wrote image to ");
+                output.push_str(&info.path);
+                output.push('\n');
+            }
+            output
+        }
+        OutcomePayload::Bytes { bytes, path } => format!("Wrote {bytes} bytes to {path}\n"),
+    }
+}
+
+/// Generates a random 5-character filename with the given extension in the
+/// current directory, the same way every response type that writes to disk
+/// always has.
+fn random_path(extension: &str) -> PathBuf {
+    let rand_string: String = rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(5)
+        .map(char::from)
+        .collect();
+    let mut path = PathBuf::from(".");
+    path.push(format!("{}.{}", rand_string, extension));
+    path
+}
+
 fn list() {
     let list = List::execute(&List, vec![]).unwrap();
     eprintln!("Commands:");
@@ -0,0 +1,140 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Interactive shell mode (`exfiltrate shell`): one long-lived [`CLIENT`]
+//! connection, with a line editor in front of the same [`crate::dispatch`]
+//! every one-shot invocation uses.
+//!
+//! A plain one-shot invocation pays [`CLIENT`]'s connection and handshake
+//! cost on every launch. Entering the shell instead pays it once and reuses
+//! the connection -- and its per-`reply_id` pending-response map (see
+//! [`crate::wire::client::Client`]) -- for every line entered, so responses (and, once a command produces
+//! them, streamed items) keep landing on whichever request is waiting for
+//! them exactly as they would for a single command.
+
+use crate::wire::client::CLIENT;
+use exfiltrate_internal::command::Response;
+use exfiltrate_internal::commands::list::ListItem;
+use exfiltrate_internal::rpc::{CommandInvocation, RPC};
+use rustyline::completion::{Completer, Pair};
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::borrow::Cow;
+
+/// Runs the interactive shell until the user exits (`exit`, `quit`, or EOF).
+///
+/// Each line is split on whitespace and handed to [`crate::dispatch`] exactly
+/// as `std::env::args()` would be for a one-shot invocation, and rendered as
+/// text -- `--format json` is a scripting affordance that doesn't make sense
+/// for a line someone just typed at a prompt.
+pub fn run() {
+    let mut editor: Editor<ShellHelper, rustyline::history::DefaultHistory> =
+        Editor::new().expect("failed to initialize line editor");
+    editor.set_helper(Some(ShellHelper {
+        commands: command_names(),
+    }));
+    eprintln!("exfiltrate interactive shell. Type `help` for commands, `exit` to quit.");
+    loop {
+        match editor.readline("exfiltrate> ") {
+            Ok(line) => {
+                let line = line.trim();
+                if line.is_empty() {
+                    continue;
+                }
+                let _ = editor.add_history_entry(line);
+                if line == "exit" || line == "quit" {
+                    break;
+                }
+                let args: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+                if args[0] == "-h" || args[0] == "--help" || (args[0] == "help" && args.len() == 1)
+                {
+                    crate::help();
+                    continue;
+                }
+                let outcome = crate::dispatch(args);
+                match &outcome.payload {
+                    crate::OutcomePayload::Error { message } => eprintln!("{}", message),
+                    payload => println!("{}", crate::render_text(payload)),
+                }
+            }
+            Err(rustyline::error::ReadlineError::Interrupted) => continue,
+            Err(rustyline::error::ReadlineError::Eof) => break,
+            Err(e) => {
+                eprintln!("readline error: {}", e);
+                break;
+            }
+        }
+    }
+}
+
+/// The local command names plus, if connected, the remote application's
+/// command names -- the same two sources [`crate::local_commands::list::List`]
+/// merges, used here for tab-completion instead of a printed listing.
+fn command_names() -> Vec<String> {
+    let mut names: Vec<String> = crate::local_commands::COMMANDS
+        .iter()
+        .map(|c| c.name().to_string())
+        .collect();
+    if let Ok(client) = &*CLIENT {
+        let reply_id = client.next_reply_id();
+        let rpc = RPC::Command(CommandInvocation::new("list".to_string(), vec![], reply_id));
+        if client.send_rpc(rpc).is_ok()
+            && let Ok(msg) = client.pop_msg(reply_id)
+            && msg.success
+            && let Response::Bytes(bytes) = msg.response
+            && let Ok(items) = rmp_serde::from_slice::<Vec<ListItem>>(&bytes)
+        {
+            for item in items {
+                if !names.contains(&item.name) {
+                    names.push(item.name);
+                }
+            }
+        }
+    }
+    names
+}
+
+/// Tab-completes command names sourced from [`command_names`]; only the
+/// first word on the line is completed, since none of this CLI's commands
+/// take flags that benefit from completion of their own.
+struct ShellHelper {
+    commands: Vec<String>,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let start = line[..pos].rfind(' ').map(|i| i + 1).unwrap_or(0);
+        if start > 0 {
+            // Only the command name (the first word) is completed.
+            return Ok((pos, Vec::new()));
+        }
+        let prefix = &line[start..pos];
+        let candidates = self
+            .commands
+            .iter()
+            .filter(|name| name.starts_with(prefix))
+            .map(|name| Pair {
+                display: name.clone(),
+                replacement: name.clone(),
+            })
+            .collect();
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ShellHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ShellHelper {}
+
+impl Validator for ShellHelper {}
+
+impl Helper for ShellHelper {}
@@ -0,0 +1,66 @@
+//! Handshake cookie used to authenticate connections to the server socket.
+//!
+//! The exfiltrate server trusts any process that can connect to its TCP port,
+//! which is risky on shared or sandboxed hosts. To mitigate this, the server
+//! generates a random secret on startup, distributed to clients via the
+//! rendezvous file (see [`crate::rendezvous`]), which also records where the
+//! server ended up listening.
+//!
+//! Every new connection must prove it knows the cookie before any command is
+//! accepted. Earlier versions of this handshake had the client echo the
+//! cookie back verbatim as the first frame; that works, but it puts the raw
+//! secret on the wire on every single connection. Instead the server issues a
+//! fresh random nonce per connection (see [`crate::rpc::RPC::Challenge`]) and
+//! the client returns [`prove`]'s output for it, so the cookie itself is
+//! never transmitted after the process that generated it.
+
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
+
+/// Number of random bytes in a handshake cookie.
+pub const COOKIE_LEN: usize = 32;
+
+/// A random secret shared between the server and the CLI to authenticate connections.
+pub type Cookie = [u8; COOKIE_LEN];
+
+/// Number of random bytes in a handshake challenge nonce.
+pub const NONCE_LEN: usize = 32;
+
+/// A per-connection random value the server challenges the client with; see
+/// [`prove`] and [`verify`].
+pub type Nonce = [u8; NONCE_LEN];
+
+/// Generates a fresh random cookie.
+pub fn generate() -> Cookie {
+    let mut cookie = [0u8; COOKIE_LEN];
+    rand::rng().fill(&mut cookie);
+    cookie
+}
+
+/// Generates a fresh random challenge nonce.
+pub fn generate_nonce() -> Nonce {
+    let mut nonce = [0u8; NONCE_LEN];
+    rand::rng().fill(&mut nonce);
+    nonce
+}
+
+/// Proves knowledge of `cookie` for `nonce`, without ever sending `cookie`
+/// itself: `HMAC-SHA256(key = cookie, message = nonce)`.
+pub fn prove(cookie: &Cookie, nonce: &Nonce) -> Vec<u8> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(cookie).expect("HMAC-SHA256 accepts keys of any length");
+    mac.update(nonce);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Verifies a `proof` produced by [`prove`] for `nonce` under `cookie`.
+///
+/// Uses `hmac`'s constant-time tag comparison, so a mismatch can't leak
+/// timing information about the secret.
+pub fn verify(cookie: &Cookie, nonce: &Nonce, proof: &[u8]) -> bool {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(cookie).expect("HMAC-SHA256 accepts keys of any length");
+    mac.update(nonce);
+    mac.verify_slice(proof).is_ok()
+}
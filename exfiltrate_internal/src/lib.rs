@@ -7,7 +7,13 @@
 pub mod command;
 /// Built-in command implementations.
 pub mod commands;
+/// Handshake cookie used to authenticate connections to the server socket.
+pub mod cookie;
+/// Rendezvous-file based service discovery for the server socket.
+pub mod rendezvous;
 /// Remote procedure call protocol types.
 pub mod rpc;
-/// Wire protocol for TCP/WebSocket communication.
+/// Opt-in authenticated, encrypted framing layered on top of [`wire`].
+pub mod secure;
+/// Wire protocol for TCP/WebSocket/QUIC communication.
 pub mod wire;
@@ -0,0 +1,132 @@
+//! Rendezvous-file based service discovery for the server socket.
+//!
+//! The server used to always bind [`crate::wire::ADDR`], and the client used
+//! to always connect there too. That falls apart the moment the port is
+//! already taken (a second debugged process on the same host) or blocked
+//! entirely (a sandboxed environment). Instead, the server publishes wherever
+//! it actually ended up listening, plus the handshake cookie (see
+//! [`crate::cookie`]), to a well-known file. Clients read that file rather
+//! than assuming a fixed address.
+//!
+//! A lightweight lock file guards reads and writes so a client never observes
+//! a half-written rendezvous file.
+
+use crate::cookie::Cookie;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Everything a client needs to find and authenticate with a running server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rendezvous {
+    /// The address the server actually bound.
+    pub addr: SocketAddr,
+    /// The handshake cookie for that server instance.
+    pub cookie: Cookie,
+}
+
+/// Returns the path of the rendezvous file.
+pub fn rendezvous_path() -> PathBuf {
+    std::env::temp_dir().join("exfiltrate.rendezvous")
+}
+
+fn lock_path() -> PathBuf {
+    let mut path = rendezvous_path().into_os_string();
+    path.push(".lock");
+    PathBuf::from(path)
+}
+
+/// How long to wait to acquire the rendezvous lock before assuming its holder
+/// died and stealing it.
+const LOCK_STALE_AFTER: Duration = Duration::from_secs(2);
+/// How long to sleep between lock acquisition attempts.
+const LOCK_POLL_INTERVAL: Duration = Duration::from_millis(10);
+/// How long to wait for a TCP handshake when probing whether a recorded
+/// address is actually reachable.
+const PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// A simple advisory lock implemented as an exclusively-created marker file.
+///
+/// This is not an OS-level file lock (`flock`), just a best-effort mutex
+/// between exfiltrate processes sharing a temp directory. The lock file is
+/// removed on drop; a lock held longer than [`LOCK_STALE_AFTER`] is assumed
+/// to belong to a dead process and is stolen.
+struct FileLock {
+    path: PathBuf,
+}
+
+impl FileLock {
+    fn acquire(path: PathBuf) -> io::Result<Self> {
+        let start = Instant::now();
+        loop {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(FileLock { path }),
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                    if start.elapsed() > LOCK_STALE_AFTER {
+                        let _ = std::fs::remove_file(&path);
+                        continue;
+                    }
+                    std::thread::sleep(LOCK_POLL_INTERVAL);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+/// Runs `f` while holding the rendezvous lock.
+///
+/// Callers should do as little work as possible inside `f`, since other
+/// processes block on this lock for the duration.
+pub fn with_lock<T>(f: impl FnOnce() -> io::Result<T>) -> io::Result<T> {
+    let _lock = FileLock::acquire(lock_path())?;
+    f()
+}
+
+/// Writes the rendezvous file.
+///
+/// On unix the file is created mode `0600`, since it carries the handshake
+/// cookie. Callers should hold [`with_lock`] while calling this.
+pub fn write(info: &Rendezvous) -> io::Result<()> {
+    use std::io::Write;
+    let bytes =
+        serde_json::to_vec(info).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut file = std::fs::File::create(rendezvous_path())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Reads the rendezvous file.
+///
+/// Returns an error if no server has ever published one on this host, or if
+/// the contents are malformed.
+pub fn read() -> io::Result<Rendezvous> {
+    let bytes = std::fs::read(rendezvous_path())?;
+    serde_json::from_slice(&bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Returns `true` if something is actually accepting TCP connections at
+/// `addr`.
+///
+/// Used to tell a live rendezvous file apart from a stale one left behind by
+/// a process that exited without cleaning up.
+pub fn is_reachable(addr: SocketAddr) -> bool {
+    std::net::TcpStream::connect_timeout(&addr, PROBE_TIMEOUT).is_ok()
+}
@@ -7,10 +7,78 @@ use std::fmt::Display;
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[non_exhaustive]
 pub enum RPC {
+    /// The first frame the server sends on every freshly accepted
+    /// connection, before anything else: a fresh random value the client
+    /// must answer with [`RPC::Hello`] to prove it knows the handshake
+    /// cookie, without the cookie itself ever crossing the wire. See
+    /// [`crate::cookie`].
+    Challenge {
+        /// The per-connection nonce to prove knowledge of the cookie for.
+        nonce: crate::cookie::Nonce,
+    },
+    /// The handshake response to [`RPC::Challenge`].
+    ///
+    /// `proof` must be [`crate::cookie::prove`]'s output for the nonce just
+    /// sent and the secret the server wrote to the rendezvous file on startup,
+    /// or the server closes the connection with [`RPC::AuthError`].
+    Hello {
+        /// Proof of knowledge of the handshake cookie for the most recent
+        /// [`RPC::Challenge`]; see [`crate::cookie::prove`].
+        proof: Vec<u8>,
+    },
+    /// Sent by the server in place of any other reply when a connection
+    /// fails the cookie handshake.
+    AuthError {
+        /// A human-readable description of why the handshake failed.
+        reason: String,
+    },
     /// A command invocation request from the client.
     Command(CommandInvocation),
     /// A response to a command invocation.
     CommandResponse(CommandResponse),
+    /// Several command invocations sent as a single frame, to save a round
+    /// trip when a caller (e.g. `help`) needs multiple remote queries at
+    /// once.
+    ///
+    /// There is no corresponding batch response variant: the server replies
+    /// to each invocation with an ordinary [`RPC::CommandResponse`] carrying
+    /// that invocation's own `reply_id`, so the existing per-`reply_id`
+    /// dispatch in the CLI's wire client handles them with no extra
+    /// plumbing.
+    Batch {
+        /// The invocations to run, each carrying its own `reply_id`.
+        invocations: Vec<CommandInvocation>,
+        /// If `false`, the server may run every invocation concurrently and
+        /// makes no ordering guarantee between them. If `true`, the server
+        /// runs them strictly in order and, on the first failure, replies to
+        /// every remaining invocation with a synthetic failed
+        /// [`CommandResponse`] instead of executing it.
+        sequence: bool,
+    },
+    /// One item of an ordered, possibly unbounded sequence of responses to a
+    /// single [`CommandInvocation`], for commands that tail logs or watch
+    /// state rather than returning one snapshot.
+    ///
+    /// All items for one invocation share its `reply_id`, numbered by `seq`
+    /// starting at `0`, and the item with `is_final` set to `true` is always
+    /// the last one the server will send for that `reply_id` -- there is no
+    /// separate terminal [`RPC::CommandResponse`]. A client that isn't
+    /// watching for streamed items (i.e. never called
+    /// `Client::subscribe_stream` for this `reply_id`) should treat an
+    /// unexpected one the same as any other unsolicited reply.
+    CommandStreamItem {
+        /// The invocation this item is a part of.
+        reply_id: u32,
+        /// This item's position in the sequence, starting at `0`.
+        seq: u32,
+        /// This item's payload, using the same [`Response`] shape a
+        /// one-shot [`CommandResponse`] would.
+        response: Response,
+        /// Whether this is the last item the server will send for
+        /// `reply_id`.
+        #[serde(rename = "final")]
+        is_final: bool,
+    },
 }
 
 /// A request to invoke a command on the server.
@@ -63,8 +131,30 @@ impl CommandInvocation {
 impl Display for RPC {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            RPC::Challenge { .. } => write!(f, "Challenge"),
+            RPC::Hello { .. } => write!(f, "Hello"),
+            RPC::AuthError { reason } => write!(f, "AuthError({})", reason),
             RPC::Command(cmd) => write!(f, "Command({})", cmd),
             RPC::CommandResponse(resp) => write!(f, "CommandResponse({})", resp),
+            RPC::Batch {
+                invocations,
+                sequence,
+            } => write!(
+                f,
+                "Batch({} invocations, sequence={})",
+                invocations.len(),
+                sequence
+            ),
+            RPC::CommandStreamItem {
+                reply_id,
+                seq,
+                is_final,
+                ..
+            } => write!(
+                f,
+                "CommandStreamItem(reply_id={}, seq={}, final={})",
+                reply_id, seq, is_final
+            ),
         }
     }
 }
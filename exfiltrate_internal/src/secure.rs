@@ -0,0 +1,323 @@
+//! Authenticated, encrypted framing layered on top of any [`WireTransport`].
+//!
+//! [`crate::wire`]'s frames (and the cookie handshake in [`crate::cookie`])
+//! are sent in cleartext: anything that can reach the listening socket can
+//! read every command and response that crosses it, and the cookie proves
+//! only that a client once read the rendezvous file, not that the bytes in
+//! transit haven't been tampered with. [`SecureChannel`] is an opt-in upgrade
+//! for callers that need more than that:
+//!
+//! 1.  Both sides generate an ephemeral X25519 keypair and exchange public
+//!     keys, deriving a fresh shared secret per connection (see
+//!     [`SecureChannel::handshake_client`]/[`SecureChannel::handshake_server`]).
+//! 2.  Each side proves knowledge of a pre-shared [`Token`] (distributed out
+//!     of band, the same way [`crate::cookie::Cookie`] is) by signing the
+//!     handshake transcript, so a peer that can see the key exchange but
+//!     doesn't know the token can't complete it.
+//! 3.  Every frame afterwards is sealed with ChaCha20-Poly1305, keyed
+//!     separately per direction from the shared secret, with a monotonically
+//!     increasing per-direction nonce counter and the plaintext frame length
+//!     as associated data.
+//!
+//! A failed proof or a failed decryption is always fatal to the connection:
+//! neither side has a way to resynchronize a nonce counter or transcript
+//! once one has diverged, so [`SecureChannel::send`] and
+//! [`SecureInFlightMessage::read_stream`] hard-fail rather than attempt to
+//! recover.
+
+use crate::wire::{InFlightMessage, ReadStatus, WireTransport, send_socket_frame};
+use chacha20poly1305::aead::{Aead, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, KeyInit};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use sha2::Sha256;
+use x25519_dalek::{EphemeralSecret, PublicKey};
+
+/// Number of bytes in the pre-shared [`Token`] both sides of a
+/// [`SecureChannel`] must know.
+pub const TOKEN_LEN: usize = 32;
+
+/// A secret shared out of band between the two ends of a [`SecureChannel`],
+/// analogous to [`crate::cookie::Cookie`] but for the encrypted transport.
+pub type Token = [u8; TOKEN_LEN];
+
+/// Which end of the handshake a [`SecureChannel`] is playing, so the two
+/// directions get distinct derived keys even though the shared secret itself
+/// is symmetric.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Role {
+    Client,
+    Server,
+}
+
+/// A [`WireTransport`]-wrapping channel that encrypts every frame sent
+/// through it with ChaCha20-Poly1305 and decrypts (via
+/// [`SecureInFlightMessage`]) every frame read back.
+///
+/// Constructed by completing a handshake with
+/// [`SecureChannel::handshake_client`] or
+/// [`SecureChannel::handshake_server`]; there is no bare constructor, since a
+/// channel with no agreed keys can't encrypt anything.
+#[derive(Debug)]
+pub struct SecureChannel<T> {
+    inner: T,
+    send_cipher: ChaCha20Poly1305,
+    recv_cipher: ChaCha20Poly1305,
+    send_counter: u64,
+    recv_counter: u64,
+}
+
+impl<T: WireTransport> SecureChannel<T> {
+    /// Performs the client side of the handshake over `inner`: send our
+    /// ephemeral public key, receive the server's, prove knowledge of
+    /// `token` for the resulting transcript, and verify the server's
+    /// reciprocal proof.
+    ///
+    /// Blocks (via the usual non-blocking-read/backoff pattern other
+    /// handshakes in this crate use, e.g. the cookie challenge/response) until
+    /// the server answers or the connection fails.
+    pub fn handshake_client(mut inner: T, token: &Token) -> std::io::Result<Self> {
+        let secret = EphemeralSecret::random_from_rng(rand::rng());
+        let public = PublicKey::from(&secret);
+        let mut in_flight = InFlightMessage::new();
+
+        send_socket_frame(public.as_bytes(), &mut inner)?;
+        let server_public = read_frame_blocking(&mut in_flight, &mut inner)?;
+        let server_public = public_key_from_frame(&server_public)?;
+
+        let transcript = transcript(public.as_bytes(), server_public.as_bytes());
+        // Each direction signs a distinct message, so the server can't pass
+        // by echoing our own proof back.
+        let client_message = [&transcript[..], &b"client"[..]].concat();
+        let server_message = [&transcript[..], &b"server"[..]].concat();
+        send_socket_frame(&prove(token, &client_message), &mut inner)?;
+
+        let server_proof = read_frame_blocking(&mut in_flight, &mut inner)?;
+        if !verify(token, &server_message, &server_proof) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "server did not prove knowledge of the shared token",
+            ));
+        }
+
+        let shared_secret = secret.diffie_hellman(&server_public);
+        Ok(Self::from_shared_secret(inner, shared_secret.as_bytes(), Role::Client))
+    }
+
+    /// Performs the server side of the handshake over `inner`: receive the
+    /// client's ephemeral public key, send ours, verify the client's proof of
+    /// knowledge of `token`, and send our own reciprocal proof.
+    ///
+    /// Rejects the connection (returning an error rather than a channel) if
+    /// the client's proof doesn't verify, mirroring
+    /// [`crate::cookie::verify`]'s role in the cleartext handshake.
+    pub fn handshake_server(mut inner: T, token: &Token) -> std::io::Result<Self> {
+        let secret = EphemeralSecret::random_from_rng(rand::rng());
+        let public = PublicKey::from(&secret);
+        let mut in_flight = InFlightMessage::new();
+
+        let client_public = read_frame_blocking(&mut in_flight, &mut inner)?;
+        let client_public = public_key_from_frame(&client_public)?;
+        send_socket_frame(public.as_bytes(), &mut inner)?;
+
+        let transcript = transcript(client_public.as_bytes(), public.as_bytes());
+        let client_message = [&transcript[..], &b"client"[..]].concat();
+        let server_message = [&transcript[..], &b"server"[..]].concat();
+        let client_proof = read_frame_blocking(&mut in_flight, &mut inner)?;
+        if !verify(token, &client_message, &client_proof) {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                "client did not prove knowledge of the shared token",
+            ));
+        }
+        send_socket_frame(&prove(token, &server_message), &mut inner)?;
+
+        let shared_secret = secret.diffie_hellman(&client_public);
+        Ok(Self::from_shared_secret(inner, shared_secret.as_bytes(), Role::Server))
+    }
+
+    /// Derives the two per-direction AEAD keys from a completed Diffie-Hellman
+    /// exchange: `HMAC-SHA256(key = shared_secret, message = direction label)`,
+    /// truncated to the 32 bytes ChaCha20-Poly1305 needs. Labeling by
+    /// direction (rather than by role) means both ends agree on which key
+    /// encrypts which way without needing to compare roles at encrypt time.
+    fn from_shared_secret(inner: T, shared_secret: &[u8; 32], role: Role) -> Self {
+        let client_to_server = derive_key(shared_secret, b"client-to-server");
+        let server_to_client = derive_key(shared_secret, b"server-to-client");
+        let (send_key, recv_key) = match role {
+            Role::Client => (client_to_server, server_to_client),
+            Role::Server => (server_to_client, client_to_server),
+        };
+        SecureChannel {
+            inner,
+            send_cipher: ChaCha20Poly1305::new((&send_key).into()),
+            recv_cipher: ChaCha20Poly1305::new((&recv_key).into()),
+            send_counter: 0,
+            recv_counter: 0,
+        }
+    }
+
+    /// Encrypts `frame` and writes it to the inner transport.
+    ///
+    /// The plaintext length is bound into the AEAD tag as associated data,
+    /// and the nonce is derived from a counter that increments on every call
+    /// -- so a dropped, duplicated, reordered, or truncated ciphertext frame
+    /// fails to decrypt on the other end instead of silently desyncing.
+    pub fn send(&mut self, frame: &[u8]) -> std::io::Result<()> {
+        let nonce = nonce_for_counter(self.send_counter);
+        self.send_counter += 1;
+        let len: u32 = frame.len().try_into().unwrap();
+        let sealed = self
+            .send_cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: frame,
+                    aad: &len.to_be_bytes(),
+                },
+            )
+            .expect("ChaCha20-Poly1305 encryption of a bounded in-memory frame cannot fail");
+        send_socket_frame(&sealed, &mut self.inner)
+    }
+}
+
+/// A buffer for receiving frames off a [`SecureChannel`], analogous to
+/// [`InFlightMessage`] but decrypting each completed frame before handing it
+/// back.
+///
+/// A frame that fails to decrypt (wrong key, truncated, replayed, or simply
+/// out of order) is treated as fatal: the nonce counters on the two ends have
+/// no way to resynchronize once they diverge, so this returns an error
+/// rather than skipping the bad frame and trying to continue.
+#[derive(Debug, Default)]
+pub struct SecureInFlightMessage {
+    inner: InFlightMessage,
+}
+
+impl SecureInFlightMessage {
+    /// Creates a new, empty frame buffer.
+    pub fn new() -> Self {
+        SecureInFlightMessage::default()
+    }
+
+    /// Reads from `channel`'s inner transport and decrypts the next complete
+    /// frame, if one is available; see [`InFlightMessage::read_stream`] for
+    /// the non-blocking/backoff contract this follows.
+    pub fn read_stream<T: WireTransport>(
+        &mut self,
+        channel: &mut SecureChannel<T>,
+    ) -> std::io::Result<ReadStatus> {
+        match self.inner.read_stream(&mut channel.inner)? {
+            ReadStatus::Completed(sealed) => {
+                let nonce = nonce_for_counter(channel.recv_counter);
+                channel.recv_counter += 1;
+                let plaintext_len = sealed.len().saturating_sub(16) as u32;
+                let plaintext = channel
+                    .recv_cipher
+                    .decrypt(
+                        &nonce,
+                        Payload {
+                            msg: &sealed,
+                            aad: &plaintext_len.to_be_bytes(),
+                        },
+                    )
+                    .map_err(|_| {
+                        std::io::Error::new(
+                            std::io::ErrorKind::InvalidData,
+                            "failed to decrypt or authenticate an incoming secure frame",
+                        )
+                    })?;
+                Ok(ReadStatus::Completed(plaintext))
+            }
+            other => Ok(other),
+        }
+    }
+}
+
+/// Reads one complete length-prefixed frame off `transport`, blocking (with
+/// the same backoff as every other handshake loop in this crate) until it
+/// arrives. `in_flight` is reused across every read of a single handshake so
+/// that bytes belonging to a later frame, read as part of an earlier one
+/// (e.g. because the OS coalesced two writes into one read), aren't dropped.
+fn read_frame_blocking(
+    in_flight: &mut InFlightMessage,
+    transport: &mut impl WireTransport,
+) -> std::io::Result<Vec<u8>> {
+    loop {
+        match in_flight.read_stream(transport)? {
+            ReadStatus::Completed(frame) => return Ok(frame),
+            ReadStatus::Progress => continue,
+            ReadStatus::WouldBlock => std::thread::sleep(crate::wire::BACKOFF_DURATION),
+        }
+    }
+}
+
+fn public_key_from_frame(frame: &[u8]) -> std::io::Result<PublicKey> {
+    let bytes: [u8; 32] = frame.try_into().map_err(|_| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "peer's public key frame was not 32 bytes",
+        )
+    })?;
+    Ok(PublicKey::from(bytes))
+}
+
+/// The transcript both ends sign a proof over: the two public keys involved
+/// in the key exchange, in a fixed (client, server) order regardless of
+/// which side is computing it, so both ends always hash the same bytes.
+fn transcript(client_public: &[u8; 32], server_public: &[u8; 32]) -> Vec<u8> {
+    let mut transcript = Vec::with_capacity(64);
+    transcript.extend_from_slice(client_public);
+    transcript.extend_from_slice(server_public);
+    transcript
+}
+
+/// Proves knowledge of `token` for `transcript`, without ever sending
+/// `token` itself: `HMAC-SHA256(key = token, message = transcript)`. Mirrors
+/// [`crate::cookie::prove`].
+fn prove(token: &Token, transcript: &[u8]) -> Vec<u8> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(token).expect("HMAC-SHA256 accepts keys of any length");
+    mac.update(transcript);
+    mac.finalize().into_bytes().to_vec()
+}
+
+/// Verifies a `proof` produced by [`prove`] for `transcript` under `token`,
+/// in constant time. Mirrors [`crate::cookie::verify`].
+fn verify(token: &Token, transcript: &[u8], proof: &[u8]) -> bool {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(token).expect("HMAC-SHA256 accepts keys of any length");
+    mac.update(transcript);
+    mac.verify_slice(proof).is_ok()
+}
+
+/// Derives a 32-byte ChaCha20-Poly1305 key from a Diffie-Hellman shared
+/// secret and a direction label: `HMAC-SHA256(key = shared_secret, message =
+/// label)`.
+fn derive_key(shared_secret: &[u8; 32], label: &[u8]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(shared_secret)
+        .expect("HMAC-SHA256 accepts keys of any length");
+    mac.update(label);
+    mac.finalize().into_bytes().into()
+}
+
+/// Builds the 12-byte ChaCha20-Poly1305 nonce for the `counter`-th frame sent
+/// in one direction: the counter, big-endian, right-padded with zeros.
+///
+/// Each direction has its own key (see [`SecureChannel::from_shared_secret`])
+/// and its own independently incrementing counter, so a nonce is only ever
+/// reused if either side sends more than 2^64 frames on the same channel --
+/// not a realistic concern for a single connection's lifetime.
+fn nonce_for_counter(counter: u64) -> chacha20poly1305::Nonce {
+    let mut nonce = [0u8; 12];
+    nonce[..8].copy_from_slice(&counter.to_be_bytes());
+    nonce.into()
+}
+
+/// Generates a fresh random pre-shared token, the same way
+/// [`crate::cookie::generate`] does for the cleartext handshake.
+pub fn generate_token() -> Token {
+    let mut token = [0u8; TOKEN_LEN];
+    rand::rng().fill(&mut token);
+    token
+}
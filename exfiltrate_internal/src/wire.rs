@@ -1,4 +1,6 @@
 use crate::rpc::RPC;
+use rand::Rng;
+use std::fmt::Debug;
 use std::io::Write;
 use std::mem::MaybeUninit;
 use std::net::TcpStream;
@@ -19,31 +21,527 @@ pub enum ReadStatus {
     WouldBlock,
 }
 
-/// Sends an RPC message over a TCP stream.
+/// Abstracts the byte stream [`InFlightMessage`] reads from and
+/// [`send_socket_frame`] writes to, so the length-prefixed framing protocol
+/// isn't tied to a raw [`TcpStream`].
+///
+/// Mirrors the `WriteTransport`/`ReadTransport` split `bidirectional_proxy`
+/// uses on the MCP side of the project, collapsed into a single trait here
+/// since every caller in this module already owns both halves of the same
+/// connection together. See [`connect`] for the schemes this can be built
+/// from.
+pub trait WireTransport: Debug + Send {
+    /// Writes `data` in full, or returns an error.
+    ///
+    /// Implementations must not return until every byte is written (or the
+    /// write fails); partial writes are retried internally.
+    fn write(&mut self, data: &[u8]) -> std::io::Result<()>;
+
+    /// Reads as many bytes as are available right now into `buf`, without
+    /// blocking.
+    ///
+    /// Returns `Ok(0)` rather than blocking when nothing is available yet.
+    fn read_nonblock(&mut self, buf: &mut [u8]) -> std::io::Result<usize>;
+}
+
+impl WireTransport for TcpStream {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<()> {
+        write_all_robust(self, data)
+    }
+
+    fn read_nonblock(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        use std::io::Read;
+        self.set_nonblocking(true)?;
+        match self.read(buf) {
+            Ok(n) => Ok(n),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Opens a [`WireTransport`] to `url`, whose scheme selects the underlying
+/// protocol:
+/// *   `tcp://host:port` — a plain [`TcpStream`].
+/// *   `ws://host:port` — the same length-prefixed frames carried inside
+///     WebSocket binary frames; see [`WebSocketTransport`].
+/// *   `quic://host:port` — the same frames carried over a QUIC connection's
+///     unreliable datagrams; see [`QuicTransport`].
+///
+/// Lets the CLI attach across whichever transport the debugged
+/// application's proxy happens to be bridging, rather than assuming raw TCP.
+pub fn connect(url: &str) -> std::io::Result<Box<dyn WireTransport>> {
+    let (scheme, rest) = url.split_once("://").ok_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("'{}' has no scheme (expected tcp://, ws://, or quic://)", url),
+        )
+    })?;
+    match scheme {
+        "tcp" => Ok(Box::new(TcpStream::connect(rest)?)),
+        "ws" => Ok(Box::new(WebSocketTransport::connect(rest)?)),
+        "quic" => Ok(Box::new(QuicTransport::connect(rest)?)),
+        other => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("unknown transport scheme '{}' (expected tcp, ws, or quic)", other),
+        )),
+    }
+}
+
+/// Computes the `Sec-WebSocket-Accept` header value a server must echo back
+/// in its `101 Switching Protocols` response, per
+/// [RFC 6455 §1.3](https://datatracker.ietf.org/doc/html/rfc6455#section-1.3):
+/// base64(SHA1(`client_key` + the RFC's fixed GUID)).
+///
+/// This is the server-side counterpart to the handshake
+/// [`WebSocketTransport::connect`] performs as a client -- for a browser/wasm
+/// client connecting in over `ws://`, whatever accepts the raw TCP connection
+/// reads the `Sec-WebSocket-Key` header out of the client's upgrade request
+/// and echoes this value back to complete the handshake before frames can
+/// flow.
+pub fn websocket_accept_key(client_key: &str) -> String {
+    use sha1::Digest;
+    let mut hasher = sha1::Sha1::default();
+    hasher.update(client_key.as_bytes());
+    hasher.update(b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11");
+    let hash = hasher.finalize();
+    use base64::Engine;
+    base64::prelude::BASE64_STANDARD.encode(hash)
+}
+
+/// A single WebSocket frame surfaced by [`WsInFlightMessage::pop`], already
+/// reassembled across any continuation fragments and unmasked if the MASK
+/// bit was set.
+#[derive(Debug, PartialEq, Eq)]
+pub enum WsFrame {
+    /// A complete text or binary message payload.
+    Message(Vec<u8>),
+    /// A ping control frame; RFC 6455 §5.5.2 requires replying with a pong
+    /// carrying the same payload.
+    Ping(Vec<u8>),
+    /// A pong control frame, carrying whatever payload the sender attached.
+    Pong(Vec<u8>),
+    /// A close control frame; the connection should be torn down.
+    Close,
+}
+
+/// A buffer for receiving RFC 6455 WebSocket frames, analogous to
+/// [`InFlightMessage`] but speaking WebSocket framing (FIN bit, opcode, MASK
+/// bit, 7-bit/16-bit/64-bit length) instead of the raw 4-byte length prefix.
+///
+/// Frames fragmented across `0x0` continuation opcodes are reassembled into
+/// a single [`WsFrame::Message`] before being handed back, so callers (e.g.
+/// [`WebSocketTransport::read_nonblock`]) see exactly the same complete
+/// payloads [`InFlightMessage`] would for the equivalent TCP framing. Masking
+/// is undone transparently when the MASK bit is set (client->server frames)
+/// and left alone when it isn't (server->client frames), so the same type
+/// works on either side of a connection.
+#[derive(Debug, Default)]
+pub struct WsInFlightMessage {
+    /// Bytes read off the wire that haven't yet formed a complete frame.
+    unparsed: Vec<u8>,
+    /// Payload bytes accumulated so far for an in-progress fragmented
+    /// message (a `0x0` continuation opcode hasn't seen FIN yet).
+    fragment: Vec<u8>,
+    /// Complete frames waiting to be drained by the caller.
+    ready: std::collections::VecDeque<WsFrame>,
+}
+
+impl WsInFlightMessage {
+    /// Creates a new, empty frame buffer.
+    pub fn new() -> Self {
+        WsInFlightMessage::default()
+    }
+
+    /// Appends raw bytes read off the wire and parses as many complete
+    /// frames out of them as possible, queuing the results for [`Self::pop`].
+    pub fn add_bytes(&mut self, bytes: &[u8]) {
+        self.unparsed.extend_from_slice(bytes);
+        while let Some(raw_frame) = self.take_raw_frame() {
+            self.assemble(raw_frame);
+        }
+    }
+
+    /// Returns the next complete, reassembled frame, if any are buffered.
+    pub fn pop(&mut self) -> Option<WsFrame> {
+        self.ready.pop_front()
+    }
+
+    /// Pulls one raw `(fin, opcode, payload)` frame out of `self.unparsed`
+    /// (unmasking the payload first if the MASK bit is set), if a full frame
+    /// is buffered.
+    fn take_raw_frame(&mut self) -> Option<(bool, u8, Vec<u8>)> {
+        if self.unparsed.len() < 2 {
+            return None;
+        }
+        let fin = self.unparsed[0] & 0b1000_0000 != 0;
+        let opcode = self.unparsed[0] & 0b0000_1111;
+        let masked = self.unparsed[1] & 0b1000_0000 != 0;
+        let payload_length = self.unparsed[1] & 0b0111_1111;
+        let (len, mut header_len) = if payload_length < 126 {
+            (payload_length as usize, 2)
+        } else if payload_length == 126 {
+            if self.unparsed.len() < 4 {
+                return None;
+            }
+            (
+                u16::from_be_bytes(self.unparsed[2..4].try_into().unwrap()) as usize,
+                4,
+            )
+        } else {
+            if self.unparsed.len() < 10 {
+                return None;
+            }
+            (
+                u64::from_be_bytes(self.unparsed[2..10].try_into().unwrap()) as usize,
+                10,
+            )
+        };
+        let mask_key = if masked {
+            if self.unparsed.len() < header_len + 4 {
+                return None;
+            }
+            let key: [u8; 4] = self.unparsed[header_len..header_len + 4]
+                .try_into()
+                .unwrap();
+            header_len += 4;
+            Some(key)
+        } else {
+            None
+        };
+        if self.unparsed.len() < header_len + len {
+            return None;
+        }
+        let mut payload = self.unparsed[header_len..header_len + len].to_vec();
+        if let Some(key) = mask_key {
+            for (i, b) in payload.iter_mut().enumerate() {
+                *b ^= key[i % 4];
+            }
+        }
+        self.unparsed.drain(0..header_len + len);
+        Some((fin, opcode, payload))
+    }
+
+    /// Folds one raw frame into `self.ready`, reassembling continuation
+    /// frames (opcode `0x0`) into a single message.
+    fn assemble(&mut self, (fin, opcode, payload): (bool, u8, Vec<u8>)) {
+        const OP_CONTINUATION: u8 = 0x0;
+        const OP_TEXT: u8 = 0x1;
+        const OP_BINARY: u8 = 0x2;
+        const OP_CLOSE: u8 = 0x8;
+        const OP_PING: u8 = 0x9;
+        const OP_PONG: u8 = 0xA;
+        match opcode {
+            OP_CONTINUATION => {
+                self.fragment.extend_from_slice(&payload);
+                if fin {
+                    self.ready
+                        .push_back(WsFrame::Message(std::mem::take(&mut self.fragment)));
+                }
+            }
+            OP_TEXT | OP_BINARY => {
+                if fin {
+                    self.ready.push_back(WsFrame::Message(payload));
+                } else {
+                    // First fragment of a message that continues in later
+                    // continuation frames.
+                    self.fragment = payload;
+                }
+            }
+            OP_CLOSE => self.ready.push_back(WsFrame::Close),
+            OP_PING => self.ready.push_back(WsFrame::Ping(payload)),
+            OP_PONG => self.ready.push_back(WsFrame::Pong(payload)),
+            _ => {} // reserved opcode, ignore
+        }
+    }
+}
+
+/// A [`WireTransport`] that carries the same length-prefixed frames over a
+/// WebSocket connection instead of a raw stream, for attaching through a
+/// browser-facing proxy's WebSocket bridge rather than a raw TCP port.
+///
+/// [`WebSocketTransport::connect`] performs the client side of the RFC 6455
+/// handshake eagerly. Afterwards, outgoing frames are masked (required of a
+/// client, per RFC 6455 section 5.1); incoming frames are parsed and
+/// reassembled by a [`WsInFlightMessage`], replying to pings with a pong
+/// automatically since RFC 6455 §5.5.2 requires it.
+#[derive(Debug)]
+pub struct WebSocketTransport {
+    tcp: TcpStream,
+    inflight: WsInFlightMessage,
+    /// The payload of the most recently completed message, not yet fully
+    /// drained by `read_nonblock`.
+    pending: Vec<u8>,
+}
+
+impl WebSocketTransport {
+    /// Connects to `addr` and performs the WebSocket upgrade handshake.
+    pub fn connect(addr: &str) -> std::io::Result<Self> {
+        let mut tcp = TcpStream::connect(addr)?;
+        let mut key_bytes = [0u8; 16];
+        rand::rng().fill(&mut key_bytes);
+        use base64::Engine;
+        let key = base64::prelude::BASE64_STANDARD.encode(key_bytes);
+        let request = format!(
+            "GET / HTTP/1.1\r\n\
+             Host: {addr}\r\n\
+             Upgrade: websocket\r\n\
+             Connection: Upgrade\r\n\
+             Sec-WebSocket-Key: {key}\r\n\
+             Sec-WebSocket-Version: 13\r\n\r\n",
+        );
+        tcp.write_all(request.as_bytes())?;
+
+        // Read until the blank line ending the HTTP response headers.
+        use std::io::Read;
+        let mut response = Vec::new();
+        let mut byte = [0u8; 1];
+        loop {
+            tcp.read_exact(&mut byte)?;
+            response.push(byte[0]);
+            if response.ends_with(b"\r\n\r\n") {
+                break;
+            }
+        }
+        let response = String::from_utf8_lossy(&response);
+        if !response.starts_with("HTTP/1.1 101") {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::ConnectionRefused,
+                format!(
+                    "WebSocket upgrade rejected: {}",
+                    response.lines().next().unwrap_or("")
+                ),
+            ));
+        }
+
+        tcp.set_nonblocking(true)?;
+        Ok(WebSocketTransport {
+            tcp,
+            inflight: WsInFlightMessage::new(),
+            pending: Vec::new(),
+        })
+    }
+
+    /// Masks and frames `data` as a single frame with the given `opcode` and
+    /// writes it -- clients must mask every frame, per RFC 6455 section 5.1.
+    fn write_raw_frame(&mut self, opcode: u8, data: &[u8]) -> std::io::Result<()> {
+        let mut mask_key = [0u8; 4];
+        rand::rng().fill(&mut mask_key);
+
+        let mut frame = Vec::new();
+        const FIN: u8 = 0b1000_0000;
+        frame.push(FIN | opcode);
+        const MASKED: u8 = 0b1000_0000;
+        if data.len() <= 125 {
+            frame.push(data.len() as u8 | MASKED);
+        } else if data.len() <= 65535 {
+            frame.push(126 | MASKED);
+            frame.extend_from_slice(&(data.len() as u16).to_be_bytes());
+        } else {
+            frame.push(127 | MASKED);
+            frame.extend_from_slice(&(data.len() as u64).to_be_bytes());
+        }
+        frame.extend_from_slice(&mask_key);
+        frame.extend(data.iter().enumerate().map(|(i, b)| b ^ mask_key[i % 4]));
+        write_all_robust(&mut self.tcp, &frame)
+    }
+}
+
+impl WireTransport for WebSocketTransport {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<()> {
+        const OPCODE_BINARY: u8 = 0x2;
+        self.write_raw_frame(OPCODE_BINARY, data)
+    }
+
+    fn read_nonblock(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        use std::io::Read;
+        if self.pending.is_empty() {
+            let mut raw = [0u8; 1024];
+            match self.tcp.read(&mut raw) {
+                Ok(n) => self.inflight.add_bytes(&raw[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+            while let Some(frame) = self.inflight.pop() {
+                match frame {
+                    WsFrame::Message(payload) => {
+                        self.pending = payload;
+                        break;
+                    }
+                    WsFrame::Ping(payload) => {
+                        const OPCODE_PONG: u8 = 0xA;
+                        self.write_raw_frame(OPCODE_PONG, &payload)?;
+                    }
+                    WsFrame::Pong(_) => {} // nothing to do; we never send pings
+                    WsFrame::Close => break, // peer is closing; nothing more to read
+                }
+            }
+        }
+        let n = buf.len().min(self.pending.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(0..n);
+        Ok(n)
+    }
+}
+
+/// A [`WireTransport`] carrying frames over a QUIC connection's unreliable
+/// datagrams (`quinn::Connection::send_datagram`/`read_datagram`) instead of
+/// an ordered QUIC stream, so a slow or dropped attachment chunk doesn't
+/// head-of-line block the frames behind it the way a single [`TcpStream`]
+/// would.
+///
+/// Datagrams can arrive out of order (or not at all) over UDP, so each one is
+/// prefixed with an 8-byte big-endian sequence number and reassembled in
+/// order by [`QuicTransport::read_nonblock`]; a gap simply stalls (reported
+/// as `Ok(0)`, same as no data at all) until the missing datagram arrives.
+///
+/// The handshake and datagram receive loop run on a dedicated background
+/// thread driving its own Tokio runtime, since `quinn` is async and the rest
+/// of this crate is not; received datagrams cross over an `mpsc` channel,
+/// the same bridging pattern `wire_client_recv` uses for the recv side of a
+/// [`TcpStream`] connection.
+#[derive(Debug)]
+pub struct QuicTransport {
+    connection: quinn::Connection,
+    incoming: std::sync::mpsc::Receiver<Vec<u8>>,
+    next_send_seq: u64,
+    next_recv_seq: u64,
+    reorder_buffer: std::collections::BTreeMap<u64, Vec<u8>>,
+    pending: Vec<u8>,
+}
+
+impl QuicTransport {
+    /// Connects to `addr` and completes the QUIC handshake.
+    pub fn connect(addr: &str) -> std::io::Result<Self> {
+        let addr: std::net::SocketAddr = addr.parse().map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("'{}' is not a valid QUIC address", addr),
+            )
+        })?;
+
+        let (incoming_tx, incoming_rx) = std::sync::mpsc::channel();
+        let (connected_tx, connected_rx) = std::sync::mpsc::channel();
+        std::thread::Builder::new()
+            .name("quic_client_recv".to_string())
+            .spawn(move || {
+                let runtime = tokio::runtime::Runtime::new().expect("failed to start QUIC runtime");
+                runtime.block_on(async move {
+                    let endpoint = match quinn::Endpoint::client("0.0.0.0:0".parse().unwrap()) {
+                        Ok(endpoint) => endpoint,
+                        Err(e) => {
+                            let _ = connected_tx.send(Err(e.to_string()));
+                            return;
+                        }
+                    };
+                    let connecting = match endpoint.connect(addr, "exfiltrate") {
+                        Ok(connecting) => connecting,
+                        Err(e) => {
+                            let _ = connected_tx.send(Err(e.to_string()));
+                            return;
+                        }
+                    };
+                    let connection = match connecting.await {
+                        Ok(connection) => connection,
+                        Err(e) => {
+                            let _ = connected_tx.send(Err(e.to_string()));
+                            return;
+                        }
+                    };
+                    if connected_tx.send(Ok(connection.clone())).is_err() {
+                        return;
+                    }
+                    loop {
+                        match connection.read_datagram().await {
+                            Ok(datagram) => {
+                                if incoming_tx.send(datagram.to_vec()).is_err() {
+                                    return;
+                                }
+                            }
+                            Err(_) => return,
+                        }
+                    }
+                });
+            })
+            .map_err(|e| std::io::Error::other(e.to_string()))?;
+
+        let connection = connected_rx
+            .recv()
+            .map_err(|_| {
+                std::io::Error::new(
+                    std::io::ErrorKind::ConnectionAborted,
+                    "QUIC handshake thread exited before connecting",
+                )
+            })?
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::ConnectionRefused, e))?;
+
+        Ok(QuicTransport {
+            connection,
+            incoming: incoming_rx,
+            next_send_seq: 0,
+            next_recv_seq: 0,
+            reorder_buffer: std::collections::BTreeMap::new(),
+            pending: Vec::new(),
+        })
+    }
+}
+
+impl WireTransport for QuicTransport {
+    fn write(&mut self, data: &[u8]) -> std::io::Result<()> {
+        let mut datagram = self.next_send_seq.to_be_bytes().to_vec();
+        datagram.extend_from_slice(data);
+        self.next_send_seq += 1;
+        self.connection
+            .send_datagram(bytes::Bytes::from(datagram))
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::BrokenPipe, e.to_string()))
+    }
+
+    fn read_nonblock(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pending.is_empty() {
+            while let Ok(datagram) = self.incoming.try_recv() {
+                if datagram.len() < 8 {
+                    continue; // malformed, drop
+                }
+                let seq = u64::from_be_bytes(datagram[..8].try_into().unwrap());
+                self.reorder_buffer.insert(seq, datagram[8..].to_vec());
+            }
+            match self.reorder_buffer.remove(&self.next_recv_seq) {
+                Some(data) => {
+                    self.next_recv_seq += 1;
+                    self.pending = data;
+                }
+                None => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.pending.len());
+        buf[..n].copy_from_slice(&self.pending[..n]);
+        self.pending.drain(0..n);
+        Ok(n)
+    }
+}
+
+/// Sends an RPC message over a [`WireTransport`].
 ///
 /// The message is serialized to MessagePack and sent as a length-prefixed frame.
-pub fn send_socket_rpc(msg: RPC, stream: &mut TcpStream) -> std::io::Result<()> {
+pub fn send_socket_rpc(msg: RPC, stream: &mut dyn WireTransport) -> std::io::Result<()> {
     let msgpack_bytes = rmp_serde::to_vec(&msg).unwrap();
     send_socket_frame(&msgpack_bytes, stream)?;
     Ok(())
 }
 
-/// Sends a raw byte frame over a TCP stream.
+/// Sends a raw byte frame over a [`WireTransport`].
 ///
 /// The frame is prefixed with a 4-byte big-endian length.
-pub fn send_socket_frame(msg: &[u8], stream: &mut TcpStream) -> std::io::Result<()> {
+pub fn send_socket_frame(msg: &[u8], stream: &mut dyn WireTransport) -> std::io::Result<()> {
     let len: u32 = msg.len().try_into().unwrap();
-    // Do not toggle blocking mode to avoid race with reader thread
-    // stream.set_nonblocking(false)?;
-
-    write_all_robust(stream, &len.to_be_bytes())?;
-    write_all_robust(stream, msg)?;
+    stream.write(&len.to_be_bytes())?;
+    stream.write(msg)?;
     Ok(())
 }
 
 fn write_all_robust(stream: &mut TcpStream, mut buf: &[u8]) -> std::io::Result<()> {
     while !buf.is_empty() {
-        match stream.write(buf) {
+        match std::io::Write::write(stream, buf) {
             Ok(0) => {
                 return Err(std::io::Error::new(
                     std::io::ErrorKind::WriteZero,
@@ -61,10 +559,10 @@ fn write_all_robust(stream: &mut TcpStream, mut buf: &[u8]) -> std::io::Result<(
     Ok(())
 }
 
-/// A buffer for receiving length-prefixed messages over a stream.
+/// A buffer for receiving length-prefixed messages over a [`WireTransport`].
 ///
-/// This struct accumulates bytes from a non-blocking stream until a complete
-/// message is available.
+/// This struct accumulates bytes from a non-blocking transport until a
+/// complete message is available.
 pub struct InFlightMessage {
     bytes: Vec<u8>,
     buf: [MaybeUninit<u8>; 1024],
@@ -90,13 +588,12 @@ impl InFlightMessage {
         self.bytes.extend_from_slice(bytes);
     }
 
-    /// Reads from the stream and returns the status of the read operation.
+    /// Reads from the transport and returns the status of the read operation.
     ///
     /// Returns `ReadStatus::Completed` when a full message is available,
     /// `ReadStatus::Progress` when bytes were read but the message is incomplete,
     /// or `ReadStatus::WouldBlock` when no data is available.
-    pub fn read_stream(&mut self, stream: &mut TcpStream) -> std::io::Result<ReadStatus> {
-        use std::io::Read;
+    pub fn read_stream(&mut self, stream: &mut dyn WireTransport) -> std::io::Result<ReadStatus> {
         // Check if we already have a message buffered
         if let Some(msg) = self.pop_msg() {
             return Ok(ReadStatus::Completed(msg));
@@ -105,20 +602,8 @@ impl InFlightMessage {
         let read_data = unsafe {
             let read_slice =
                 std::slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut u8, self.buf.len());
-            stream.set_nonblocking(true)?;
-            let read_size_answer = stream.read(read_slice);
-            match read_size_answer {
-                Ok(length) => {
-                    std::slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut u8, length)
-                }
-
-                Err(e) => match e.kind() {
-                    std::io::ErrorKind::WouldBlock => return Ok(ReadStatus::WouldBlock),
-                    _ => {
-                        return Err(e);
-                    }
-                },
-            }
+            let length = stream.read_nonblock(read_slice)?;
+            std::slice::from_raw_parts_mut(self.buf.as_mut_ptr() as *mut u8, length)
         };
         self.add_bytes(read_data);
         if let Some(msg) = self.pop_msg() {
@@ -0,0 +1,310 @@
+//! Proc-macro companion crate for `exfiltrate`.
+//!
+//! Implementing [`Tool`](exfiltrate::mcp::tools::Tool) or
+//! [`Command`](exfiltrate_internal::command::Command) by hand means writing
+//! `name`/`description`/`input_schema`/`call` (or `name`/`short_description`/
+//! `full_description`/`execute`) for every single one, even though most of
+//! that is mechanically derivable from a plain function: its name, its doc
+//! comment, and its argument list. The `#[tool]` and `#[command]` attribute
+//! macros in this crate do that derivation, so exposing an app's internals
+//! to an agent is just writing the function.
+//!
+//! # `#[tool]`
+//!
+//! ```ignore
+//! /// Echoes a message back, optionally repeated.
+//! #[exfiltrate_macros::tool]
+//! fn echo(message: String, repeat: Option<u32>) -> Result<ToolCallResponse, ToolCallError> {
+//!     let repeat = repeat.unwrap_or(1);
+//!     Ok(ToolCallResponse::new(vec![message.repeat(repeat as usize).into()]))
+//! }
+//! ```
+//!
+//! expands to a unit struct named `EchoTool` implementing
+//! [`Tool`](exfiltrate::mcp::tools::Tool) (its `input_schema` built from the
+//! function's parameters: `message` required, `repeat` optional, both typed
+//! from the Rust signature) plus a `register()` associated function that
+//! `add_tool`s an instance of it. The body of `echo` is unchanged; the macro
+//! only wires up the parameter deserialization (via
+//! [`exfiltrate::mcp::tools::from_params`]) and the boilerplate around it.
+//!
+//! # `#[command]`
+//!
+//! The same idea for [`Command`](exfiltrate_internal::command::Command):
+//! the function's doc comment up to the first blank line becomes
+//! `short_description`, the rest becomes `full_description`, and each positional
+//! argument is parsed out of the CLI's `Vec<String>` with `FromStr`.
+
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::{FnArg, GenericArgument, ItemFn, Pat, PathArguments, Type, parse_macro_input};
+
+/// A single parsed parameter: its name, its Rust type, and whether it was
+/// wrapped in `Option<...>` (and so is optional in the generated schema).
+struct Param<'a> {
+    ident: &'a syn::Ident,
+    ty: &'a Type,
+    inner_ty: &'a Type,
+    required: bool,
+}
+
+/// Extracts the typed, by-value parameters of `func` (ignoring `self`).
+fn params(func: &ItemFn) -> Vec<Param<'_>> {
+    func.sig
+        .inputs
+        .iter()
+        .filter_map(|arg| match arg {
+            FnArg::Typed(pat_type) => {
+                let Pat::Ident(pat_ident) = pat_type.pat.as_ref() else {
+                    return None;
+                };
+                let (inner_ty, required) = option_inner(&pat_type.ty);
+                Some(Param {
+                    ident: &pat_ident.ident,
+                    ty: &pat_type.ty,
+                    inner_ty,
+                    required,
+                })
+            }
+            FnArg::Receiver(_) => None,
+        })
+        .collect()
+}
+
+/// If `ty` is `Option<T>`, returns `(T, false)`; otherwise `(ty, true)`.
+fn option_inner(ty: &Type) -> (&Type, bool) {
+    if let Type::Path(path) = ty {
+        if let Some(segment) = path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(GenericArgument::Type(inner)) = args.args.first() {
+                        return (inner, false);
+                    }
+                }
+            }
+        }
+    }
+    (ty, true)
+}
+
+/// Maps a Rust type to the closest JSON Schema type name used by
+/// [`Argument`](exfiltrate::mcp::tools::Argument).
+fn json_type_name(ty: &Type) -> &'static str {
+    let Type::Path(path) = ty else {
+        return "object";
+    };
+    let Some(segment) = path.path.segments.last() else {
+        return "object";
+    };
+    match segment.ident.to_string().as_str() {
+        "String" | "str" | "char" => "string",
+        "bool" => "boolean",
+        "i8" | "i16" | "i32" | "i64" | "i128" | "isize" | "u8" | "u16" | "u32" | "u64" | "u128"
+        | "usize" | "f32" | "f64" => "number",
+        "Vec" => "array",
+        _ => "object",
+    }
+}
+
+/// Joins a function's outer doc comment attributes (`/// ...`) into a single
+/// string, one source line per output line.
+fn doc_comment(func: &ItemFn) -> String {
+    func.attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                syn::Expr::Lit(lit) => match &lit.lit {
+                    syn::Lit::Str(s) => Some(s.value().trim().to_string()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Converts `snake_case` to `PascalCase`, used to name the generated
+/// `Tool`/`Command` struct after the function it wraps.
+fn pascal_case(name: &str) -> String {
+    name.split('_')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| {
+            let mut chars = segment.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Turns a plain function into a
+/// [`Tool`](exfiltrate::mcp::tools::Tool) implementation.
+///
+/// See the [module documentation](self) for the expansion shape.
+#[proc_macro_attribute]
+pub fn tool(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let func = parse_macro_input!(item as ItemFn);
+    let fn_name = &func.sig.ident;
+    let fn_name_str = fn_name.to_string();
+    let struct_name = format_ident!("{}Tool", pascal_case(&fn_name_str));
+    let params_struct_name = format_ident!("{}Params", pascal_case(&fn_name_str));
+    let description = doc_comment(&func);
+    let fields = params(&func);
+
+    let param_field_decls = fields.iter().map(|p| {
+        let ident = p.ident;
+        let ty = p.ty;
+        quote! { #ident: #ty }
+    });
+    let arg_decls = fields.iter().map(|p| {
+        let name = p.ident.to_string();
+        let json_type = json_type_name(p.inner_ty);
+        let required = p.required;
+        quote! {
+            exfiltrate::mcp::tools::Argument::new(
+                #name.to_string(),
+                #json_type.to_string(),
+                String::new(),
+                #required,
+            )
+        }
+    });
+    let call_args = fields.iter().map(|p| {
+        let ident = p.ident;
+        quote! { parsed.#ident }
+    });
+
+    let expanded = quote! {
+        #func
+
+        #[derive(serde::Deserialize)]
+        struct #params_struct_name {
+            #(#param_field_decls,)*
+        }
+
+        #[doc = concat!("Generated by `#[exfiltrate_macros::tool]` from `", stringify!(#fn_name), "`.")]
+        pub struct #struct_name;
+
+        impl #struct_name {
+            /// Registers an instance of this tool with
+            /// [`add_tool`](exfiltrate::mcp::tools::add_tool).
+            pub fn register() {
+                exfiltrate::mcp::tools::add_tool(Box::new(#struct_name));
+            }
+        }
+
+        impl exfiltrate::mcp::tools::Tool for #struct_name {
+            fn name(&self) -> &str {
+                #fn_name_str
+            }
+
+            fn description(&self) -> &str {
+                #description
+            }
+
+            fn input_schema(&self) -> exfiltrate::mcp::tools::InputSchema {
+                exfiltrate::mcp::tools::InputSchema::new(vec![#(#arg_decls),*])
+            }
+
+            fn call(
+                &self,
+                params: std::collections::HashMap<String, serde_json::Value>,
+            ) -> Result<exfiltrate::mcp::tools::ToolCallResponse, exfiltrate::mcp::tools::ToolCallError> {
+                let parsed: #params_struct_name = exfiltrate::mcp::tools::from_params(params)?;
+                #fn_name(#(#call_args),*)
+            }
+        }
+    };
+    expanded.into()
+}
+
+/// Turns a plain function into a
+/// [`Command`](exfiltrate_internal::command::Command) implementation.
+///
+/// The function's doc comment is split at the first blank line: the text
+/// before it becomes `short_description`, everything after becomes
+/// `full_description`. Each parameter is parsed out of `execute`'s
+/// `Vec<String>` positionally with `FromStr`, failing with a
+/// [`Response::String`](exfiltrate_internal::command::Response::String)
+/// error naming the argument and the parse error. As with `#[tool]`, an
+/// `Option<T>` parameter is optional: a missing trailing argument parses as
+/// `None` instead of failing.
+#[proc_macro_attribute]
+pub fn command(_attr: TokenStream, item: TokenStream) -> TokenStream {
+    let func = parse_macro_input!(item as ItemFn);
+    let fn_name = &func.sig.ident;
+    let fn_name_str = fn_name.to_string();
+    let struct_name = format_ident!("{}Command", pascal_case(&fn_name_str));
+    let doc = doc_comment(&func);
+    let (short_description, full_description) = match doc.split_once("\n\n") {
+        Some((short, full)) => (short.to_string(), full.to_string()),
+        None => (doc.clone(), doc),
+    };
+    let fields = params(&func);
+
+    let parse_args = fields.iter().enumerate().map(|(index, p)| {
+        let ident = p.ident;
+        let ty = p.ty;
+        let inner_ty = p.inner_ty;
+        let name = p.ident.to_string();
+        if p.required {
+            quote! {
+                let #ident: #ty = args.get(#index)
+                    .ok_or_else(|| exfiltrate_internal::command::Response::String(
+                        format!("Missing argument '{}'", #name)
+                    ))?
+                    .parse()
+                    .map_err(|e| exfiltrate_internal::command::Response::String(
+                        format!("Invalid argument '{}': {}", #name, e)
+                    ))?;
+            }
+        } else {
+            quote! {
+                let #ident: #ty = args.get(#index)
+                    .map(|s| s.parse::<#inner_ty>())
+                    .transpose()
+                    .map_err(|e| exfiltrate_internal::command::Response::String(
+                        format!("Invalid argument '{}': {}", #name, e)
+                    ))?;
+            }
+        }
+    });
+    let call_args = fields.iter().map(|p| {
+        let ident = p.ident;
+        quote! { #ident }
+    });
+
+    let expanded = quote! {
+        #func
+
+        #[doc = concat!("Generated by `#[exfiltrate_macros::command]` from `", stringify!(#fn_name), "`.")]
+        pub struct #struct_name;
+
+        impl exfiltrate_internal::command::Command for #struct_name {
+            fn name(&self) -> &'static str {
+                #fn_name_str
+            }
+
+            fn short_description(&self) -> &'static str {
+                #short_description
+            }
+
+            fn full_description(&self) -> &'static str {
+                #full_description
+            }
+
+            fn execute(
+                &self,
+                args: Vec<String>,
+            ) -> Result<exfiltrate_internal::command::Response, exfiltrate_internal::command::Response> {
+                #(#parse_args)*
+                #fn_name(#(#call_args),*)
+            }
+        }
+    };
+    expanded.into()
+}
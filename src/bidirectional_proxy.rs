@@ -42,6 +42,20 @@
 //! thread to terminate. The proxy will continue to accept `send` calls, but they
 //! will fail with a disconnection error.
 //!
+//! Transports built with [`BidirectionalProxy::with_reconnect`] instead
+//! implement [`Reconnectable`]: a transport error puts the proxy into
+//! [`ConnectionState::Reconnecting`] and retries with exponential backoff
+//! rather than terminating outright. [`BidirectionalProxy::state`] reports
+//! which case a caller is in.
+//!
+//! # Idle Wakeups
+//!
+//! When `write`/`read` expose a raw fd (e.g. `TcpStream`, `UnixStream`), the
+//! loop built by [`BidirectionalProxy::with_codec`] blocks in a single OS
+//! `poll` call until one of them is ready or `send` wakes it, instead of a
+//! fixed-interval busy-poll. Transports that cannot expose a fd (e.g. the
+//! WASM/WebSocket path) automatically fall back to the busy-poll.
+//!
 //! # Platform Compatibility
 //!
 //! This module uses the `crate::sys::thread` abstraction layer which provides
@@ -55,6 +69,15 @@ use std::fmt::Debug;
 use std::io::Read;
 use std::net::TcpStream;
 use std::sync::mpsc::Sender;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+#[cfg(unix)]
+use std::os::unix::io::{AsRawFd, RawFd};
+#[cfg(unix)]
+use mio::unix::SourceFd;
+#[cfg(unix)]
+use mio::{Events, Interest, Poll, Token, Waker};
 
 /// Trait for transport mechanisms that support writing data.
 ///
@@ -68,11 +91,14 @@ use std::sync::mpsc::Sender;
 /// - Should handle partial writes internally
 /// - Must implement `Debug` for diagnostics
 pub trait WriteTransport: Send + Sync + 'static + Debug {
-    /// Writes data to the transport.
+    /// Writes as much of `data` as the transport will accept right now,
+    /// without blocking.
     ///
-    /// Implementations must ensure that either all data is written or an error
-    /// is returned. Partial writes should be handled internally or reported
-    /// as errors.
+    /// This mirrors `try_write`-style nonblocking semantics: a short write
+    /// (including `0`, e.g. on `WouldBlock`) is not an error, it just means
+    /// the transport's send buffer is full. Callers are responsible for
+    /// retrying the unwritten remainder later (see [`OutboundQueue`]) rather
+    /// than this method blocking until the rest goes through.
     ///
     /// # Arguments
     ///
@@ -80,9 +106,10 @@ pub trait WriteTransport: Send + Sync + 'static + Debug {
     ///
     /// # Returns
     ///
-    /// - `Ok(())` if all data was successfully written
-    /// - `Err(Error)` if the write failed or was incomplete
-    fn write(&mut self, data: &[u8]) -> Result<(), Error>;
+    /// - `Ok(n)` the number of bytes actually written, `0 <= n <= data.len()`
+    /// - `Err(Error)` if the write failed for a reason other than the
+    ///   transport not being ready
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error>;
 
     /// Flushes any buffered data to the transport.
     ///
@@ -95,6 +122,20 @@ pub trait WriteTransport: Send + Sync + 'static + Debug {
     /// - `Ok(())` if the flush succeeded
     /// - `Err(Error)` if the flush operation failed
     fn flush(&mut self) -> Result<(), Error>;
+
+    /// Returns a raw fd the background loop can register with an OS
+    /// readiness mechanism to block until this transport is writable,
+    /// instead of busy-polling it.
+    ///
+    /// The default implementation returns `None`, which makes the loop fall
+    /// back to its fixed-interval busy-poll; transports backed by a raw fd
+    /// (e.g. `TcpStream`, `UnixStream`) override this. Transports that have
+    /// no fd to offer (e.g. the WASM/WebSocket path) simply don't, and keep
+    /// working via the fallback.
+    #[cfg(unix)]
+    fn poll_fd(&self) -> Option<RawFd> {
+        None
+    }
 }
 /// Trait for transport mechanisms that support reading data.
 ///
@@ -108,11 +149,8 @@ pub trait WriteTransport: Send + Sync + 'static + Debug {
 /// - Must implement `Debug` for diagnostics
 /// - Should not block when no data is available
 pub trait ReadTransport: Send + 'static + Debug {
-    /// Reads as many bytes as possible without blocking.
-    ///
-    /// This method should attempt to read data into the provided buffer
-    /// without blocking. If no data is available, it should return `Ok(0)`
-    /// rather than blocking the thread.
+    /// Reads as many bytes as possible without blocking, distinguishing "no
+    /// data available yet" from a genuine (possibly zero-length) read.
     ///
     /// # Arguments
     ///
@@ -121,19 +159,172 @@ pub trait ReadTransport: Send + 'static + Debug {
     ///
     /// # Returns
     ///
-    /// - `Ok(n)` where `n` is the number of bytes read (0 if no data available)
+    /// - `Ok(Some(n))` a read completed; `n` is the number of bytes read,
+    ///   including `0` for a true EOF (the peer closed its write half)
+    /// - `Ok(None)` no data is available right now (`WouldBlock`); the caller
+    ///   should try again later rather than treating this as EOF
     /// - `Err(Error)` if a read error occurred (excluding `WouldBlock`)
     ///
     /// # Implementation Notes
     ///
     /// - Must not block if no data is available
-    /// - Should convert `WouldBlock` errors to `Ok(0)`
+    /// - Should map `WouldBlock` to `Ok(None)`
     /// - Other I/O errors should be propagated
-    fn read_nonblock(&mut self, buf: &mut [u8]) -> Result<usize, Error>;
+    fn try_read(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error>;
+
+    /// Reads as many bytes as possible without blocking, collapsing "no data
+    /// available" and "connection closed" into `Ok(0)`.
+    ///
+    /// A thin wrapper over [`Self::try_read`] kept for callers that don't
+    /// need to tell those two cases apart; prefer `try_read` in a poll loop
+    /// that needs to detect EOF.
+    fn read_nonblock(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        Ok(self.try_read(buf)?.unwrap_or(0))
+    }
+
+    /// Returns a raw fd the background loop can register with an OS
+    /// readiness mechanism to block until this transport is readable,
+    /// instead of busy-polling it. See [`WriteTransport::poll_fd`].
+    #[cfg(unix)]
+    fn poll_fd(&self) -> Option<RawFd> {
+        None
+    }
+}
+
+/// Wraps any [`ReadTransport`] and caps the total number of bytes that may
+/// ever be read through it: the caller's buffer is clamped to the remaining
+/// budget before each read, and once the budget hits zero `try_read` reports
+/// a clean EOF (`Ok(Some(0))`) instead of reading any further. Modeled on the
+/// `read-restrict` crate's `Restrict<R>`, adapted to exfiltrate's
+/// non-blocking [`ReadTransport::try_read`] instead of `std::io::Read`.
+///
+/// Useful for bounding how many bytes a single frame (or an untrusted
+/// exfiltrate peer as a whole) is allowed to deliver, independent of the
+/// [`BidirectionalProxyBuilder::max_frame_size`] check on the declared
+/// length prefix.
+#[derive(Debug)]
+pub struct RestrictedRead<R> {
+    inner: R,
+    remaining: u64,
+}
+
+impl<R: ReadTransport> RestrictedRead<R> {
+    /// Wraps `inner`, allowing at most `limit` more bytes to be read through
+    /// it.
+    pub fn restrict(inner: R, limit: u64) -> Self {
+        RestrictedRead {
+            inner,
+            remaining: limit,
+        }
+    }
+
+    /// Bytes still allowed to be read before this wrapper reports EOF.
+    pub fn limit(&self) -> u64 {
+        self.remaining
+    }
+
+    /// Resets the remaining budget to `limit`, independent of how much has
+    /// already been read through this wrapper.
+    pub fn set_limit(&mut self, limit: u64) {
+        self.remaining = limit;
+    }
+
+    /// Unwraps this adapter, returning the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: ReadTransport> ReadTransport for RestrictedRead<R> {
+    fn try_read(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        if self.remaining == 0 {
+            return Ok(Some(0)); // budget exhausted: report a clean EOF
+        }
+        let cap = (buf.len() as u64).min(self.remaining) as usize;
+        match self.inner.try_read(&mut buf[..cap])? {
+            Some(n) => {
+                self.remaining -= n as u64;
+                Ok(Some(n))
+            }
+            None => Ok(None),
+        }
+    }
+
+    #[cfg(unix)]
+    fn poll_fd(&self) -> Option<RawFd> {
+        self.inner.poll_fd()
+    }
+}
+
+/// Describes what I/O operation an [`Error::IoError`] happened during, and
+/// optionally what resource it was performed on, so the error renders as
+/// "while reading from \<resource\>: \<io error\>" instead of a bare I/O
+/// error. Modeled on Mercurial's `HgError::IoError { error, context }`.
+///
+/// Attach one with [`IoContextExt`] rather than constructing
+/// [`Error::IoError`] by hand; a contextless error (e.g. from `?` via
+/// `From<std::io::Error>`) gets [`IoErrorContext::default`], which renders
+/// the same bare "IO error: " prefix this type's message had before context
+/// existed.
+#[derive(Debug, Clone, Default)]
+pub struct IoErrorContext {
+    operation: Option<&'static str>,
+    resource: Option<String>,
 }
 
+impl IoErrorContext {
+    /// Tags the error with the operation being attempted, e.g. `"reading"`.
+    pub fn new(operation: &'static str) -> Self {
+        IoErrorContext {
+            operation: Some(operation),
+            resource: None,
+        }
+    }
+
+    /// Additionally names the resource the operation was performed on, e.g.
+    /// a peer address or file path.
+    pub fn on(mut self, resource: impl Into<String>) -> Self {
+        self.resource = Some(resource.into());
+        self
+    }
+}
+
+impl std::fmt::Display for IoErrorContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.operation, &self.resource) {
+            (Some(op), Some(resource)) => write!(f, "while {} {}: ", op, resource),
+            (Some(op), None) => write!(f, "while {}: ", op),
+            (None, _) => write!(f, "IO error: "),
+        }
+    }
+}
 
+/// Attaches an [`IoErrorContext`] to a `std::io::Error`, turning it into an
+/// [`Error::IoError`] without restructuring the call site into a struct
+/// literal every time.
+pub trait IoContextExt<T> {
+    /// Tags a failed I/O result with the operation being attempted.
+    fn io_context(self, operation: &'static str) -> Result<T, Error>;
+    /// Tags a failed I/O result with the operation and the resource it was
+    /// performed on.
+    fn io_context_on(self, operation: &'static str, resource: impl Into<String>) -> Result<T, Error>;
+}
 
+impl<T> IoContextExt<T> for Result<T, std::io::Error> {
+    fn io_context(self, operation: &'static str) -> Result<T, Error> {
+        self.map_err(|error| Error::IoError {
+            error,
+            context: IoErrorContext::new(operation),
+        })
+    }
+
+    fn io_context_on(self, operation: &'static str, resource: impl Into<String>) -> Result<T, Error> {
+        self.map_err(|error| Error::IoError {
+            error,
+            context: IoErrorContext::new(operation).on(resource),
+        })
+    }
+}
 
 /// Error type for bidirectional proxy operations.
 ///
@@ -146,24 +337,34 @@ pub trait ReadTransport: Send + 'static + Debug {
 /// Error handling in practice:
 /// ```
 /// use std::io;
+/// # #[derive(Debug, Clone, Default)]
+/// # pub struct IoErrorContext;
+/// # impl std::fmt::Display for IoErrorContext {
+/// #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+/// #         write!(f, "IO error: ")
+/// #     }
+/// # }
 /// # #[derive(Debug, thiserror::Error)]
 /// # pub enum Error {
-/// #     #[error("IO error: {0}")]
-/// #     IoError(#[from] io::Error),
+/// #     #[error("{context}{error}")]
+/// #     IoError { #[from] error: io::Error, context: IoErrorContext },
 /// # }
-/// 
+///
 /// fn handle_error() -> Result<(), Error> {
 ///     // Errors are typically created from I/O operations
-///     Err(Error::IoError(io::Error::new(
-///         io::ErrorKind::ConnectionRefused,
-///         "Cannot connect to server"
-///     )))
+///     Err(Error::IoError {
+///         error: io::Error::new(
+///             io::ErrorKind::ConnectionRefused,
+///             "Cannot connect to server"
+///         ),
+///         context: IoErrorContext::default(),
+///     })
 /// }
-/// 
+///
 /// # fn main() {
 /// match handle_error() {
-///     Err(Error::IoError(e)) if e.kind() == io::ErrorKind::ConnectionRefused => {
-///         println!("Connection refused: {}", e);
+///     Err(Error::IoError { error, .. }) if error.kind() == io::ErrorKind::ConnectionRefused => {
+///         println!("Connection refused: {}", error);
 ///     }
 ///     Err(e) => println!("Other error: {}", e),
 ///     Ok(_) => println!("Success"),
@@ -173,13 +374,145 @@ pub trait ReadTransport: Send + 'static + Debug {
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
     /// An I/O error occurred during transport operations.
-    /// 
+    ///
     /// This variant wraps standard I/O errors that may occur during
-    /// reading, writing, or flushing data to/from the transport.
-    #[error("IO error: {0}")]
-    IoError(#[from] std::io::Error),
+    /// reading, writing, or flushing data to/from the transport, tagged
+    /// with an [`IoErrorContext`] describing what was attempted and on
+    /// what resource.
+    #[error("{context}{error}")]
+    IoError {
+        #[from]
+        error: std::io::Error,
+        context: IoErrorContext,
+    },
+
+    /// A peer declared a frame larger than the configured
+    /// [`BidirectionalProxyBuilder::max_frame_size`]. Returned from
+    /// `pop_msg` instead of panicking, so an oversized or malicious length
+    /// prefix tears the connection down cleanly instead of crashing the
+    /// process.
+    #[error("frame of {size} bytes exceeds the {max}-byte limit")]
+    FrameTooLarge {
+        /// The length the peer declared in the frame's 4-byte prefix.
+        size: usize,
+        /// The configured ceiling it exceeded.
+        max: usize,
+    },
+}
+
+/// A transform applied to each message payload, sitting between the
+/// length-prefix framing and the user's `recv` callback.
+///
+/// `encode` runs on the way out, before the 4-byte length prefix is computed
+/// (so the prefix frames the *encoded* bytes); `decode` runs on the way in,
+/// on the message body already extracted by [`ReadState::pop_msg`]. This is
+/// the extension point for layering compression (e.g. zstd), encryption, or
+/// a checksum onto the wire protocol without touching the framing/transport
+/// code in [`BidirectionalProxy::with_codec`].
+pub trait Codec: Send + 'static {
+    /// Transforms an outgoing message body before it is length-prefixed and
+    /// written to the transport.
+    fn encode(&mut self, frame: &[u8]) -> Result<Vec<u8>, Error>;
+    /// Transforms an incoming message body, already extracted from its
+    /// length prefix, before it reaches the `recv` callback.
+    fn decode(&mut self, frame: &[u8]) -> Result<Vec<u8>, Error>;
+}
+
+/// The no-op [`Codec`] used by [`BidirectionalProxy::new`]: passes frames
+/// through unchanged, matching the proxy's original raw length-prefix
+/// behavior.
+#[derive(Debug, Default)]
+pub struct IdentityCodec;
+
+impl Codec for IdentityCodec {
+    fn encode(&mut self, frame: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(frame.to_vec())
+    }
+
+    fn decode(&mut self, frame: &[u8]) -> Result<Vec<u8>, Error> {
+        Ok(frame.to_vec())
+    }
+}
+
+/// Applies a sequence of [`Codec`]s as a single [`Codec`]: `encode` runs
+/// them in list order (outermost layer last, e.g. compress then encrypt),
+/// `decode` runs them in reverse order to undo that (decrypt then
+/// decompress), so the two sides of the stack mirror each other.
+pub struct CodecStack(pub Vec<Box<dyn Codec>>);
+
+impl Codec for CodecStack {
+    fn encode(&mut self, frame: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut data = frame.to_vec();
+        for codec in self.0.iter_mut() {
+            data = codec.encode(&data)?;
+        }
+        Ok(data)
+    }
+
+    fn decode(&mut self, frame: &[u8]) -> Result<Vec<u8>, Error> {
+        let mut data = frame.to_vec();
+        for codec in self.0.iter_mut().rev() {
+            data = codec.decode(&data)?;
+        }
+        Ok(data)
+    }
 }
 
+/// The proxy's current relationship to its transport, as seen by callers
+/// polling [`BidirectionalProxy::state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConnectionState {
+    /// The transport is up and messages are flowing normally.
+    #[default]
+    Connected,
+    /// A transport error was hit; the proxy is retrying [`Reconnectable::reconnect`]
+    /// with exponential backoff and has not given up yet.
+    Reconnecting,
+    /// The background thread has stopped for good: either a non-reconnectable
+    /// transport hit an error, or reconnection was attempted and exhausted its
+    /// retries. `send` returns an error, and messages still queued are
+    /// never transmitted.
+    Dead,
+}
+
+/// Optional capability for transports used with
+/// [`BidirectionalProxy::with_reconnect`]: lets the proxy recover from a
+/// transport error instead of tearing down the background thread.
+///
+/// On a read or write failure the proxy discards the half-assembled
+/// [`ReadState`] buffer (a frame torn mid-transmission cannot be completed
+/// across a new connection) but keeps any not-yet-written outgoing messages,
+/// so they are retransmitted once reconnected.
+pub trait Reconnectable {
+    /// Re-establishes the transport, replacing whatever connection it was
+    /// using internally. Called repeatedly with exponential backoff between
+    /// attempts until it succeeds or the proxy gives up.
+    fn reconnect(&mut self) -> Result<(), Error>;
+
+    /// Runs once after a successful `reconnect`, before the proxy resumes
+    /// reading/writing, so both ends can agree on where to resume (e.g. an
+    /// application-level handshake exchanging last-seen sequence numbers).
+    ///
+    /// The default implementation does nothing.
+    fn resync(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Initial delay before the first reconnect retry; doubles after each failed
+/// attempt up to [`RECONNECT_MAX_BACKOFF`].
+const RECONNECT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+/// Ceiling on the exponential backoff between reconnect attempts.
+const RECONNECT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Number of reconnect attempts before the proxy gives up and marks the
+/// connection [`ConnectionState::Dead`].
+const RECONNECT_MAX_ATTEMPTS: u32 = 10;
+
+/// Default ceiling on an incoming frame's declared length, used by every
+/// constructor except [`BidirectionalProxyBuilder::max_frame_size`]. Matches
+/// the sanity limit the proxy has always enforced.
+const DEFAULT_MAX_FRAME_SIZE: usize = 10_000;
+
 /// Internal state for buffering and parsing incoming messages.
 ///
 /// This struct maintains a buffer of partially received data and provides
@@ -201,31 +534,33 @@ pub enum Error {
 #[derive(Debug)]
 struct ReadState {
     /// Buffer containing partially received message data.
-    /// 
+    ///
     /// This buffer accumulates bytes from multiple read operations
     /// until complete messages can be extracted.
     buf: Vec<u8>,
+    /// Ceiling on the declared length of an incoming message, enforced by
+    /// `pop_msg`. Configurable via [`BidirectionalProxyBuilder::max_frame_size`].
+    max_frame_size: usize,
 }
 
 impl ReadState {
-    /// Creates a new empty read state.
-    ///
-    /// Initializes an empty buffer for accumulating incoming message data.
-    /// The buffer will grow as data is added via `add_bytes`.
+    /// Creates a new empty read state that rejects any message larger than
+    /// `max_frame_size`.
     ///
     /// # Examples
     ///
     /// ```
-    /// # struct ReadState { buf: Vec<u8> }
+    /// # struct ReadState { buf: Vec<u8>, max_frame_size: usize }
     /// # impl ReadState {
-    /// #     fn new() -> Self { ReadState { buf: Vec::new() } }
+    /// #     fn new(max_frame_size: usize) -> Self { ReadState { buf: Vec::new(), max_frame_size } }
     /// # }
-    /// let mut state = ReadState::new();
+    /// let mut state = ReadState::new(10_000);
     /// assert!(state.buf.is_empty());
     /// ```
-    fn new() -> Self {
+    fn new(max_frame_size: usize) -> Self {
         ReadState {
             buf: Vec::new(),
+            max_frame_size,
         }
     }
 
@@ -283,13 +618,12 @@ impl ReadState {
     ///
     /// # Returns
     ///
-    /// - `Some(message)` if a complete message is available
-    /// - `None` if more data is needed to form a complete message
-    ///
-    /// # Panics
-    ///
-    /// Panics if a message claims to be larger than 10,000 bytes. This is a
-    /// sanity check to prevent memory exhaustion from malformed data.
+    /// - `Ok(Some(message))` if a complete message is available
+    /// - `Ok(None)` if more data is needed to form a complete message
+    /// - `Err(Error::FrameTooLarge)` if the peer declared a message larger
+    ///   than `max_frame_size`. The buffer is left as-is; the caller should
+    ///   treat this like any other fatal framing error and tear down the
+    ///   connection, since the stream is now desynced.
     ///
     /// # Edge Cases
     ///
@@ -301,67 +635,350 @@ impl ReadState {
     /// # Examples
     ///
     /// ```
-    /// # struct ReadState { buf: Vec<u8> }
+    /// # struct ReadState { buf: Vec<u8>, max_frame_size: usize }
     /// # impl ReadState {
-    /// #     fn pop_msg(&mut self) -> Option<Box<[u8]>> {
-    /// #         if self.buf.len() < 4 { return None; }
+    /// #     fn pop_msg(&mut self) -> Result<Option<Box<[u8]>>, String> {
+    /// #         if self.buf.len() < 4 { return Ok(None); }
     /// #         let size = u32::from_le_bytes(self.buf[..4].try_into().unwrap()) as usize;
-    /// #         if size > 10_000 { panic!("Message too large"); }
-    /// #         if self.buf.len() < size + 4 { return None; }
+    /// #         if size > self.max_frame_size { return Err("frame too large".to_owned()); }
+    /// #         if self.buf.len() < size + 4 { return Ok(None); }
     /// #         let msg = self.buf[4..size + 4].to_vec().into_boxed_slice();
     /// #         self.buf.drain(..size + 4);
-    /// #         Some(msg)
+    /// #         Ok(Some(msg))
     /// #     }
     /// # }
-    /// # let mut state = ReadState { buf: Vec::new() };
+    /// # let mut state = ReadState { buf: Vec::new(), max_frame_size: 10_000 };
     /// // Add a complete message: length=5, data="hello"
     /// let mut msg = vec![];
     /// msg.extend_from_slice(&5u32.to_le_bytes());
     /// msg.extend_from_slice(b"hello");
     /// state.buf = msg;
-    /// 
-    /// let extracted = state.pop_msg();
+    ///
+    /// let extracted = state.pop_msg().unwrap();
     /// assert_eq!(extracted.as_deref(), Some(&b"hello"[..]));
     /// assert!(state.buf.is_empty());
-    /// 
+    ///
     /// // Example with partial message
     /// state.buf = vec![0, 0, 0]; // Only 3 bytes of length header
-    /// assert!(state.pop_msg().is_none()); // Not enough data
+    /// assert!(state.pop_msg().unwrap().is_none()); // Not enough data
     /// ```
-    fn pop_msg(&mut self) -> Option<Box<[u8]>> {
-        // eprintln!("pop_msg: Called with buffer size {}", self.buf.len());
+    fn pop_msg(&mut self) -> Result<Option<Box<[u8]>>, Error> {
         if self.buf.len() < 4 {
-            // eprintln!("pop_msg: Not enough data to read size, current buffer length: {}", self.buf.len());
-            return None; // Not enough data to read size
+            return Ok(None); // Not enough data to read size
         }
 
         let size_bytes = &self.buf[..4];
         let size = u32::from_le_bytes(size_bytes.try_into().unwrap()) as usize;
-        // eprintln!("pop_msg: Size_bytes: {:?}, size: {:?}, buffer len: {}", size_bytes, size, self.buf.len());
-        // eprintln!("pop_msg: Full buffer preview (first 60 bytes): {:?}", &self.buf[..self.buf.len().min(60)]);
 
-        if size > 10_000 {
-            eprintln!("ERROR: Invalid message size {} detected. Buffer contents: {:?}", size, &self.buf[..self.buf.len().min(100)]);
-            panic!("Probably the wrong size.");
+        if size > self.max_frame_size {
+            return Err(Error::FrameTooLarge { size, max: self.max_frame_size });
         }
 
         if self.buf.len() < size + 4 {
-            // eprintln!("pop_msg: Not enough data to read full message. Need {}, have {}", size + 4, self.buf.len());
-            return None; // Not enough data to read the full message
+            return Ok(None); // Not enough data to read the full message
         }
 
-        // eprintln!("pop_msg: Extracting message from bytes [4..{}]", size + 4);
         let msg = self.buf[4..size + 4].to_vec().into_boxed_slice();
-        // eprintln!("pop_msg: Extracted message: {:?}", &msg[..msg.len().min(20)]);
-        // eprintln!("pop_msg: About to drain bytes [0..{}] from buffer", size + 4);
         self.buf.drain(..size + 4);
-        // eprintln!("pop_msg: Buffer after drain: {:?} (size: {})", &self.buf[..self.buf.len().min(50)], self.buf.len());
-        Some(msg)
+        Ok(Some(msg))
     }
 }
 
 
 
+/// A non-blocking, per-connection outbound write queue.
+///
+/// Holds fully length-prefixed frames and tracks how many bytes of the front
+/// one have already been accepted by the transport, so a short or zero-byte
+/// (`WouldBlock`) [`WriteTransport::write`] just leaves the remainder queued
+/// for the next [`Self::drain`] call instead of panicking or blocking the
+/// background thread on a backpressured peer.
+#[derive(Debug, Default)]
+struct OutboundQueue {
+    /// Queued frames, each already `4-byte length + payload`. The front
+    /// element may be partially written already (see `out_pos`).
+    frames: std::collections::VecDeque<Vec<u8>>,
+    /// Bytes of `frames[0]` already accepted by the transport.
+    out_pos: usize,
+}
+
+impl OutboundQueue {
+    fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues `payload` as one length-prefixed frame (4-byte LE length +
+    /// body), to be sent in order by future [`Self::drain`] calls.
+    fn push(&mut self, payload: &[u8]) {
+        let mut frame = Vec::with_capacity(4 + payload.len());
+        frame.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        frame.extend_from_slice(payload);
+        self.frames.push_back(frame);
+    }
+
+    /// Number of frames still queued, including a partially-written front one.
+    fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Writes as much of the queue as `write` will accept without blocking,
+    /// optionally throttled by `limiter`.
+    ///
+    /// Returns `Ok((wrote_any, bytes_written))`: `wrote_any` is `false` if
+    /// the queue was empty or the transport/rate limit wasn't ready for
+    /// more, `Err` on a hard transport error (the queue is left intact so
+    /// the caller can retry after reconnecting, if applicable).
+    fn drain<W: WriteTransport>(
+        &mut self,
+        write: &mut W,
+        mut limiter: Option<&mut RateLimiter>,
+    ) -> Result<(bool, u64), Error> {
+        let mut wrote_any = false;
+        let mut bytes_written: u64 = 0;
+        while let Some(frame) = self.frames.front() {
+            let remaining = &frame[self.out_pos..];
+            let chunk = match limiter.as_mut() {
+                Some(limiter) => {
+                    let allowed = limiter.take(remaining.len());
+                    if allowed == 0 {
+                        // Budget exhausted; defer the rest instead of
+                        // dropping it. The next `drain` call will retry.
+                        std::thread::sleep(limiter.delay_for(remaining.len().min(1)));
+                        break;
+                    }
+                    &remaining[..allowed]
+                }
+                None => remaining,
+            };
+            let written = write.write(chunk)?;
+            if written == 0 {
+                break; // transport not ready; leave the rest for next time
+            }
+            wrote_any = true;
+            bytes_written += written as u64;
+            self.out_pos += written;
+            if self.out_pos == frame.len() {
+                self.frames.pop_front();
+                self.out_pos = 0;
+            } else {
+                break; // partial write; send buffer is likely full (or rate-limited) for now
+            }
+        }
+        if wrote_any {
+            write.flush()?;
+        }
+        Ok((wrote_any, bytes_written))
+    }
+}
+
+/// Token-bucket outbound rate limiter: refills at `rate` bytes/sec up to a
+/// one-second burst ceiling, tracked against a monotonic clock. Callers
+/// spend from the budget via [`Self::take`]; when it's empty they get back
+/// `0` and should consult [`Self::delay_for`] for how long to wait rather
+/// than dropping data, matching a typical rate-limited relay.
+#[derive(Debug)]
+struct RateLimiter {
+    /// Configured throughput ceiling, bytes/sec.
+    rate: u64,
+    /// Bytes currently available to spend.
+    budget: f64,
+    /// Burst ceiling `budget` refills up to: one second worth of `rate`.
+    max_budget: f64,
+    last_refill: std::time::Instant,
+}
+
+impl RateLimiter {
+    fn new(rate: u64) -> Self {
+        RateLimiter {
+            rate,
+            budget: rate as f64,
+            max_budget: rate as f64,
+            last_refill: std::time::Instant::now(),
+        }
+    }
+
+    /// Adds bytes to the budget for time elapsed since the last refill,
+    /// capped at `max_budget`.
+    fn refill(&mut self) {
+        let now = std::time::Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.last_refill = now;
+        self.budget = (self.budget + elapsed * self.rate as f64).min(self.max_budget);
+    }
+
+    /// Refills, then spends up to `want` bytes from the budget, returning how
+    /// many were actually granted (`0` if the budget is currently empty).
+    fn take(&mut self, want: usize) -> usize {
+        self.refill();
+        let allowed = (self.budget.floor() as usize).min(want);
+        self.budget -= allowed as f64;
+        allowed
+    }
+
+    /// How long the caller should wait for at least `bytes` to become
+    /// available, given the budget as of the last `refill`/`take`.
+    fn delay_for(&self, bytes: usize) -> Duration {
+        let deficit = (bytes as f64 - self.budget).max(0.0);
+        Duration::from_secs_f64(deficit / self.rate as f64)
+    }
+}
+
+/// Snapshot of cumulative transfer counters and a moving-average throughput
+/// estimate, passed periodically to a [`BidirectionalProxyBuilder::metrics`]
+/// callback.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Metrics {
+    /// Total bytes written to the transport since the proxy started, 4-byte
+    /// length prefixes included.
+    pub bytes_sent: u64,
+    /// Total payload bytes received since the proxy started.
+    pub bytes_received: u64,
+    /// Total messages sent since the proxy started.
+    pub messages_sent: u64,
+    /// Total messages received since the proxy started.
+    pub messages_received: u64,
+    /// Exponential moving average of send throughput, bytes/sec.
+    pub send_rate: f64,
+    /// Exponential moving average of receive throughput, bytes/sec.
+    pub recv_rate: f64,
+}
+
+/// How often a configured `metrics` callback is invoked.
+const METRICS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Smoothing factor for the [`Metrics::send_rate`]/[`Metrics::recv_rate`]
+/// exponential moving average: weight given to the newest sample each time
+/// it's folded in.
+const METRICS_EMA_ALPHA: f64 = 0.3;
+
+/// Running totals and EMA state for [`Metrics`] reporting, local to the
+/// background loop.
+#[derive(Debug, Default)]
+struct MetricsTracker {
+    bytes_sent: u64,
+    bytes_received: u64,
+    messages_sent: u64,
+    messages_received: u64,
+    send_rate: f64,
+    recv_rate: f64,
+    sent_since_report: u64,
+    received_since_report: u64,
+    last_report: Option<std::time::Instant>,
+}
+
+impl MetricsTracker {
+    fn record_sent(&mut self, bytes: u64, messages: u64) {
+        self.bytes_sent += bytes;
+        self.messages_sent += messages;
+        self.sent_since_report += bytes;
+    }
+
+    fn record_received(&mut self, bytes: u64) {
+        self.bytes_received += bytes;
+        self.messages_received += 1;
+        self.received_since_report += bytes;
+    }
+
+    /// Folds this interval's throughput into the EMA and invokes `callback`
+    /// if at least [`METRICS_INTERVAL`] has passed since the last report.
+    fn maybe_report(&mut self, callback: &(dyn Fn(Metrics) + Send)) {
+        let now = std::time::Instant::now();
+        let elapsed = match self.last_report {
+            Some(last) => now.duration_since(last),
+            None => {
+                self.last_report = Some(now);
+                return;
+            }
+        };
+        if elapsed < METRICS_INTERVAL {
+            return;
+        }
+        let secs = elapsed.as_secs_f64();
+        let sample_send_rate = self.sent_since_report as f64 / secs;
+        let sample_recv_rate = self.received_since_report as f64 / secs;
+        self.send_rate = METRICS_EMA_ALPHA * sample_send_rate + (1.0 - METRICS_EMA_ALPHA) * self.send_rate;
+        self.recv_rate = METRICS_EMA_ALPHA * sample_recv_rate + (1.0 - METRICS_EMA_ALPHA) * self.recv_rate;
+        self.sent_since_report = 0;
+        self.received_since_report = 0;
+        self.last_report = Some(now);
+        callback(Metrics {
+            bytes_sent: self.bytes_sent,
+            bytes_received: self.bytes_received,
+            messages_sent: self.messages_sent,
+            messages_received: self.messages_received,
+            send_rate: self.send_rate,
+            recv_rate: self.recv_rate,
+        });
+    }
+}
+
+/// Registration token for the read transport's fd in a [`Reactor`]'s `Poll`.
+#[cfg(unix)]
+const REACTOR_READ_TOKEN: Token = Token(0);
+/// Registration token for the write transport's fd in a [`Reactor`]'s `Poll`.
+#[cfg(unix)]
+const REACTOR_WRITE_TOKEN: Token = Token(1);
+/// Registration token for the [`Reactor::waker`] that `send`
+/// triggers to wake the loop without waiting for I/O readiness.
+#[cfg(unix)]
+const REACTOR_WAKE_TOKEN: Token = Token(2);
+
+/// Ceiling on how long [`Reactor::wait`] blocks in a single `poll` call, so
+/// the loop still notices a disconnected channel (which isn't an fd event)
+/// in bounded time even if nothing else happens.
+#[cfg(unix)]
+const REACTOR_FALLBACK_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Readiness-driven alternative to the fixed-interval busy-poll: registers
+/// the read and write transports' fds with an OS polling mechanism (mio
+/// `Poll`) so the background loop can block in a single call until one of
+/// them is ready, rather than waking up on a timer regardless of whether
+/// there is anything to do.
+///
+/// Built automatically by [`BidirectionalProxy::with_codec`] and friends
+/// when both transports expose a fd via `poll_fd`; transports that can't
+/// (e.g. the WASM/WebSocket path) leave the loop on its busy-poll fallback
+/// instead, since [`Self::new`] returns `None`.
+#[cfg(unix)]
+struct Reactor {
+    poll: Poll,
+    events: Events,
+    /// Wakes a blocked `poll` call from outside the background thread, used
+    /// by `send` so a newly queued outgoing message doesn't
+    /// wait out the rest of `REACTOR_FALLBACK_TIMEOUT`.
+    waker: Arc<Waker>,
+}
+
+#[cfg(unix)]
+impl Reactor {
+    /// Builds a reactor registered for `read_fd` readability and `write_fd`
+    /// writability, or returns `None` if either transport has no fd to
+    /// offer. A shared fd (e.g. a single duplex socket used for both) is
+    /// registered once for both interests.
+    fn new(read_fd: Option<RawFd>, write_fd: Option<RawFd>) -> Option<Self> {
+        let (read_fd, write_fd) = (read_fd?, write_fd?);
+        let poll = Poll::new().ok()?;
+        let registry = poll.registry();
+        if read_fd == write_fd {
+            registry
+                .register(&mut SourceFd(&read_fd), REACTOR_READ_TOKEN, Interest::READABLE | Interest::WRITABLE)
+                .ok()?;
+        } else {
+            registry.register(&mut SourceFd(&read_fd), REACTOR_READ_TOKEN, Interest::READABLE).ok()?;
+            registry.register(&mut SourceFd(&write_fd), REACTOR_WRITE_TOKEN, Interest::WRITABLE).ok()?;
+        }
+        let waker = Arc::new(Waker::new(registry, REACTOR_WAKE_TOKEN).ok()?);
+        Some(Reactor { poll, events: Events::with_capacity(16), waker })
+    }
+
+    /// Blocks until the read side is readable, the write side is writable,
+    /// `waker.wake()` was called, or `REACTOR_FALLBACK_TIMEOUT` elapses (a
+    /// safety net for channel-disconnect checks, which aren't fd events).
+    fn wait(&mut self) {
+        let _ = self.poll.poll(&mut self.events, Some(REACTOR_FALLBACK_TIMEOUT));
+    }
+}
+
 /// A bidirectional message proxy that handles framed message communication.
 ///
 /// `BidirectionalProxy` manages communication between two endpoints using a
@@ -381,6 +998,22 @@ impl ReadState {
 pub struct BidirectionalProxy {
     /// Channel sender for queuing outgoing messages.
     data_sender: Sender<Box<[u8]>>,
+    /// Current connection state, updated by the background thread and
+    /// readable via [`Self::state`].
+    state: Arc<Mutex<ConnectionState>>,
+    /// Number of frames still sitting in the background thread's
+    /// [`OutboundQueue`], readable via [`Self::queue_depth`] so callers can
+    /// apply their own backpressure against a slow peer.
+    queue_depth: Arc<AtomicUsize>,
+    /// Channel for queuing outgoing fd-carrying messages, present only on a
+    /// proxy built with [`Self::with_fds`].
+    #[cfg(unix)]
+    fd_sender: Option<Sender<(Box<[u8]>, Vec<RawFd>)>>,
+    /// Wakes the background thread's [`Reactor`] after `send`
+    /// queues a message, if the loop is running readiness-driven rather than
+    /// on the busy-poll fallback.
+    #[cfg(unix)]
+    reactor_waker: Option<Arc<Waker>>,
 }
 
 impl BidirectionalProxy {
@@ -420,9 +1053,56 @@ impl BidirectionalProxy {
     pub fn new<F,W,R>(write: W, read: R, recv: F) -> Self
     where F: Fn(Box<[u8]>) -> Option<Box<[u8]>> + Send + 'static,
     R: ReadTransport, W: WriteTransport  {
+        Self::with_codec(write, read, recv, IdentityCodec)
+    }
 
-        let (s, r) = std::sync::mpsc::channel::<Box<[u8]>>();
+    /// Like [`Self::new`], but applies `codec` to each message payload:
+    /// `codec.encode` on the way out (after the handler/queued `send` bytes
+    /// are known, before the length prefix is computed) and `codec.decode`
+    /// on the way in (on the body [`ReadState::pop_msg`] already extracted
+    /// from its length prefix, before it reaches `recv`). Use a
+    /// [`CodecStack`] to layer more than one transform.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `C` - The [`Codec`] applied to every frame.
+    pub fn with_codec<F, W, R, C>(write: W, read: R, recv: F, codec: C) -> Self
+    where F: Fn(Box<[u8]>) -> Option<Box<[u8]>> + Send + 'static,
+    R: ReadTransport, W: WriteTransport, C: Codec {
+        Self::spawn_loop(write, read, recv, Box::new(codec), DEFAULT_MAX_FRAME_SIZE, None, None)
+    }
+
+    /// Returns a [`BidirectionalProxyBuilder`] for configuring `max_frame_size`,
+    /// an outbound `rate_limit`, and a periodic `metrics` callback before
+    /// spawning the proxy. Equivalent to [`Self::new`] with every knob left
+    /// at its default.
+    pub fn builder() -> BidirectionalProxyBuilder {
+        BidirectionalProxyBuilder::new()
+    }
 
+    /// Shared implementation behind [`Self::with_codec`] and
+    /// [`BidirectionalProxyBuilder::build`].
+    fn spawn_loop<F, W, R>(
+        write: W,
+        read: R,
+        recv: F,
+        mut codec: Box<dyn Codec>,
+        max_frame_size: usize,
+        rate_limit: Option<u64>,
+        metrics: Option<Box<dyn Fn(Metrics) + Send + 'static>>,
+    ) -> Self
+    where F: Fn(Box<[u8]>) -> Option<Box<[u8]>> + Send + 'static,
+    R: ReadTransport, W: WriteTransport {
+
+        let (s, r) = std::sync::mpsc::channel::<Box<[u8]>>();
+        let state = Arc::new(Mutex::new(ConnectionState::Connected));
+        let thread_state = state.clone();
+        let queue_depth = Arc::new(AtomicUsize::new(0));
+        let thread_queue_depth = queue_depth.clone();
+        #[cfg(unix)]
+        let reactor = Reactor::new(read.poll_fd(), write.poll_fd());
+        #[cfg(unix)]
+        let reactor_waker = reactor.as_ref().map(|r| r.waker.clone());
 
         crate::sys::thread::Builder::new()
             .name("exfiltrate::BidirectionalProxy".to_owned())
@@ -430,12 +1110,30 @@ impl BidirectionalProxy {
                 let mut read = read;
                 let mut write = write;
                 // we wind up copying it into here
-                let mut partial_read = ReadState::new();
+                let mut partial_read = ReadState::new(max_frame_size);
+                let mut outbound = OutboundQueue::new();
+                let mut rate_limiter = rate_limit.map(RateLimiter::new);
+                let mut metrics_tracker = metrics.is_some().then(MetricsTracker::default);
+                #[cfg(unix)]
+                let mut reactor = reactor;
                 loop { //the entire flow
                     //todo: this buffer strategy is not as efficient as it could be
                     let mut buf = vec![0; 1024];
 
                     let mut did_stuff = false;
+                    let queued_before = outbound.len();
+                    match outbound.drain(&mut write, rate_limiter.as_mut()) {
+                        Ok((wrote, bytes)) => {
+                            did_stuff |= wrote;
+                            if let Some(tracker) = &mut metrics_tracker {
+                                tracker.record_sent(bytes, (queued_before - outbound.len()) as u64);
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Error writing to transport: {}", e);
+                            break;
+                        }
+                    }
                     match read.read_nonblock(&mut buf) {
                         Ok(size) if size > 0 => {
                             // eprintln!("bidi: Initial read of {} bytes from transport, first 10 bytes: {:?}", size, &buf[..size.min(10)]);
@@ -451,37 +1149,58 @@ impl BidirectionalProxy {
                         }
                     }
                     //now try to pop
-                    if let Some(msg) = partial_read.pop_msg() {
-                        // eprintln!("Pop message of size {}", msg.len());
-                        // Call the provided function with the message
-                        did_stuff = true;
-                        let buf = recv(msg);
-                        match buf {
-                            Some(buf) => {
-                                // If the function returns a response, send it back
-                                let size = buf.len() as u32;
-                                let size_bytes = size.to_le_bytes();
-                                // eprintln!("bidi: Sending response of {} bytes, size_bytes: {:?}, first 10 data bytes: {:?}",
-                                //           buf.len(), size_bytes, &buf[..buf.len().min(10)]);
-
-                                write.write(&size_bytes).unwrap();
-                                write.write(&buf).unwrap();
-                                write.flush().unwrap();
+                    match partial_read.pop_msg() {
+                        Ok(Some(msg)) => {
+                            // eprintln!("Pop message of size {}", msg.len());
+                            // Call the provided function with the message
+                            did_stuff = true;
+                            if let Some(tracker) = &mut metrics_tracker {
+                                tracker.record_received(msg.len() as u64);
                             }
-                            None => {
-                                // eprintln!("bidi: Function returned None, not sending response");
-                                // If the function returns None, do nothing
+                            let msg = match codec.decode(&msg) {
+                                Ok(msg) => msg.into_boxed_slice(),
+                                Err(e) => {
+                                    eprintln!("Error decoding frame: {}", e);
+                                    break; // Exit the loop; the codec stream is now desynced
+                                }
+                            };
+                            let buf = recv(msg);
+                            match buf {
+                                Some(buf) => {
+                                    // If the function returns a response, queue it
+                                    // for the next drain rather than writing it
+                                    // (and potentially blocking) right here.
+                                    match codec.encode(&buf) {
+                                        Ok(buf) => outbound.push(&buf),
+                                        Err(e) => {
+                                            eprintln!("Error encoding frame: {}", e);
+                                            break;
+                                        }
+                                    }
+                                }
+                                None => {
+                                    // eprintln!("bidi: Function returned None, not sending response");
+                                    // If the function returns None, do nothing
+                                }
                             }
                         }
+                        Ok(None) => {}
+                        Err(e) => {
+                            eprintln!("Error framing inbound data: {}", e);
+                            break; // Exit the loop; an oversized frame desyncs the stream
+                        }
                     }
                     //try handling receive queue
                     match r.try_recv() {
                         Ok(msg) => {
                             // eprintln!("bidi: Received message from channel, size: {}", msg.len());
-                            let size_bytes = (msg.len() as u32).to_le_bytes();
-                            write.write(&size_bytes).unwrap();
-                            write.write(&msg).unwrap();
-                            write.flush().unwrap();
+                            match codec.encode(&msg) {
+                                Ok(msg) => outbound.push(&msg),
+                                Err(e) => {
+                                    eprintln!("Error encoding frame: {}", e);
+                                    break;
+                                }
+                            }
                             did_stuff = true;
                         }
                         Err(std::sync::mpsc::TryRecvError::Empty) => {
@@ -492,16 +1211,181 @@ impl BidirectionalProxy {
                             break; // Exit the loop if the channel is disconnected
                         }
                     }
+                    thread_queue_depth.store(outbound.len(), Ordering::Relaxed);
+                    if let (Some(tracker), Some(callback)) = (&mut metrics_tracker, &metrics) {
+                        tracker.maybe_report(callback.as_ref());
+                    }
                     if !did_stuff {
-                        // eprintln!("bidi: No data processed, sleeping for a bit");
+                        #[cfg(unix)]
+                        match &mut reactor {
+                            Some(reactor) => reactor.wait(),
+                            None => std::thread::sleep(std::time::Duration::from_millis(10)),
+                        }
+                        #[cfg(not(unix))]
                         std::thread::sleep(std::time::Duration::from_millis(10)); // Sleep to avoid busy waiting
                     }
                 }
                 //exit main loop
+                thread_queue_depth.store(outbound.len(), Ordering::Relaxed);
+                *thread_state.lock().unwrap() = ConnectionState::Dead;
             }).unwrap();
 
 
-        BidirectionalProxy {  data_sender: s }
+        BidirectionalProxy { data_sender: s, state, queue_depth, #[cfg(unix)] fd_sender: None, #[cfg(unix)] reactor_waker }
+    }
+
+    /// Like [`Self::with_codec`], but requires the transports to implement
+    /// [`Reconnectable`]: on a read or write error, instead of terminating
+    /// the background thread, the proxy marks itself
+    /// [`ConnectionState::Reconnecting`], discards the half-assembled
+    /// [`ReadState`] buffer, and retries `reconnect` (then `resync`) on both
+    /// transports with exponential backoff. Outgoing messages that had not
+    /// yet been written are kept and retransmitted once reconnected, in
+    /// order. If [`RECONNECT_MAX_ATTEMPTS`] is exhausted the proxy gives up
+    /// and marks itself [`ConnectionState::Dead`], same as a non-reconnecting
+    /// proxy.
+    ///
+    /// # Type Parameters
+    ///
+    /// * `C` - The [`Codec`] applied to every frame.
+    pub fn with_reconnect<F, W, R, C>(write: W, read: R, recv: F, mut codec: C) -> Self
+    where F: Fn(Box<[u8]>) -> Option<Box<[u8]>> + Send + 'static,
+    R: ReadTransport + Reconnectable, W: WriteTransport + Reconnectable, C: Codec {
+
+        let (s, r) = std::sync::mpsc::channel::<Box<[u8]>>();
+        let state = Arc::new(Mutex::new(ConnectionState::Connected));
+        let thread_state = state.clone();
+        let queue_depth = Arc::new(AtomicUsize::new(0));
+        let thread_queue_depth = queue_depth.clone();
+
+        crate::sys::thread::Builder::new()
+            .name("exfiltrate::BidirectionalProxy".to_owned())
+            .spawn(move || {
+                let mut read = read;
+                let mut write = write;
+                let mut partial_read = ReadState::new(DEFAULT_MAX_FRAME_SIZE);
+                // Outgoing frames, queued rather than written immediately so
+                // a write failure can be retried (in order, restarting a
+                // half-written frame from its first byte) after reconnecting
+                // instead of losing the message.
+                let mut outbound = OutboundQueue::new();
+
+                // Attempts to reconnect both transports with exponential
+                // backoff, then runs the resync handshake. Returns `true` once
+                // recovered, `false` if `RECONNECT_MAX_ATTEMPTS` was exhausted
+                // (in which case the caller should stop the loop).
+                macro_rules! try_reconnect {
+                    () => {{
+                        *thread_state.lock().unwrap() = ConnectionState::Reconnecting;
+                        partial_read = ReadState::new(DEFAULT_MAX_FRAME_SIZE);
+                        // The new connection never saw the front frame's
+                        // first bytes, so resend it whole.
+                        outbound.out_pos = 0;
+                        let mut backoff = RECONNECT_INITIAL_BACKOFF;
+                        let mut recovered = false;
+                        for attempt in 0..RECONNECT_MAX_ATTEMPTS {
+                            match read.reconnect().and_then(|_| write.reconnect())
+                                .and_then(|_| read.resync())
+                                .and_then(|_| write.resync())
+                            {
+                                Ok(()) => {
+                                    recovered = true;
+                                    break;
+                                }
+                                Err(e) => {
+                                    eprintln!("bidi: Reconnect attempt {} failed: {}", attempt + 1, e);
+                                    std::thread::sleep(backoff);
+                                    backoff = (backoff * 2).min(RECONNECT_MAX_BACKOFF);
+                                }
+                            }
+                        }
+                        *thread_state.lock().unwrap() = if recovered { ConnectionState::Connected } else { ConnectionState::Dead };
+                        recovered
+                    }};
+                }
+
+                'outer: loop {
+                    let mut did_stuff = false;
+
+                    match outbound.drain(&mut write, None) {
+                        Ok((wrote, _bytes)) => did_stuff |= wrote,
+                        Err(e) => {
+                            eprintln!("bidi: Error writing to transport: {}", e);
+                            if !try_reconnect!() {
+                                break 'outer;
+                            }
+                            continue 'outer;
+                        }
+                    }
+
+                    let mut buf = vec![0; 1024];
+                    match read.read_nonblock(&mut buf) {
+                        Ok(size) if size > 0 => {
+                            partial_read.add_bytes(&buf[0..size]);
+                            did_stuff = true;
+                        }
+                        Ok(_) => {}
+                        Err(e) => {
+                            eprintln!("Error reading from transport: {}", e);
+                            if !try_reconnect!() {
+                                break 'outer;
+                            }
+                            continue 'outer;
+                        }
+                    }
+                    match partial_read.pop_msg() {
+                        Ok(Some(msg)) => {
+                            did_stuff = true;
+                            let msg = match codec.decode(&msg) {
+                                Ok(msg) => msg.into_boxed_slice(),
+                                Err(e) => {
+                                    eprintln!("Error decoding frame: {}", e);
+                                    break 'outer;
+                                }
+                            };
+                            if let Some(buf) = recv(msg) {
+                                match codec.encode(&buf) {
+                                    Ok(buf) => outbound.push(&buf),
+                                    Err(e) => {
+                                        eprintln!("Error encoding frame: {}", e);
+                                        break 'outer;
+                                    }
+                                }
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            eprintln!("Error framing inbound data: {}", e);
+                            break 'outer; // oversized frame desyncs the stream
+                        }
+                    }
+                    match r.try_recv() {
+                        Ok(msg) => {
+                            match codec.encode(&msg) {
+                                Ok(msg) => outbound.push(&msg),
+                                Err(e) => {
+                                    eprintln!("Error encoding frame: {}", e);
+                                    break 'outer;
+                                }
+                            }
+                            did_stuff = true;
+                        }
+                        Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            eprintln!("bidi: Channel disconnected, exiting loop");
+                            break 'outer;
+                        }
+                    }
+                    thread_queue_depth.store(outbound.len(), Ordering::Relaxed);
+                    if !did_stuff {
+                        std::thread::sleep(std::time::Duration::from_millis(10));
+                    }
+                }
+                thread_queue_depth.store(outbound.len(), Ordering::Relaxed);
+                *thread_state.lock().unwrap() = ConnectionState::Dead;
+            }).unwrap();
+
+        BidirectionalProxy { data_sender: s, state, queue_depth, #[cfg(unix)] fd_sender: None, #[cfg(unix)] reactor_waker: None }
     }
 
     /// Sends a message through the proxy.
@@ -534,39 +1418,307 @@ impl BidirectionalProxy {
 
     pub fn send(&self, data: &[u8]) -> Result<(), Error> {
         self.data_sender.send(data.to_vec().into_boxed_slice())
-            .map_err(|_| Error::IoError(std::io::Error::new(std::io::ErrorKind::Other, "Failed to send data to proxy")))?;
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "Failed to send data to proxy"))
+            .io_context("queuing a message for the proxy's background thread")?;
+        // If the background loop is parked in a Reactor::wait (rather than
+        // the busy-poll fallback), wake it now instead of leaving it to
+        // notice this message up to REACTOR_FALLBACK_TIMEOUT later.
+        #[cfg(unix)]
+        if let Some(waker) = &self.reactor_waker {
+            let _ = waker.wake();
+        }
+        Ok(())
+    }
+
+    /// Returns the proxy's current [`ConnectionState`].
+    ///
+    /// Callers polling `send` can use this to distinguish a transient outage
+    /// (`Reconnecting`, only meaningful for a proxy built with
+    /// [`Self::with_reconnect`]) from a permanent one (`Dead`), where queued
+    /// messages will never be transmitted.
+    pub fn state(&self) -> ConnectionState {
+        *self.state.lock().unwrap()
+    }
+
+    /// Returns the number of frames still sitting in the background thread's
+    /// outbound queue, waiting to be written to a backpressured or slow
+    /// transport. Callers can poll this to apply their own backpressure
+    /// (e.g. pausing upstream work) instead of calling `send` unboundedly.
+    pub fn queue_depth(&self) -> usize {
+        self.queue_depth.load(Ordering::Relaxed)
+    }
+
+    /// Like [`Self::new`], but for transports that can pass open file
+    /// descriptors alongside message bytes (`W: `[`WriteTransportFd`]`, R: `
+    /// [`ReadTransportFd`]`), the way a Wayland or D-Bus connection does.
+    ///
+    /// `recv` receives the fds that arrived with its frame and may return
+    /// fds to send back alongside its response. Use [`Self::send_with_fds`]
+    /// to queue an outgoing message with fds from outside the callback; the
+    /// plain [`Self::send`] still works and sends no fds.
+    #[cfg(unix)]
+    pub fn with_fds<F, W, R>(write: W, read: R, recv: F) -> Self
+    where
+        F: Fn(Box<[u8]>, Vec<RawFd>) -> Option<(Box<[u8]>, Vec<RawFd>)> + Send + 'static,
+        W: WriteTransportFd,
+        R: ReadTransportFd,
+    {
+        let (s, r) = std::sync::mpsc::channel::<Box<[u8]>>();
+        let (fd_s, fd_r) = std::sync::mpsc::channel::<(Box<[u8]>, Vec<RawFd>)>();
+        let state = Arc::new(Mutex::new(ConnectionState::Connected));
+        let thread_state = state.clone();
+
+        crate::sys::thread::Builder::new()
+            .name("exfiltrate::BidirectionalProxy".to_owned())
+            .spawn(move || {
+                let mut read = read;
+                let mut write = write;
+                let mut partial_read = ReadState::new(DEFAULT_MAX_FRAME_SIZE);
+                // fds that arrived before the frame carrying them finished
+                // assembling (e.g. split across reads); delivered alongside
+                // the next `pop_msg` completes.
+                let mut pending_fds: Vec<RawFd> = Vec::new();
+                let mut fd_buf = vec![0 as RawFd; MAX_FDS_OUT];
+
+                loop {
+                    let mut did_stuff = false;
+                    let mut buf = vec![0; 1024];
+                    match read.read_nonblock_fds(&mut buf, &mut fd_buf) {
+                        Ok((size, fds)) => {
+                            if size > 0 {
+                                partial_read.add_bytes(&buf[0..size]);
+                                did_stuff = true;
+                            }
+                            if !fds.is_empty() {
+                                pending_fds.extend(fds);
+                                did_stuff = true;
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("Error reading from transport: {}", e);
+                            break;
+                        }
+                    }
+                    match partial_read.pop_msg() {
+                        Ok(Some(msg)) => {
+                            did_stuff = true;
+                            let fds = std::mem::take(&mut pending_fds);
+                            if let Some((resp, resp_fds)) = recv(msg, fds) {
+                                let size_bytes = (resp.len() as u32).to_le_bytes();
+                                if write.write_with_fds(&size_bytes, &[]).is_err()
+                                    || write.write_with_fds(&resp, &resp_fds).is_err()
+                                {
+                                    eprintln!("Error writing to transport");
+                                    break;
+                                }
+                            }
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            eprintln!("Error framing inbound data: {}", e);
+                            break; // oversized frame desyncs the stream
+                        }
+                    }
+                    match fd_r.try_recv() {
+                        Ok((msg, fds)) => {
+                            let size_bytes = (msg.len() as u32).to_le_bytes();
+                            if write.write_with_fds(&size_bytes, &[]).is_err()
+                                || write.write_with_fds(&msg, &fds).is_err()
+                            {
+                                eprintln!("Error writing to transport");
+                                break;
+                            }
+                            did_stuff = true;
+                        }
+                        Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            eprintln!("bidi: fd channel disconnected, exiting loop");
+                            break;
+                        }
+                    }
+                    match r.try_recv() {
+                        Ok(msg) => {
+                            let size_bytes = (msg.len() as u32).to_le_bytes();
+                            if write.write_with_fds(&size_bytes, &[]).is_err()
+                                || write.write_with_fds(&msg, &[]).is_err()
+                            {
+                                eprintln!("Error writing to transport");
+                                break;
+                            }
+                            did_stuff = true;
+                        }
+                        Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                        Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                            eprintln!("bidi: Channel disconnected, exiting loop");
+                            break;
+                        }
+                    }
+                    if !did_stuff {
+                        std::thread::sleep(Duration::from_millis(10));
+                    }
+                }
+                *thread_state.lock().unwrap() = ConnectionState::Dead;
+            }).unwrap();
+
+        BidirectionalProxy { data_sender: s, state, queue_depth: Arc::new(AtomicUsize::new(0)), fd_sender: Some(fd_s), reactor_waker: None }
+    }
+
+    /// Queues a message to send with `fds` attached, from outside the
+    /// `recv` callback. Requires a proxy built with [`Self::with_fds`];
+    /// returns `Err` otherwise.
+    #[cfg(unix)]
+    pub fn send_with_fds(&self, data: &[u8], fds: &[RawFd]) -> Result<(), Error> {
+        let fd_sender = self.fd_sender.as_ref().ok_or_else(|| std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "proxy was not constructed with BidirectionalProxy::with_fds",
+        )).io_context("queuing a message with fds")?;
+        fd_sender
+            .send((data.to_vec().into_boxed_slice(), fds.to_vec()))
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::Other, "Failed to send data to proxy"))
+            .io_context("queuing a message with fds for the proxy's background thread")?;
         Ok(())
     }
 
 }
 
+/// Configures and spawns a [`BidirectionalProxy`] with frame-size limits,
+/// outbound rate limiting, and periodic throughput metrics — the safe-to-use
+/// counterpart to [`BidirectionalProxy::new`] for pointing the proxy at an
+/// unbounded or untrusted stream.
+///
+/// Built via [`BidirectionalProxy::builder`]; every knob defaults to the
+/// same behavior as [`BidirectionalProxy::new`] (10,000-byte frame ceiling,
+/// no rate limit, no metrics).
+pub struct BidirectionalProxyBuilder {
+    max_frame_size: usize,
+    rate_limit: Option<u64>,
+    metrics: Option<Box<dyn Fn(Metrics) + Send + 'static>>,
+    codec: Box<dyn Codec>,
+}
+
+impl Default for BidirectionalProxyBuilder {
+    fn default() -> Self {
+        BidirectionalProxyBuilder {
+            max_frame_size: DEFAULT_MAX_FRAME_SIZE,
+            rate_limit: None,
+            metrics: None,
+            codec: Box::new(IdentityCodec),
+        }
+    }
+}
+
+impl BidirectionalProxyBuilder {
+    /// Creates a builder with every knob at [`BidirectionalProxy::new`]'s
+    /// defaults.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Rejects any incoming frame whose declared length exceeds
+    /// `max_frame_size`, tearing the connection down with
+    /// [`Error::FrameTooLarge`] instead of the unconfigurable 10,000-byte
+    /// panic `pop_msg` used to apply.
+    pub fn max_frame_size(mut self, max_frame_size: usize) -> Self {
+        self.max_frame_size = max_frame_size;
+        self
+    }
+
+    /// Throttles the outbound drain to at most `bytes_per_sec`, refilled on
+    /// a monotonic clock with up to one second of burst. When the budget
+    /// runs out, writes are deferred (the background thread sleeps until
+    /// more budget is available) rather than dropped.
+    pub fn rate_limit(mut self, bytes_per_sec: u64) -> Self {
+        self.rate_limit = Some(bytes_per_sec);
+        self
+    }
+
+    /// Registers `callback` to be invoked roughly once per
+    /// [`METRICS_INTERVAL`] with cumulative byte/message counts and a
+    /// moving-average transfer rate.
+    pub fn metrics<M: Fn(Metrics) + Send + 'static>(mut self, callback: M) -> Self {
+        self.metrics = Some(Box::new(callback));
+        self
+    }
+
+    /// Applies `codec` to each message payload, the same as
+    /// [`BidirectionalProxy::with_codec`]. Defaults to [`IdentityCodec`].
+    pub fn codec<C: Codec>(mut self, codec: C) -> Self {
+        self.codec = Box::new(codec);
+        self
+    }
+
+    /// Spawns the configured proxy.
+    pub fn build<F, W, R>(self, write: W, read: R, recv: F) -> BidirectionalProxy
+    where F: Fn(Box<[u8]>) -> Option<Box<[u8]>> + Send + 'static,
+    R: ReadTransport, W: WriteTransport {
+        BidirectionalProxy::spawn_loop(write, read, recv, self.codec, self.max_frame_size, self.rate_limit, self.metrics)
+    }
+}
+
+/// Outcome of a one-shot [`linux_nowait_read`] attempt.
+#[cfg(target_os = "linux")]
+enum NowaitRead {
+    /// The kernel honored the non-positional, non-blocking read; forward the
+    /// result (or error) to the caller as-is.
+    Done(Result<Option<usize>, std::io::Error>),
+    /// `preadv2`/`RWF_NOWAIT` isn't supported on this kernel or this fd type;
+    /// the caller should fall back to `set_nonblocking`-then-`read`.
+    Unsupported,
+}
+
+/// Attempts a non-blocking read of `fd` into `buf` via `preadv2(2)` with
+/// `RWF_NOWAIT`, without ever toggling the fd's `O_NONBLOCK` flag. Passing
+/// offset `-1` makes the kernel treat this like a normal (non-positional)
+/// read rather than a `pread`, so it also works on sockets and pipes.
+///
+/// This is the technique the `jobserver` crate uses for `try_acquire`: it
+/// lets a stream be read from a non-blocking poll loop while staying safe
+/// to use from a blocking call site at the same time, since `O_NONBLOCK` is
+/// shared fd-wide state rather than per-caller.
+#[cfg(target_os = "linux")]
+fn linux_nowait_read(fd: RawFd, buf: &mut [u8]) -> NowaitRead {
+    let iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    let result = unsafe { libc::preadv2(fd, &iov, 1, -1, libc::RWF_NOWAIT) };
+    if result >= 0 {
+        return NowaitRead::Done(Ok(Some(result as usize)));
+    }
+    let err = std::io::Error::last_os_error();
+    match err.raw_os_error() {
+        Some(libc::ENOSYS) | Some(libc::EOPNOTSUPP) | Some(libc::EINVAL) => NowaitRead::Unsupported,
+        Some(libc::EAGAIN) => NowaitRead::Done(Ok(None)),
+        _ => NowaitRead::Done(Err(err)),
+    }
+}
+
 /// Implementation of `WriteTransport` for TCP streams.
 ///
-/// This implementation ensures all data is written to the TCP stream,
-/// returning an error if a partial write occurs. The TCP stream must be
-/// cloneable (via `try_clone`) to allow separate read and write handles.
+/// This implementation performs a single non-blocking write attempt and
+/// reports back how much of `data` was actually accepted, leaving any
+/// caller-side retry of the remainder to [`OutboundQueue`]. The TCP stream
+/// must be cloneable (via `try_clone`) to allow separate read and write
+/// handles.
 impl WriteTransport for TcpStream {
-    /// Writes all data to the TCP stream.
-    ///
-    /// This implementation ensures that all data is written to the stream.
-    /// If a partial write occurs (not all bytes are written), an error is returned.
+    /// Writes as much of `data` as the socket's send buffer has room for,
+    /// without blocking.
     ///
     /// # Arguments
     ///
-    /// * `data` - The complete data to write to the stream
+    /// * `data` - The data to write to the stream
     ///
     /// # Returns
     ///
-    /// - `Ok(())` if all data was successfully written
-    /// - `Err(Error::IoError)` if the write failed or was partial
-    fn write(&mut self, data: &[u8]) -> Result<(), Error> {
-        match std::io::Write::write(self,data) {
-            Ok(size) if size == data.len() => Ok(()),
-            Ok(_) => Err(Error::IoError(std::io::Error::new(
-                std::io::ErrorKind::WriteZero,
-                "Not all data was written",
-            ))),
-            Err(e) => Err(Error::IoError(e)),
+    /// - `Ok(n)` the number of bytes actually written (`0` if the send
+    ///   buffer is full, i.e. `WouldBlock`)
+    /// - `Err(Error::IoError)` for other I/O errors
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        self.set_nonblocking(true).unwrap();
+        match std::io::Write::write(self, data) {
+            Ok(size) => Ok(size),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(0),
+            Err(e) => Err(e).io_context("writing to the TCP stream"),
         }
     }
 
@@ -580,10 +1732,12 @@ impl WriteTransport for TcpStream {
     /// - `Ok(())` if the flush succeeded
     /// - `Err(Error::IoError)` if the flush operation failed
     fn flush(&mut self) -> Result<(), Error> {
-        match std::io::Write::flush(self) {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::IoError(e)),
-        }
+        std::io::Write::flush(self).io_context("flushing the TCP stream")
+    }
+
+    #[cfg(unix)]
+    fn poll_fd(&self) -> Option<RawFd> {
+        Some(self.as_raw_fd())
     }
 }
 
@@ -595,10 +1749,11 @@ impl WriteTransport for TcpStream {
 impl ReadTransport for TcpStream {
     /// Performs a non-blocking read from the TCP stream.
     ///
-    /// Sets the stream to non-blocking mode and attempts to read data.
-    /// If no data is available (would block), returns 0 rather than blocking
-    /// the thread. This allows the proxy to efficiently poll for data without
-    /// consuming excessive CPU.
+    /// On Linux, first tries [`linux_nowait_read`], which never touches the
+    /// socket's shared `O_NONBLOCK` flag; only if that isn't supported (or
+    /// on other platforms) does it fall back to `set_nonblocking` then
+    /// `read`. Either way, if no data is available, returns `Ok(None)`
+    /// rather than blocking the thread or conflating it with EOF.
     ///
     /// # Arguments
     ///
@@ -606,15 +1761,275 @@ impl ReadTransport for TcpStream {
     ///
     /// # Returns
     ///
-    /// - `Ok(n)` where `n` is the number of bytes read
-    /// - `Ok(0)` if no data is available (would block)
+    /// - `Ok(Some(n))` where `n` is the number of bytes read (`0` on EOF)
+    /// - `Ok(None)` if no data is available (would block)
     /// - `Err(Error::IoError)` for other I/O errors
-    fn read_nonblock(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+    fn try_read(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        #[cfg(target_os = "linux")]
+        loop {
+            match linux_nowait_read(self.as_raw_fd(), buf) {
+                NowaitRead::Done(Ok(result)) => return Ok(result),
+                NowaitRead::Done(Err(e)) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                NowaitRead::Done(Err(e)) => return Err(e).io_context("reading from the TCP stream"),
+                NowaitRead::Unsupported => break,
+            }
+        }
         self.set_nonblocking(true).unwrap();
-        match self.read(buf) {
+        loop {
+            match self.read(buf) {
+                Ok(size) => return Ok(Some(size)),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(None),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue, // EINTR, retry
+                Err(e) => return Err(e).io_context("reading from the TCP stream"),
+            }
+        }
+    }
+
+    #[cfg(unix)]
+    fn poll_fd(&self) -> Option<RawFd> {
+        Some(self.as_raw_fd())
+    }
+}
+
+/// Implementation of `WriteTransport` for Unix domain sockets, mirroring the
+/// `TcpStream` impl above.
+#[cfg(unix)]
+impl WriteTransport for std::os::unix::net::UnixStream {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
+        self.set_nonblocking(true).unwrap();
+        match std::io::Write::write(self, data) {
             Ok(size) => Ok(size),
-            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(0), // No data available
-            Err(e) => Err(Error::IoError(e)),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(0),
+            Err(e) => Err(e).io_context("writing to the Unix stream"),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        std::io::Write::flush(self).io_context("flushing the Unix stream")
+    }
+
+    fn poll_fd(&self) -> Option<RawFd> {
+        Some(self.as_raw_fd())
+    }
+}
+
+/// Implementation of `ReadTransport` for Unix domain sockets, mirroring the
+/// `TcpStream` impl above.
+#[cfg(unix)]
+impl ReadTransport for std::os::unix::net::UnixStream {
+    fn try_read(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
+        #[cfg(target_os = "linux")]
+        loop {
+            match linux_nowait_read(self.as_raw_fd(), buf) {
+                NowaitRead::Done(Ok(result)) => return Ok(result),
+                NowaitRead::Done(Err(e)) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                NowaitRead::Done(Err(e)) => return Err(e).io_context("reading from the Unix stream"),
+                NowaitRead::Unsupported => break,
+            }
+        }
+        self.set_nonblocking(true).unwrap();
+        loop {
+            match self.read(buf) {
+                Ok(size) => return Ok(Some(size)),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(None),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue, // EINTR, retry
+                Err(e) => return Err(e).io_context("reading from the Unix stream"),
+            }
+        }
+    }
+
+    fn poll_fd(&self) -> Option<RawFd> {
+        Some(self.as_raw_fd())
+    }
+}
+
+/// Max open file descriptors attachable to a single `sendmsg` call. Linux
+/// silently truncates `SCM_RIGHTS` control messages that ask for more than
+/// the kernel's internal limit, so we cap well under the common default
+/// (`SCM_MAX_FD`, usually 253) to stay portable across platforms.
+#[cfg(unix)]
+pub const MAX_FDS_OUT: usize = 28;
+
+/// Max payload bytes per `sendmsg` call when fds are attached. Ancillary
+/// data (the `SCM_RIGHTS` control message) rides along with the *first*
+/// `sendmsg` of a message; some kernels silently drop ancillary data if the
+/// accompanying payload is too large for the socket's send buffer, so larger
+/// payloads are split into multiple frames with the fds only on the first.
+#[cfg(unix)]
+pub const MAX_FD_PASSING_BYTES: usize = 4096;
+
+/// Extends [`WriteTransport`] with the ability to pass open file descriptors
+/// alongside message bytes, the way a Wayland or D-Bus connection does.
+///
+/// Only meaningful for transports backed by a Unix domain socket, which is
+/// the only `AF_UNIX`-style mechanism with kernel support for passing fds
+/// between processes (`SCM_RIGHTS`).
+#[cfg(unix)]
+pub trait WriteTransportFd: WriteTransport {
+    /// Writes `data` to the transport, handing `fds` to the peer process
+    /// alongside it.
+    ///
+    /// `fds` must not exceed [`MAX_FDS_OUT`]. If `data` is longer than
+    /// [`MAX_FD_PASSING_BYTES`] it is split across multiple underlying
+    /// `sendmsg` calls; `fds` travel only with the first.
+    fn write_with_fds(&mut self, data: &[u8], fds: &[RawFd]) -> Result<(), Error>;
+}
+
+/// Extends [`ReadTransport`] with the ability to receive open file
+/// descriptors alongside message bytes. See [`WriteTransportFd`].
+#[cfg(unix)]
+pub trait ReadTransportFd: ReadTransport {
+    /// Reads as many bytes as possible into `buf` without blocking, the same
+    /// as [`ReadTransport::read_nonblock`], additionally collecting any fds
+    /// that arrived with this read (up to `fd_buf.len()` of them, which also
+    /// bounds how much ancillary-data space is reserved for the underlying
+    /// `recvmsg` call).
+    ///
+    /// Returns the number of bytes read and the fds received with them (a
+    /// copy of the same descriptors written into the leading portion of
+    /// `fd_buf`).
+    fn read_nonblock_fds(
+        &mut self,
+        buf: &mut [u8],
+        fd_buf: &mut [RawFd],
+    ) -> Result<(usize, Vec<RawFd>), Error>;
+}
+
+/// Sends `data` and `fds` as a single `sendmsg` call with an `SCM_RIGHTS`
+/// ancillary-data control message.
+#[cfg(unix)]
+fn sendmsg_with_fds(socket: RawFd, data: &[u8], fds: &[RawFd]) -> Result<usize, Error> {
+    let cmsg_space = if fds.is_empty() {
+        0
+    } else {
+        unsafe { libc::CMSG_SPACE((fds.len() * std::mem::size_of::<RawFd>()) as u32) as usize }
+    };
+    let mut cmsg_buf = vec![0u8; cmsg_space];
+
+    let mut iov = libc::iovec {
+        iov_base: data.as_ptr() as *mut libc::c_void,
+        iov_len: data.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+
+    if !fds.is_empty() {
+        msg.msg_control = cmsg_buf.as_mut_ptr() as *mut libc::c_void;
+        msg.msg_controllen = cmsg_buf.len() as _;
+        unsafe {
+            let cmsg = libc::CMSG_FIRSTHDR(&msg);
+            (*cmsg).cmsg_level = libc::SOL_SOCKET;
+            (*cmsg).cmsg_type = libc::SCM_RIGHTS;
+            (*cmsg).cmsg_len = libc::CMSG_LEN((fds.len() * std::mem::size_of::<RawFd>()) as u32) as _;
+            std::ptr::copy_nonoverlapping(fds.as_ptr(), libc::CMSG_DATA(cmsg) as *mut RawFd, fds.len());
         }
     }
+
+    let sent = unsafe { libc::sendmsg(socket, &msg, 0) };
+    if sent < 0 {
+        return Err(std::io::Error::last_os_error()).io_context("sending fds over the Unix socket");
+    }
+    Ok(sent as usize)
+}
+
+/// Receives bytes and any attached fds via a single non-blocking `recvmsg`
+/// call, sized to accept up to `fd_buf.len()` descriptors (at most
+/// [`MAX_FDS_OUT`]).
+#[cfg(unix)]
+fn recvmsg_with_fds(
+    socket: RawFd,
+    buf: &mut [u8],
+    fd_buf: &mut [RawFd],
+) -> Result<(usize, Vec<RawFd>), Error> {
+    let max_fds = fd_buf.len().min(MAX_FDS_OUT);
+    let cmsg_space =
+        unsafe { libc::CMSG_SPACE((max_fds * std::mem::size_of::<RawFd>()) as u32) as usize };
+    let mut cmsg_buf = vec![0u8; cmsg_space];
+
+    let mut iov = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    let mut msg: libc::msghdr = unsafe { std::mem::zeroed() };
+    msg.msg_iov = &mut iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = cmsg_buf.as_mut_ptr() as *mut libc::c_void;
+    msg.msg_controllen = cmsg_buf.len() as _;
+
+    let received = unsafe { libc::recvmsg(socket, &mut msg, libc::MSG_DONTWAIT) };
+    if received < 0 {
+        let err = std::io::Error::last_os_error();
+        if err.kind() == std::io::ErrorKind::WouldBlock {
+            return Ok((0, Vec::new()));
+        }
+        return Err(err).io_context("receiving fds over the Unix socket");
+    }
+
+    let mut fds = Vec::new();
+    unsafe {
+        let mut cmsg = libc::CMSG_FIRSTHDR(&msg);
+        while !cmsg.is_null() {
+            if (*cmsg).cmsg_level == libc::SOL_SOCKET && (*cmsg).cmsg_type == libc::SCM_RIGHTS {
+                let payload_len = (*cmsg).cmsg_len as usize - libc::CMSG_LEN(0) as usize;
+                let n = (payload_len / std::mem::size_of::<RawFd>()).min(fd_buf.len());
+                let data_ptr = libc::CMSG_DATA(cmsg) as *const RawFd;
+                for i in 0..n {
+                    let received_fd = *data_ptr.add(i);
+                    fd_buf[i] = received_fd;
+                    fds.push(received_fd);
+                }
+            }
+            cmsg = libc::CMSG_NXTHDR(&msg, cmsg);
+        }
+    }
+
+    Ok((received as usize, fds))
+}
+
+/// Implementation of `WriteTransportFd` for Unix domain sockets.
+#[cfg(unix)]
+impl WriteTransportFd for std::os::unix::net::UnixStream {
+    fn write_with_fds(&mut self, data: &[u8], fds: &[RawFd]) -> Result<(), Error> {
+        if fds.len() > MAX_FDS_OUT {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!("too many fds in one message: {} > {}", fds.len(), MAX_FDS_OUT),
+            ))
+            .io_context("writing to the Unix stream with fds");
+        }
+        let socket = self.as_raw_fd();
+        let mut offset = 0;
+        loop {
+            let end = (offset + MAX_FD_PASSING_BYTES).min(data.len());
+            let chunk = &data[offset..end];
+            let chunk_fds = if offset == 0 { fds } else { &[] };
+            let sent = sendmsg_with_fds(socket, chunk, chunk_fds)?;
+            if sent != chunk.len() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::WriteZero,
+                    "sendmsg wrote fewer bytes than requested",
+                ))
+                .io_context("writing to the Unix stream with fds");
+            }
+            offset = end;
+            if offset >= data.len() {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Implementation of `ReadTransportFd` for Unix domain sockets.
+#[cfg(unix)]
+impl ReadTransportFd for std::os::unix::net::UnixStream {
+    fn read_nonblock_fds(
+        &mut self,
+        buf: &mut [u8],
+        fd_buf: &mut [RawFd],
+    ) -> Result<(usize, Vec<RawFd>), Error> {
+        self.set_nonblocking(true).unwrap();
+        recvmsg_with_fds(self.as_raw_fd(), buf, fd_buf)
+    }
 }
\ No newline at end of file
@@ -26,7 +26,9 @@
 //!
 //! # Platform Support
 //!
-//! - **Native platforms**: Uses TCP sockets to connect to `127.0.0.1:1985`
+//! - **Native platforms**: Connects via the configured [`Endpoint`] -- TCP
+//!   to `127.0.0.1:1985` by default, or a local IPC channel (a Unix domain
+//!   socket) set with [`set_endpoint`]
 //! - **WebAssembly**: Uses WebSocket connections to `ws://localhost:1984`
 //!
 //! # Internal Usage
@@ -47,13 +49,19 @@
 //! The proxy is designed to be thread-safe. On WebAssembly targets, special care
 //! is taken to handle notifications from the main thread without blocking.
 
+mod codec;
 mod websocket_adapter;
 
 use crate::bidirectional_proxy::BidirectionalProxy;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::bidirectional_proxy::{Error as TransportError, IoContextExt, ReadTransport, WriteTransport};
 use crate::internal_proxy::Error::NotConnected;
+use crate::internal_proxy::codec::Codec;
 use crate::once_nonlock::OnceNonLock;
+use std::collections::HashMap;
 use std::net::TcpStream;
-use std::sync::{Arc, LazyLock, Mutex};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, RwLock};
 
 /// Error types for internal proxy operations.
 ///
@@ -85,6 +93,241 @@ pub enum Error {
     /// This error occurs when attempting to send data but no connection
     /// has been established yet.
     NotConnected,
+    /// The connection was lost while a request sent through
+    /// [`InternalProxy::send_request`] was still awaiting its response.
+    NoResponse,
+    /// [`InternalProxy::buffer_notification`] was called with
+    /// [`OverflowPolicy::Block`] configured, but the buffer was full and
+    /// blocking wasn't possible -- currently only on wasm32, where the
+    /// caller is assumed to be the main thread and must never block.
+    BufferFull,
+}
+
+/// Tracks JSON-RPC requests this proxy has sent to the remote endpoint (see
+/// [`InternalProxy::send_request`]) so the matching response -- received
+/// back through [`bidi_fn`] like any other inbound message -- can be routed
+/// to the call that's waiting on it instead of being dispatched as a fresh
+/// inbound request.
+#[derive(Debug, Default)]
+struct PendingRequests {
+    next_id: AtomicU64,
+    waiters: Mutex<HashMap<String, std::sync::mpsc::Sender<crate::jrpc::Response<serde_json::Value>>>>,
+}
+
+impl PendingRequests {
+    /// Allocates a fresh id, registers a waiter for it, and returns both the
+    /// id (to stamp onto the outgoing request) and the receiver to block on
+    /// for the reply.
+    fn register(
+        &self,
+    ) -> (
+        serde_json::Value,
+        std::sync::mpsc::Receiver<crate::jrpc::Response<serde_json::Value>>,
+    ) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let id = serde_json::Value::from(format!("internal-{id}"));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.waiters.lock().unwrap().insert(id.to_string(), sender);
+        (id, receiver)
+    }
+
+    /// Routes `response` to its waiter, if one is registered for its id.
+    /// Returns whether a waiter was found; the caller treats a miss as an
+    /// ordinary, unrelated message instead.
+    fn resolve(&self, response: crate::jrpc::Response<serde_json::Value>) -> bool {
+        let waiter = self
+            .waiters
+            .lock()
+            .unwrap()
+            .remove(&response.id.to_string());
+        match waiter {
+            Some(sender) => {
+                let _ = sender.send(response);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Paces reconnection attempts made by [`InternalProxy::reconnect_if_possible`].
+///
+/// Applies capped exponential backoff -- 100ms, doubling up to a 30s ceiling,
+/// reset on success -- so repeated `send_notification`/`send_buffered_if_possible`
+/// calls during an outage don't hammer `TcpStream::connect`.
+#[derive(Debug, Default)]
+struct ReconnectBackoff {
+    failures: AtomicU64,
+    last_attempt: Mutex<Option<crate::sys::time::Instant>>,
+}
+
+/// The backoff delay before the very first retry.
+const INITIAL_BACKOFF: std::time::Duration = std::time::Duration::from_millis(100);
+/// The backoff delay never grows past this, no matter how many consecutive
+/// failures there have been.
+const MAX_BACKOFF: std::time::Duration = std::time::Duration::from_secs(30);
+
+impl ReconnectBackoff {
+    /// Returns whether enough time has passed since the last attempt (if
+    /// any) for another one to be worth making. If so, records `now` as the
+    /// new last-attempt time so a concurrent caller sees the updated pacing
+    /// immediately, rather than after the attempt finishes.
+    fn ready(&self) -> bool {
+        let mut last_attempt = self.last_attempt.lock().unwrap();
+        let now = crate::sys::time::Instant::now();
+        if let Some(last) = *last_attempt {
+            // The first retry (one failure so far) waits INITIAL_BACKOFF itself.
+            let failures = self.failures.load(Ordering::Relaxed).saturating_sub(1).min(16) as u32;
+            let delay = INITIAL_BACKOFF
+                .saturating_mul(1u32 << failures)
+                .min(MAX_BACKOFF);
+            if now.duration_since(last) < delay {
+                return false;
+            }
+        }
+        *last_attempt = Some(now);
+        true
+    }
+
+    /// Records a failed attempt, lengthening the delay before the next one.
+    fn record_failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Resets the backoff after a successful connection, so the next outage
+    /// starts from [`INITIAL_BACKOFF`] again.
+    fn reset(&self) {
+        self.failures.store(0, Ordering::Relaxed);
+        *self.last_attempt.lock().unwrap() = None;
+    }
+}
+
+/// What [`NotificationBuffer::push`] does when the buffer is already at
+/// capacity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Evict the oldest queued notification to make room, logging the
+    /// eviction so telemetry loss isn't silent. The default.
+    #[default]
+    DropOldest,
+    /// Discard the incoming notification, leaving the buffer unchanged.
+    DropNewest,
+    /// Wait for room to free up. On wasm32, where the caller is assumed to
+    /// be the main thread and must never block, this instead fails
+    /// immediately with [`Error::BufferFull`].
+    Block,
+}
+
+/// Capacity and [`OverflowPolicy`] for [`InternalProxy`]'s notification
+/// buffer; set with [`set_buffer_config`].
+#[derive(Debug, Clone, Copy)]
+pub struct BufferConfig {
+    /// The maximum number of notifications the buffer holds at once.
+    pub capacity: usize,
+    /// What happens when a push would exceed `capacity`.
+    pub overflow: OverflowPolicy,
+}
+
+impl Default for BufferConfig {
+    fn default() -> Self {
+        BufferConfig {
+            capacity: 1024,
+            overflow: OverflowPolicy::DropOldest,
+        }
+    }
+}
+
+/// The process-wide [`BufferConfig`], set with [`set_buffer_config`].
+static BUFFER_CONFIG: LazyLock<RwLock<BufferConfig>> =
+    LazyLock::new(|| RwLock::new(BufferConfig::default()));
+
+/// Sets the [`BufferConfig`] used for notifications buffered by
+/// [`InternalProxy::buffer_notification`].
+///
+/// Only takes effect for `InternalProxy` instances created afterward --
+/// in practice, this means calling it before the first use of
+/// [`InternalProxy::current`], since the singleton is created lazily on
+/// first access and its buffer capacity is fixed at that point.
+pub fn set_buffer_config(config: BufferConfig) {
+    *BUFFER_CONFIG.write().unwrap() = config;
+}
+
+/// A bounded ring buffer of notifications awaiting a connection, with an
+/// explicit [`OverflowPolicy`] for what happens when it's full.
+///
+/// Replaces an earlier unbounded `mpsc::channel`, which let the queue grow
+/// without limit if the connection stayed down during heavy startup
+/// logging.
+#[derive(Debug)]
+struct NotificationBuffer {
+    capacity: usize,
+    overflow: OverflowPolicy,
+    queue: Mutex<std::collections::VecDeque<crate::jrpc::Notification>>,
+    room: std::sync::Condvar,
+}
+
+impl NotificationBuffer {
+    fn new(config: BufferConfig) -> Self {
+        NotificationBuffer {
+            capacity: config.capacity.max(1),
+            overflow: config.overflow,
+            queue: Mutex::new(std::collections::VecDeque::new()),
+            room: std::sync::Condvar::new(),
+        }
+    }
+
+    /// Pushes `notification` onto the buffer, applying [`OverflowPolicy`]
+    /// if it's already at capacity.
+    fn push(&self, notification: crate::jrpc::Notification) -> Result<(), Error> {
+        let mut queue = self.queue.lock().unwrap();
+        loop {
+            if queue.len() < self.capacity {
+                queue.push_back(notification);
+                return Ok(());
+            }
+            match self.overflow {
+                OverflowPolicy::DropNewest => {
+                    eprintln!(
+                        "ip: notification buffer full ({} queued), dropping newest",
+                        self.capacity
+                    );
+                    return Ok(());
+                }
+                OverflowPolicy::DropOldest => {
+                    queue.pop_front();
+                    eprintln!(
+                        "ip: notification buffer full ({} queued), evicted oldest",
+                        self.capacity
+                    );
+                    queue.push_back(notification);
+                    return Ok(());
+                }
+                OverflowPolicy::Block => {
+                    #[cfg(target_arch = "wasm32")]
+                    {
+                        //the assumed caller (the main thread) must never block
+                        return Err(Error::BufferFull);
+                    }
+                    #[cfg(not(target_arch = "wasm32"))]
+                    {
+                        queue = self.room.wait(queue).unwrap();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes and returns every currently-buffered notification, waking any
+    /// [`OverflowPolicy::Block`] pusher waiting for room.
+    fn drain(&self) -> Vec<crate::jrpc::Notification> {
+        let mut queue = self.queue.lock().unwrap();
+        let drained = queue.drain(..).collect::<Vec<_>>();
+        drop(queue);
+        if !drained.is_empty() {
+            self.room.notify_all();
+        }
+        drained
+    }
 }
 
 /// Global singleton instance of the internal proxy.
@@ -95,17 +338,19 @@ static INTERNAL_PROXY: LazyLock<InternalProxy> = LazyLock::new(|| InternalProxy:
 
 /// Platform-specific write stream type.
 ///
-/// - On native platforms: Uses `TcpStream` for writing
+/// - On native platforms: Uses [`NativeStream`], which covers both TCP and
+///   IPC (see [`Endpoint`])
 /// - On WebAssembly: Uses `websocket_adapter::WriteAdapter`
 #[cfg(not(target_arch = "wasm32"))]
-type WriteStream = TcpStream;
+type WriteStream = NativeStream;
 
 /// Platform-specific read stream type.
 ///
-/// - On native platforms: Uses `TcpStream` for reading
+/// - On native platforms: Uses [`NativeStream`], which covers both TCP and
+///   IPC (see [`Endpoint`])
 /// - On WebAssembly: Uses `websocket_adapter::ReadAdapter`
 #[cfg(not(target_arch = "wasm32"))]
-type ReadStream = TcpStream;
+type ReadStream = NativeStream;
 
 /// Platform-specific write stream type for WebAssembly.
 #[cfg(target_arch = "wasm32")]
@@ -115,6 +360,147 @@ type WriteStream = websocket_adapter::WriteAdapter;
 #[cfg(target_arch = "wasm32")]
 type ReadStream = websocket_adapter::ReadApapter;
 
+/// Where [`InternalProxy`] connects, on native platforms.
+///
+/// Selected via [`set_endpoint`]; defaults to [`Endpoint::Tcp`] against the
+/// crate's historical `127.0.0.1:1985` loopback address. Only consulted on
+/// native platforms -- wasm32 always connects over WebSocket via
+/// `websocket_adapter`, which this setting doesn't affect.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone)]
+pub enum Endpoint {
+    /// Connect over TCP to this address. The crate's historical default.
+    Tcp(std::net::SocketAddr),
+    /// Connect over a local IPC channel at this filesystem path: a Unix
+    /// domain socket on Unix. Avoids the loopback TCP port entirely, which
+    /// matters when multiple co-located processes share one machine.
+    ///
+    /// Not yet implemented on Windows (named pipes need a platform crate
+    /// this workspace doesn't currently depend on); connecting to this
+    /// variant there fails immediately with [`Error::NotConnected`].
+    Ipc(std::path::PathBuf),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Default for Endpoint {
+    fn default() -> Self {
+        Endpoint::Tcp(ADDR.parse().expect("ADDR is a valid socket address"))
+    }
+}
+
+/// The process-wide [`Endpoint`], set with [`set_endpoint`].
+#[cfg(not(target_arch = "wasm32"))]
+static ENDPOINT: LazyLock<RwLock<Endpoint>> = LazyLock::new(|| RwLock::new(Endpoint::default()));
+
+/// Sets the [`Endpoint`] [`InternalProxy`] connects to on native platforms.
+///
+/// Takes effect on the next connection attempt; call
+/// [`InternalProxy::restart`] to apply it to an already-connected proxy.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set_endpoint(endpoint: Endpoint) {
+    *ENDPOINT.write().unwrap() = endpoint;
+}
+
+/// A native connection to the remote endpoint, wrapping whichever concrete
+/// stream type [`Endpoint`] selected.
+///
+/// `BidirectionalProxy::new` is generic over a single concrete read/write
+/// pair, but which stream type that should be depends on a runtime value
+/// (the configured [`Endpoint`]), so this enum stands in for "whichever one
+/// we actually connected with" and forwards [`WriteTransport`]/
+/// [`ReadTransport`] to the matching variant.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug)]
+enum NativeStream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Ipc(std::os::unix::net::UnixStream),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl NativeStream {
+    /// Connects to `endpoint`, producing the matching stream variant.
+    fn connect(endpoint: &Endpoint) -> std::io::Result<Self> {
+        match endpoint {
+            Endpoint::Tcp(addr) => TcpStream::connect(addr).map(NativeStream::Tcp),
+            #[cfg(unix)]
+            Endpoint::Ipc(path) => {
+                std::os::unix::net::UnixStream::connect(path).map(NativeStream::Ipc)
+            }
+            #[cfg(not(unix))]
+            Endpoint::Ipc(_) => Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "IPC endpoints require a named-pipe transport not yet implemented on this platform",
+            )),
+        }
+    }
+
+    /// Clones the stream for a separate read handle, mirroring
+    /// `TcpStream::try_clone`.
+    fn try_clone(&self) -> std::io::Result<Self> {
+        match self {
+            NativeStream::Tcp(stream) => stream.try_clone().map(NativeStream::Tcp),
+            #[cfg(unix)]
+            NativeStream::Ipc(stream) => stream.try_clone().map(NativeStream::Ipc),
+        }
+    }
+
+    /// Puts the underlying stream into (non-)blocking mode, mirroring
+    /// `TcpStream::set_nonblocking`.
+    fn set_nonblocking(&self, nonblocking: bool) -> std::io::Result<()> {
+        match self {
+            NativeStream::Tcp(stream) => stream.set_nonblocking(nonblocking),
+            #[cfg(unix)]
+            NativeStream::Ipc(stream) => stream.set_nonblocking(nonblocking),
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl WriteTransport for NativeStream {
+    fn write(&mut self, data: &[u8]) -> Result<usize, TransportError> {
+        let result = match self {
+            NativeStream::Tcp(stream) => std::io::Write::write(stream, data),
+            #[cfg(unix)]
+            NativeStream::Ipc(stream) => std::io::Write::write(stream, data),
+        };
+        match result {
+            Ok(size) => Ok(size),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(0),
+            Err(e) => Err(e).io_context("writing to the native stream"),
+        }
+    }
+
+    fn flush(&mut self) -> Result<(), TransportError> {
+        let result = match self {
+            NativeStream::Tcp(stream) => std::io::Write::flush(stream),
+            #[cfg(unix)]
+            NativeStream::Ipc(stream) => std::io::Write::flush(stream),
+        };
+        result.io_context("flushing the native stream")
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl ReadTransport for NativeStream {
+    fn try_read(&mut self, buf: &mut [u8]) -> Result<Option<usize>, TransportError> {
+        self.set_nonblocking(true).unwrap();
+        loop {
+            let result = match self {
+                NativeStream::Tcp(stream) => std::io::Read::read(stream, buf),
+                #[cfg(unix)]
+                NativeStream::Ipc(stream) => std::io::Read::read(stream, buf),
+            };
+            match result {
+                Ok(size) => return Ok(Some(size)),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(None),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue, // EINTR, retry
+                Err(e) => return Err(e).io_context("reading from the native stream"),
+            }
+        }
+    }
+}
+
 /// Internal proxy for handling JSON-RPC communication.
 ///
 /// This struct manages a bidirectional communication channel using either
@@ -134,66 +520,84 @@ type ReadStream = websocket_adapter::ReadApapter;
 /// is established.
 #[derive(Debug)]
 pub struct InternalProxy {
-    /// Sender for buffering notifications.
-    ///
-    /// In practice, notifications are sent from the main thread on wasm,
-    /// so we can't use a simple Mutex.
-    buffered_notification_sender: std::sync::mpsc::Sender<crate::jrpc::Notification>,
-
-    /// Receiver for buffered notifications.
-    ///
-    /// Protected by a Mutex, but we can simply fail if the lock is contended.
-    buffered_notification_receiver: Mutex<std::sync::mpsc::Receiver<crate::jrpc::Notification>>,
+    /// Notifications queued via [`InternalProxy::buffer_notification`] that
+    /// haven't been sent yet; see [`NotificationBuffer`] for capacity and
+    /// overflow handling.
+    notification_buffer: NotificationBuffer,
 
     /// The underlying bidirectional proxy for message transport.
     ///
     /// Uses `OnceNonLock` to avoid blocking during initialization.
     bidirectional_proxy: Arc<OnceNonLock<BidirectionalProxy>>,
+
+    /// Requests sent via [`InternalProxy::send_request`] that are still
+    /// awaiting their response.
+    pending_requests: Arc<PendingRequests>,
+
+    /// Serializes outgoing notifications and requests, and recognizes
+    /// inbound responses; see [`codec`] for why this doesn't also cover
+    /// inbound request dispatch.
+    codec: Arc<dyn Codec>,
+
+    /// Paces [`InternalProxy::reconnect_if_possible`]'s retries during an
+    /// outage; see [`ReconnectBackoff`]. `Arc`-wrapped so the wasm32 branch
+    /// can update it from inside an async block, the same way `codec` and
+    /// `pending_requests` are shared with their background callbacks.
+    reconnect_backoff: Arc<ReconnectBackoff>,
 }
 
 /// Callback function for processing incoming bidirectional messages.
 ///
 /// This function is called by the `BidirectionalProxy` when a message is received.
-/// It attempts to parse the message as a JSON-RPC request and dispatch it to
-/// the appropriate handler.
+/// It first uses `codec` to recognize a reply to one of our own requests (see
+/// [`InternalProxy::send_request`]); anything else is dispatched via
+/// [`crate::mcp::dispatch_payload`], which accepts either a single JSON-RPC
+/// request object or a JSON-RPC 2.0 batch (a JSON array of request objects).
+/// Request dispatch is always JSON, regardless of `codec`, since
+/// `dispatch_payload` is shared with other, JSON-only transports.
 ///
 /// # Arguments
 ///
+/// * `codec` - Recognizes inbound responses in whatever format `InternalProxy`
+///   was configured to speak
+/// * `pending_requests` - Waiters for responses to our own outgoing requests
 /// * `msg` - The raw message bytes received from the remote endpoint
 ///
 /// # Returns
 ///
-/// * `Some(response)` - A serialized JSON-RPC response if the message was a valid request
-/// * `None` - If the message could not be processed (currently causes a panic)
-///
-/// # Panics
-///
-/// Currently panics if the received message cannot be parsed as a valid JSON-RPC request.
-fn bidi_fn(msg: Box<[u8]>) -> Option<Box<[u8]>> {
-    //attempt parse as request
+/// * `Some(response)` - The serialized JSON-RPC response (or batch of responses)
+/// * `None` - The payload contained only notifications, so there is nothing to
+///   send back
+fn bidi_fn(
+    codec: &dyn Codec,
+    pending_requests: &PendingRequests,
+    msg: Box<[u8]>,
+) -> Option<Box<[u8]>> {
     eprintln!(
         "ip: received bidi message: {:?}",
         String::from_utf8_lossy(&msg)
     );
-    let request: Result<crate::jrpc::Request, _> = serde_json::from_slice(&msg);
-    match request {
-        Ok(request) => {
-            eprintln!("ip: received request: {:?}", request);
-            let response = crate::mcp::dispatch_in_target(request);
-            let response_bytes = serde_json::to_vec(&response).unwrap();
+    //a JSON-RPC response always carries a `result` or `error` and never a
+    //`method`; if this looks like one, it's the reply to a request we sent
+    //via `InternalProxy::send_request`, not something to dispatch
+    if let Ok(response) = codec.decode_response(&msg)
+        && (response.result.is_some() || response.error.is_some())
+    {
+        let id = response.id.clone();
+        if !pending_requests.resolve(response) {
             eprintln!(
-                "ip: sending response {:?}",
-                String::from_utf8_lossy(&response_bytes)
-            );
-            Some(response_bytes.into_boxed_slice())
-        }
-        Err(e) => {
-            todo!(
-                "Not implemented yet: Received request from internal proxy: {:?}",
-                e
+                "ip: received response with no matching pending request: id={:?}",
+                id
             );
         }
+        return None;
     }
+    let response_bytes = crate::mcp::dispatch_payload(&msg)?;
+    eprintln!(
+        "ip: sending response {:?}",
+        String::from_utf8_lossy(&response_bytes)
+    );
+    Some(response_bytes.into_boxed_slice())
 }
 
 /// The address to connect to for the internal proxy on native platforms.
@@ -204,18 +608,19 @@ impl InternalProxy {
     /// Creates a new instance of the internal proxy.
     ///
     /// This constructor:
-    /// 1. Sets up the notification buffering channels
+    /// 1. Sets up the notification buffer (see [`set_buffer_config`])
     /// 2. Initializes the bidirectional proxy connection
     /// 3. Attempts an initial connection to the remote endpoint
     ///
     /// The connection attempt is non-blocking and will be retried
     /// automatically when sending notifications.
     fn new() -> Self {
-        let (sender, receiver) = std::sync::mpsc::channel();
         let m = InternalProxy {
-            buffered_notification_sender: sender,
-            buffered_notification_receiver: Mutex::new(receiver),
+            notification_buffer: NotificationBuffer::new(*BUFFER_CONFIG.read().unwrap()),
             bidirectional_proxy: Arc::new(OnceNonLock::new()),
+            pending_requests: Arc::new(PendingRequests::default()),
+            codec: codec::default_codec(),
+            reconnect_backoff: Arc::new(ReconnectBackoff::default()),
         };
         m.reconnect_if_possible();
         m
@@ -224,34 +629,56 @@ impl InternalProxy {
     /// Attempts to establish or re-establish the connection to the remote endpoint.
     ///
     /// This method is platform-specific:
-    /// - On native platforms: Attempts a synchronous TCP connection
+    /// - On native platforms: Attempts a synchronous connection to the
+    ///   current [`Endpoint`] (TCP or IPC)
     /// - On WebAssembly: Initiates an asynchronous WebSocket connection
     ///
     /// The method is non-blocking and will not wait for the connection to complete.
-    /// If a connection is already established or in progress, this method does nothing.
+    /// If a connection is already established or in progress, this method does
+    /// nothing. If a previous attempt failed recently, this also does nothing
+    /// until [`ReconnectBackoff`] says enough time has passed -- otherwise every
+    /// `send_notification` during an outage would retry connecting immediately.
     fn reconnect_if_possible(&self) {
+        if self.bidirectional_proxy.get().is_some() || !self.reconnect_backoff.ready() {
+            return;
+        }
         #[cfg(not(target_arch = "wasm32"))]
-        self.bidirectional_proxy.try_get_or_init(|| {
-            let s = TcpStream::connect(ADDR);
-            match s {
-                Ok(stream) => {
-                    let write_stream = stream
-                        .try_clone()
-                        .expect("Failed to clone stream for writing");
-                    let read_stream = stream;
-                    let stream = crate::bidirectional_proxy::BidirectionalProxy::new(
-                        write_stream,
-                        read_stream,
-                        bidi_fn,
-                    );
-                    Some(stream)
+        {
+            let connected = self.bidirectional_proxy.try_get_or_init(|| {
+                let endpoint = ENDPOINT.read().unwrap().clone();
+                let s = NativeStream::connect(&endpoint);
+                match s {
+                    Ok(stream) => {
+                        let write_stream = stream
+                            .try_clone()
+                            .expect("Failed to clone stream for writing");
+                        let read_stream = stream;
+                        let pending_requests = self.pending_requests.clone();
+                        let codec = self.codec.clone();
+                        let stream = crate::bidirectional_proxy::BidirectionalProxy::new(
+                            write_stream,
+                            read_stream,
+                            move |msg| bidi_fn(codec.as_ref(), &pending_requests, msg),
+                        );
+                        Some(stream)
+                    }
+                    Err(_e) => return None,
                 }
-                Err(_e) => return None,
+            });
+            if connected.is_some() {
+                self.reconnect_backoff.reset();
+            } else {
+                self.reconnect_backoff.record_failure();
             }
-        });
+        }
         #[cfg(target_arch = "wasm32")]
         {
-            //on wasm, we need to connect asynchronously
+            //on wasm, we need to connect asynchronously, so the backoff is
+            //updated once the attempt actually resolves rather than here
+            let pending_requests = self.pending_requests.clone();
+            let codec = self.codec.clone();
+            let bidirectional_proxy = self.bidirectional_proxy.clone();
+            let reconnect_backoff = self.reconnect_backoff.clone();
             let f = self.bidirectional_proxy.init_async(async move || {
                 if web_sys::window().is_none() {
                     crate::internal_proxy::websocket_adapter::patch_close();
@@ -260,7 +687,9 @@ impl InternalProxy {
                 match stream {
                     Ok(stream) => {
                         let stream = crate::bidirectional_proxy::BidirectionalProxy::new(
-                            stream.0, stream.1, bidi_fn,
+                            stream.0,
+                            stream.1,
+                            move |msg| bidi_fn(codec.as_ref(), &pending_requests, msg),
                         );
                         Some(stream)
                     }
@@ -270,10 +699,43 @@ impl InternalProxy {
                     }
                 }
             });
-            wasm_bindgen_futures::spawn_local(f)
+            wasm_bindgen_futures::spawn_local(async move {
+                f.await;
+                if bidirectional_proxy.get().is_some() {
+                    reconnect_backoff.reset();
+                } else {
+                    reconnect_backoff.record_failure();
+                }
+            });
         }
     }
 
+    /// Tears down the current connection (if any) and clears it so the next
+    /// call to [`InternalProxy::reconnect_if_possible`] -- whether driven by
+    /// [`InternalProxy::send_notification`]/[`InternalProxy::send_request`]
+    /// or by an explicit [`InternalProxy::restart`] -- establishes a fresh
+    /// one. Buffered notifications that haven't been sent yet are
+    /// unaffected and will still be flushed once reconnected.
+    ///
+    /// Does nothing if there is no current connection.
+    pub fn disconnect(&self) {
+        self.bidirectional_proxy.take();
+    }
+
+    /// Disconnects (see [`InternalProxy::disconnect`]) and immediately
+    /// attempts to reconnect, bypassing the current backoff delay -- this is
+    /// an explicit request, not a retry during an outage.
+    ///
+    /// The new connection attempt is made the same way as
+    /// [`InternalProxy::reconnect_if_possible`] (synchronous on native,
+    /// asynchronous on WebAssembly), so on WebAssembly this may still return
+    /// before the new connection is established.
+    pub fn restart(&self) {
+        self.disconnect();
+        self.reconnect_backoff.reset();
+        self.reconnect_if_possible();
+    }
+
     /// Sends a JSON-RPC notification through the proxy.
     ///
     /// This method attempts to send a notification immediately. It will first
@@ -312,13 +774,66 @@ impl InternalProxy {
     pub fn send_notification(&self, notification: crate::jrpc::Notification) -> Result<(), Error> {
         self.send_buffered_if_possible();
         if let Some(proxy) = self.bidirectional_proxy.get() {
-            let msg = serde_json::to_string(&notification).map_err(|_| NotConnected)?;
-            proxy.send(msg.as_bytes()).map_err(|_| NotConnected)
+            let msg = self.codec.encode_notification(&notification);
+            proxy.send(&msg).map_err(|_| NotConnected)
         } else {
             //not connected
             Err(NotConnected)
         }
     }
+
+    /// Sends a JSON-RPC request through the proxy and blocks until its
+    /// response arrives.
+    ///
+    /// Unlike [`InternalProxy::send_notification`], this correlates the
+    /// reply with the request that provoked it -- the response is routed
+    /// back by [`bidi_fn`] via an internal [`PendingRequests`] registry --
+    /// so the caller gets back exactly the response meant for it even if
+    /// other traffic is interleaved on the same connection.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - The JSON-RPC request to send. Its `id` is overwritten
+    ///   with one allocated by the proxy, so the response can be matched
+    ///   unambiguously even if the caller reuses ids.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Response)` - the matching response
+    /// * `Err(Error::NotConnected)` - no connection is available
+    /// * `Err(Error::NoResponse)` - the connection died before a response
+    ///   arrived; while it stays up, this waits with no timeout
+    pub fn send_request(
+        &self,
+        mut request: crate::jrpc::Request,
+    ) -> Result<crate::jrpc::Response<serde_json::Value>, Error> {
+        self.send_buffered_if_possible();
+        let Some(proxy) = self.bidirectional_proxy.get() else {
+            return Err(NotConnected);
+        };
+        let (id, receiver) = self.pending_requests.register();
+        let key = id.to_string();
+        request.id = id;
+        let msg = self.codec.encode_request(&request);
+        proxy.send(&msg).map_err(|_| NotConnected)?;
+        // The waiter stays registered while the connection lives, so poll
+        // the proxy's state to notice a connection that died (or was
+        // disconnected) mid-request.
+        loop {
+            match receiver.recv_timeout(std::time::Duration::from_millis(100)) {
+                Ok(response) => return Ok(response),
+                Err(std::sync::mpsc::RecvTimeoutError::Timeout)
+                    if self.bidirectional_proxy.get().is_some_and(|p| {
+                        p.state() != crate::bidirectional_proxy::ConnectionState::Dead
+                    }) => {}
+                Err(_) => {
+                    self.pending_requests.waiters.lock().unwrap().remove(&key);
+                    return Err(Error::NoResponse);
+                }
+            }
+        }
+    }
+
     /// Buffers a notification for later sending.
     ///
     /// This method adds a notification to the buffer and immediately attempts
@@ -330,9 +845,13 @@ impl InternalProxy {
     ///
     /// * `notification` - The JSON-RPC notification to buffer
     ///
-    /// # Panics
+    /// # Returns
     ///
-    /// Panics if the internal channel is disconnected (should not happen in normal operation).
+    /// * `Ok(())` - the notification was queued (possibly evicting another,
+    ///   under [`OverflowPolicy::DropOldest`]/[`OverflowPolicy::DropNewest`])
+    /// * `Err(Error::BufferFull)` - the buffer was at capacity under
+    ///   [`OverflowPolicy::Block`] and blocking wasn't possible; see that
+    ///   variant
     ///
     /// # Example
     ///
@@ -349,41 +868,27 @@ impl InternalProxy {
     /// );
     ///
     /// let proxy = InternalProxy::current();
-    /// proxy.buffer_notification(notification);
+    /// let _ = proxy.buffer_notification(notification);
     /// // The notification will be sent when a connection becomes available
     /// ```
-    pub fn buffer_notification(&self, notification: crate::jrpc::Notification) {
-        self.buffered_notification_sender
-            .send(notification)
-            .unwrap();
+    pub fn buffer_notification(&self, notification: crate::jrpc::Notification) -> Result<(), Error> {
+        self.notification_buffer.push(notification)?;
         self.send_buffered_if_possible();
+        Ok(())
     }
 
     /// Attempts to send all buffered notifications.
     ///
     /// This method:
     /// 1. Attempts to reconnect if not connected
-    /// 2. Tries to acquire the receiver lock (non-blocking)
-    /// 3. Drains all buffered notifications
-    /// 4. Sends each notification through the proxy
-    ///
-    /// If the receiver lock is contended, this method will log a message
-    /// and return without sending notifications (they remain buffered).
+    /// 2. Drains all buffered notifications
+    /// 3. Sends each notification through the proxy
     fn send_buffered_if_possible(&self) {
         self.reconnect_if_possible();
         if let Some(proxy) = self.bidirectional_proxy.get() {
-            //short lock
-            let mut take = Vec::new();
-            if let Some(buffered_receiver) = self.buffered_notification_receiver.try_lock().ok() {
-                while let Some(notification) = buffered_receiver.try_recv().ok() {
-                    take.push(notification);
-                }
-            } else {
-                crate::logging::log(&"ip: Send contended");
-            }
-            for notification in take {
-                let msg = serde_json::to_string(&notification).unwrap();
-                if let Err(e) = proxy.send(msg.as_bytes()) {
+            for notification in self.notification_buffer.drain() {
+                let msg = self.codec.encode_notification(&notification);
+                if let Err(e) = proxy.send(&msg) {
                     crate::logging::log(&format!(
                         "ip: Failed to send buffered notification: {}",
                         e
@@ -423,7 +928,7 @@ impl InternalProxy {
     ///     "status".to_string(),
     ///     Some(json!({"ready": true}))
     /// );
-    /// proxy.buffer_notification(notification);
+    /// let _ = proxy.buffer_notification(notification);
     /// ```
     pub fn current() -> &'static InternalProxy {
         &INTERNAL_PROXY
@@ -0,0 +1,91 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Wire codecs for [`super::InternalProxy`]'s outgoing messages.
+//!
+//! `bidirectional_proxy` already deals in raw byte frames, so swapping the
+//! serialization format is just a matter of picking what fills those
+//! frames. [`JsonCodec`] is the crate's historical behavior; the
+//! `msgpack` feature adds [`MsgPackCodec`], a more compact binary format
+//! worth using on bandwidth-sensitive paths (high-volume logging, the
+//! WebAssembly WebSocket transport).
+//!
+//! This only covers the messages `InternalProxy` itself originates
+//! (notifications and requests) plus the inbound decode needed to
+//! recognize a response to one of its own requests; dispatching an
+//! inbound *request* still goes through [`crate::mcp::dispatch_payload`],
+//! which is shared with other JSON-speaking transports and is out of
+//! scope here.
+
+use crate::jrpc::{Notification, Request, Response};
+
+/// A codec failed to decode a byte frame into the requested message shape.
+#[derive(Debug)]
+pub(crate) struct DecodeError;
+
+/// Encodes and decodes the JSON-RPC messages `InternalProxy` sends and
+/// receives.
+pub(crate) trait Codec: Send + Sync + std::fmt::Debug {
+    /// Serializes an outgoing notification.
+    fn encode_notification(&self, notification: &Notification) -> Box<[u8]>;
+    /// Serializes an outgoing request.
+    fn encode_request(&self, request: &Request) -> Box<[u8]>;
+    /// Attempts to decode `bytes` as a response. Used to recognize replies
+    /// to requests sent via [`super::InternalProxy::send_request`].
+    fn decode_response(&self, bytes: &[u8]) -> Result<Response<serde_json::Value>, DecodeError>;
+}
+
+/// The default codec: plain JSON, one object per message.
+#[derive(Debug)]
+pub(crate) struct JsonCodec;
+
+impl Codec for JsonCodec {
+    fn encode_notification(&self, notification: &Notification) -> Box<[u8]> {
+        serde_json::to_vec(notification)
+            .unwrap()
+            .into_boxed_slice()
+    }
+
+    fn encode_request(&self, request: &Request) -> Box<[u8]> {
+        serde_json::to_vec(request).unwrap().into_boxed_slice()
+    }
+
+    fn decode_response(&self, bytes: &[u8]) -> Result<Response<serde_json::Value>, DecodeError> {
+        serde_json::from_slice(bytes).map_err(|_| DecodeError)
+    }
+}
+
+/// A [`Codec`] built on MessagePack (via `rmp-serde`), as used by
+/// `rmp-rpc`. Substantially smaller on the wire than JSON for the same
+/// message, at the cost of not being human-readable.
+#[cfg(feature = "msgpack")]
+#[derive(Debug)]
+pub(crate) struct MsgPackCodec;
+
+#[cfg(feature = "msgpack")]
+impl Codec for MsgPackCodec {
+    fn encode_notification(&self, notification: &Notification) -> Box<[u8]> {
+        rmp_serde::to_vec(notification)
+            .unwrap()
+            .into_boxed_slice()
+    }
+
+    fn encode_request(&self, request: &Request) -> Box<[u8]> {
+        rmp_serde::to_vec(request).unwrap().into_boxed_slice()
+    }
+
+    fn decode_response(&self, bytes: &[u8]) -> Result<Response<serde_json::Value>, DecodeError> {
+        rmp_serde::from_slice(bytes).map_err(|_| DecodeError)
+    }
+}
+
+/// Builds the codec `InternalProxy` uses by default: [`MsgPackCodec`] when
+/// the `msgpack` feature is enabled, otherwise [`JsonCodec`].
+pub(crate) fn default_codec() -> std::sync::Arc<dyn Codec> {
+    #[cfg(feature = "msgpack")]
+    {
+        std::sync::Arc::new(MsgPackCodec)
+    }
+    #[cfg(not(feature = "msgpack"))]
+    {
+        std::sync::Arc::new(JsonCodec)
+    }
+}
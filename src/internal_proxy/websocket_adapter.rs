@@ -31,7 +31,9 @@
 #![cfg(target_arch = "wasm32")]
 
 use super::super::logging::log;
+use rand::Rng;
 use std::fmt::Display;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use wasm_bindgen::JsCast;
 
@@ -57,7 +59,7 @@ use wasm_bindgen::closure::Closure;
 /// }
 /// # }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Error {
     /// Failed to establish a WebSocket connection.
     ///
@@ -69,7 +71,72 @@ pub enum Error {
 impl Display for Error {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            _ => write!(f, "WebsocketAdapter error"),
+            Error::CantConnect(msg) => write!(f, "WebsocketAdapter: can't connect: {}", msg),
+        }
+    }
+}
+
+/// Why a WebSocket connection closed, classified from the browser's
+/// `CloseEvent` per the status code ranges in RFC 6455 section 7.4.
+///
+/// `ReadApapter::read_nonblock` treats [`CloseReason::Normal`] as a clean
+/// EOF (no more data is coming, but nothing went wrong), while the other
+/// variants surface as a real `bidirectional_proxy::Error`.
+#[derive(Debug, Clone)]
+pub enum CloseReason {
+    /// Close code 1000: both endpoints agreed the connection is done.
+    Normal { reason: String },
+    /// Close code 1002: the peer received data it couldn't parse as a
+    /// valid WebSocket frame.
+    Protocol { reason: String },
+    /// The connection dropped without completing the close handshake
+    /// (`CloseEvent.wasClean` is `false`), e.g. a dropped network link or a
+    /// server crash -- this is also what's reported if an `ErrorEvent` fired
+    /// just before the close.
+    Abnormal { code: u16, reason: String },
+    /// Any other close code (e.g. 1001 Going Away, 1011 Internal Error).
+    Other { code: u16, reason: String },
+}
+
+impl CloseReason {
+    /// Classifies a browser `CloseEvent`, folding in `last_error` (the most
+    /// recent `ErrorEvent` message, if any) as the reason when the browser
+    /// didn't supply one of its own.
+    fn from_event(event: &web_sys::CloseEvent, last_error: Option<String>) -> Self {
+        let code = event.code();
+        let reason = if event.reason().is_empty() {
+            last_error.unwrap_or_default()
+        } else {
+            event.reason()
+        };
+        if !event.was_clean() {
+            CloseReason::Abnormal { code, reason }
+        } else {
+            match code {
+                1000 => CloseReason::Normal { reason },
+                1002 => CloseReason::Protocol { reason },
+                _ => CloseReason::Other { code, reason },
+            }
+        }
+    }
+
+    /// Whether both endpoints agreed the connection is done, as opposed to
+    /// it dropping unexpectedly -- see [`worker_thread`], which only
+    /// attempts to reconnect for the latter.
+    fn is_normal(&self) -> bool {
+        matches!(self, CloseReason::Normal { .. })
+    }
+}
+
+impl Display for CloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CloseReason::Normal { reason } => write!(f, "closed normally ({})", reason),
+            CloseReason::Protocol { reason } => write!(f, "protocol error ({})", reason),
+            CloseReason::Abnormal { code, reason } => {
+                write!(f, "abnormal closure (code {}, {})", code, reason)
+            }
+            CloseReason::Other { code, reason } => write!(f, "closed (code {}, {})", code, reason),
         }
     }
 }
@@ -112,10 +179,40 @@ impl<T> Clone for OneShot<T> {
 
 /// The WebSocket endpoint address.
 ///
-/// This is the address the adapter connects to when establishing
-/// a WebSocket connection on WebAssembly platforms.
+/// This is the default address the adapter connects to when establishing
+/// a WebSocket connection on WebAssembly platforms; see [`WebSocketConfig`]
+/// to override it.
 const ADDR: &str = "ws://localhost:1984";
 
+/// Configuration for a WebSocket adapter connection.
+///
+/// Modeled on `tungstenite`'s `WebSocketConfig`: lets a caller override the
+/// endpoint URL and cap resource usage, so a misbehaving or malicious peer
+/// can't grow the adapter's buffers without limit.
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    /// The WebSocket URL to connect to.
+    pub url: String,
+    /// Maximum size, in bytes, of a single inbound message. A message
+    /// exceeding this drops the socket with an error instead of being
+    /// buffered into [`ReadApapter`].
+    pub max_message_size: Option<usize>,
+    /// Maximum number of bytes the browser may hold in its outbound
+    /// WebSocket send queue (`WebSocket.bufferedAmount`) before
+    /// backpressure applies to [`WriteAdapter::flush`].
+    pub max_send_queue: Option<usize>,
+}
+
+impl Default for WebSocketConfig {
+    fn default() -> Self {
+        WebSocketConfig {
+            url: ADDR.to_string(),
+            max_message_size: None,
+            max_send_queue: None,
+        }
+    }
+}
+
 /// Write adapter for sending data through a WebSocket connection.
 ///
 /// This adapter implements the `WriteTransport` trait, allowing the
@@ -143,7 +240,12 @@ const ADDR: &str = "ws://localhost:1984";
 /// ```
 #[derive(Debug)]
 pub struct WriteAdapter {
-    send: continue_stream::Sender<Vec<u8>>,
+    channels: Arc<Channels>,
+    /// Bytes accumulated by [`WriteTransport::write`] since the last
+    /// [`WriteTransport::flush`], so a burst of small writes is coalesced
+    /// into a single WebSocket frame instead of paying per-frame overhead
+    /// for each one.
+    buf: Vec<u8>,
 }
 
 /// Read adapter for receiving data from a WebSocket connection.
@@ -179,10 +281,39 @@ pub struct WriteAdapter {
 /// ```
 #[derive(Debug)]
 pub struct ReadApapter {
-    recv: std::sync::mpsc::Receiver<Vec<u8>>,
+    channels: Arc<Channels>,
     buf: Vec<u8>,
 }
 
+/// The swappable channel endpoints behind a [`WriteAdapter`]/[`ReadApapter`]
+/// pair.
+///
+/// `worker_thread` reconnects by replacing the `Sender`/`Receiver` held here
+/// in place (see [`reconnect`]), rather than handing the caller a new pair --
+/// so a `BidirectionalProxy` already built on top of the original adapters
+/// keeps working transparently across a dropped-and-restored connection.
+#[derive(Debug)]
+struct Channels {
+    write_send: Mutex<continue_stream::Sender<Vec<u8>>>,
+    read_recv: Mutex<std::sync::mpsc::Receiver<Vec<u8>>>,
+    /// Set once the connection is no longer coming back: either it closed
+    /// normally (`CloseReason::Normal`, in which case `read_nonblock` treats
+    /// it as a clean EOF rather than an error), or it closed abnormally and
+    /// [`reconnect`] gave up after [`RECONNECT_MAX_ATTEMPTS`] (in which case
+    /// `write`/`read_nonblock` surface `bidirectional_proxy::Error::IoError`
+    /// describing the [`CloseReason`]).
+    closed: Mutex<Option<CloseReason>>,
+    /// Approximate number of bytes the browser is currently holding in its
+    /// outbound send queue for this socket (`WebSocket.bufferedAmount`),
+    /// refreshed by the worker's send loop after each send; see
+    /// [`WebSocketConfig::max_send_queue`].
+    buffered_amount: Arc<AtomicU32>,
+    /// Ceiling on `buffered_amount` above which [`WriteAdapter::flush`]
+    /// returns a `WouldBlock`-style error instead of enqueueing more data,
+    /// applying real backpressure to the caller; `None` disables the check.
+    max_send_queue: Option<usize>,
+}
+
 /// Global channel for sending messages to the WebSocket worker thread.
 ///
 /// This static ensures that only one worker thread is created per process,
@@ -192,13 +323,18 @@ static SEND_WORKER_MESSAGE: OnceNonLock<continue_stream::Sender<WorkerMessage>>
 
 /// Message requesting a WebSocket reconnection.
 ///
-/// Contains a channel to send back the result of the connection attempt.
+/// Contains the configuration to connect with and a channel to send back
+/// the result of the connection attempt.
 struct ReconnectMessage {
+    cfg: WebSocketConfig,
     func_sender: r#continue::Sender<Result<(WriteAdapter, ReadApapter), Error>>,
 }
 
 /// Message indicating that a WebSocket has been closed.
-struct SocketClosedMessage;
+struct SocketClosedMessage {
+    /// Why it closed; see [`CloseReason::from_event`].
+    reason: CloseReason,
+}
 
 /// Messages that can be sent to the WebSocket worker thread.
 enum WorkerMessage {
@@ -208,11 +344,120 @@ enum WorkerMessage {
     SocketClosed(SocketClosedMessage),
 }
 
+/// The delay before the first automatic reconnect attempt after an
+/// unexpected socket closure; see [`ReconnectSchedule`].
+const RECONNECT_INITIAL_BACKOFF: std::time::Duration = std::time::Duration::from_millis(250);
+/// The backoff delay never grows past this, no matter how many consecutive
+/// reconnect attempts have failed.
+const RECONNECT_MAX_BACKOFF: std::time::Duration = std::time::Duration::from_secs(30);
+/// After this many consecutive failed reconnect attempts, [`reconnect`]
+/// gives up rather than retrying forever against a server that isn't coming
+/// back; see [`Channels::closed`].
+const RECONNECT_MAX_ATTEMPTS: u32 = 10;
+
+/// Paces automatic reconnection attempts made by [`reconnect`].
+///
+/// Plays the same role as `InternalProxy`'s internal `ReconnectBackoff`
+/// (capped exponential backoff, doubling each failure), but is driven
+/// actively in a retry loop rather than opportunistically on the next
+/// outgoing message, so it also tracks a hard attempt ceiling
+/// ([`RECONNECT_MAX_ATTEMPTS`]) and adds jitter to avoid many adapters
+/// retrying a shared outage in lockstep.
+struct ReconnectSchedule {
+    attempt: u32,
+}
+
+impl ReconnectSchedule {
+    fn new() -> Self {
+        ReconnectSchedule { attempt: 0 }
+    }
+
+    /// Returns the delay before the next attempt and advances the attempt
+    /// counter, or `None` once [`RECONNECT_MAX_ATTEMPTS`] has been reached.
+    fn next_delay(&mut self) -> Option<std::time::Duration> {
+        if self.attempt >= RECONNECT_MAX_ATTEMPTS {
+            return None;
+        }
+        let delay = RECONNECT_INITIAL_BACKOFF
+            .saturating_mul(1u32 << self.attempt.min(16))
+            .min(RECONNECT_MAX_BACKOFF);
+        self.attempt += 1;
+        // +/- 20% jitter.
+        let jitter = rand::rng().random_range(0.8..1.2);
+        Some(delay.mul_f64(jitter))
+    }
+}
+
+/// An established (or previously-established) connection's configuration
+/// and shared channel endpoints, kept by [`worker_thread`] so a
+/// [`WorkerMessage::SocketClosed`] notification knows what to reconnect to.
+struct LiveConnection {
+    cfg: WebSocketConfig,
+    channels: Arc<Channels>,
+}
+
+/// Repeatedly attempts to reconnect `conn`'s WebSocket with capped
+/// exponential backoff and jitter (see [`ReconnectSchedule`]).
+///
+/// On success, the new `Sender`/`Receiver` are swapped into `conn.channels`
+/// in place, so the `WriteAdapter`/`ReadApapter` the caller already has keep
+/// working without it re-fetching a new pair. Each attempt is logged via
+/// [`log`]. After [`RECONNECT_MAX_ATTEMPTS`] consecutive failures, gives up
+/// and records `last_reason` -- the [`CloseReason`] that started this
+/// reconnect attempt -- on `conn.channels`, so `write`/`read_nonblock`
+/// surface it as an IO error instead of silently going nowhere.
+async fn reconnect(conn: &LiveConnection, last_reason: CloseReason) -> Option<web_sys::WebSocket> {
+    let mut schedule = ReconnectSchedule::new();
+    loop {
+        let Some(delay) = schedule.next_delay() else {
+            log(&format!(
+                "WebSocketAdapter: giving up reconnecting to {} after {} attempts",
+                conn.cfg.url, RECONNECT_MAX_ATTEMPTS
+            ));
+            *conn.channels.closed.lock().unwrap() = Some(last_reason);
+            return None;
+        };
+        log(&format!(
+            "WebSocketAdapter: reconnecting to {} in {:?} (attempt {})",
+            conn.cfg.url, delay, schedule.attempt
+        ));
+        crate::sys::thread::sleep(delay);
+
+        let (write_send, write_recv) = continue_stream::continuation::<Vec<u8>>();
+        let (read_send, read_recv) = std::sync::mpsc::channel::<Vec<u8>>();
+        match create_web_socket(
+            conn.cfg.clone(),
+            read_send,
+            write_recv,
+            conn.channels.buffered_amount.clone(),
+        )
+        .await
+        {
+            Ok(ws) => {
+                log(&format!(
+                    "WebSocketAdapter: reconnected to {} on attempt {}",
+                    conn.cfg.url, schedule.attempt
+                ));
+                *conn.channels.write_send.lock().unwrap() = write_send;
+                *conn.channels.read_recv.lock().unwrap() = read_recv;
+                return Some(ws);
+            }
+            Err(e) => {
+                log(&format!(
+                    "WebSocketAdapter: reconnect attempt {} to {} failed: {:?}",
+                    schedule.attempt, conn.cfg.url, e
+                ));
+            }
+        }
+    }
+}
+
 /// Main worker thread function that manages WebSocket connections.
 ///
 /// This function runs in a dedicated thread and:
 /// - Handles connection requests
-/// - Manages the WebSocket lifecycle
+/// - Manages the WebSocket lifecycle, including automatic reconnection
+///   with backoff after an unexpected closure (see [`reconnect`])
 /// - Routes messages between the WebSocket and the proxy system
 ///
 /// # Arguments
@@ -222,26 +467,45 @@ async fn worker_thread(receiver: continue_stream::Receiver<WorkerMessage>) {
     log("thread started");
 
     let mut socket = None;
+    let mut live: Option<LiveConnection> = None;
 
     loop {
         let r = receiver.receive().await;
         match r {
-            Some(WorkerMessage::Reconnect(reconnect)) => {
+            Some(WorkerMessage::Reconnect(reconnect_msg)) => {
                 match &socket {
                     None => {
                         log("WebSocketAdapter: received reconnect message");
                         let (write_send, write_recv) = continue_stream::continuation::<Vec<u8>>();
                         let (read_send, read_recv) = std::sync::mpsc::channel::<Vec<u8>>();
+                        let buffered_amount = Arc::new(AtomicU32::new(0));
 
-                        let s = create_web_socket(read_send, write_recv).await;
+                        let s = create_web_socket(
+                            reconnect_msg.cfg.clone(),
+                            read_send,
+                            write_recv,
+                            buffered_amount.clone(),
+                        )
+                        .await;
                         match s {
-                            Ok(_) => {
+                            Ok(ws) => {
                                 log("WebSocketAdapter: WebSocket created successfully");
-                                socket = Some(s);
-                                reconnect.func_sender.send(Ok((
-                                    WriteAdapter { send: write_send },
+                                let channels = Arc::new(Channels {
+                                    write_send: Mutex::new(write_send),
+                                    read_recv: Mutex::new(read_recv),
+                                    closed: Mutex::new(None),
+                                    buffered_amount,
+                                    max_send_queue: reconnect_msg.cfg.max_send_queue,
+                                });
+                                socket = Some(ws);
+                                live = Some(LiveConnection {
+                                    cfg: reconnect_msg.cfg,
+                                    channels: channels.clone(),
+                                });
+                                reconnect_msg.func_sender.send(Ok((
+                                    WriteAdapter { channels: channels.clone(), buf: Vec::new() },
                                     ReadApapter {
-                                        recv: read_recv,
+                                        channels,
                                         buf: Vec::new(),
                                     },
                                 )));
@@ -251,7 +515,7 @@ async fn worker_thread(receiver: continue_stream::Receiver<WorkerMessage>) {
                                     "WebSocketAdapter: Failed to create WebSocket: {:?}",
                                     e
                                 ));
-                                reconnect.func_sender.send(Err(e));
+                                reconnect_msg.func_sender.send(Err(e));
                                 // Optionally, you could send an error back to the main thread here
                             }
                         }
@@ -261,10 +525,20 @@ async fn worker_thread(receiver: continue_stream::Receiver<WorkerMessage>) {
                     }
                 }
             }
-            Some(WorkerMessage::SocketClosed(SocketClosedMessage)) => {
-                log("WebSocketAdapter: received socket closed message");
-                // Handle socket closed message if needed
+            Some(WorkerMessage::SocketClosed(SocketClosedMessage { reason })) => {
+                log(&format!("WebSocketAdapter: {}", reason));
                 socket = None; // Reset the socket
+                if let Some(conn) = live.take() {
+                    if reason.is_normal() {
+                        log("WebSocketAdapter: closed normally, not reconnecting");
+                        *conn.channels.closed.lock().unwrap() = Some(reason);
+                    } else {
+                        socket = reconnect(&conn, reason).await;
+                        if socket.is_some() {
+                            live = Some(conn);
+                        }
+                    }
+                }
             }
             None => {
                 log("WebSocketAdapter: receiver closed, exiting thread");
@@ -274,6 +548,38 @@ async fn worker_thread(receiver: continue_stream::Receiver<WorkerMessage>) {
     }
 }
 
+/// Enforces `max_message_size` and forwards `bytes` to `read_send`.
+///
+/// If `bytes` exceeds `max_message_size`, the message is dropped and `ws` is
+/// closed (notifying the worker thread via `SocketClosedMessage`) instead of
+/// being buffered, so a misbehaving or malicious peer can't grow
+/// `ReadApapter::buf` without limit.
+fn deliver_message(
+    bytes: Vec<u8>,
+    max_message_size: Option<usize>,
+    read_send: &std::sync::mpsc::Sender<Vec<u8>>,
+    ws: &web_sys::WebSocket,
+) {
+    if let Some(max) = max_message_size {
+        if bytes.len() > max {
+            web_sys::console::error_1(&format!(
+                "WebSocketAdapter: inbound message of {} bytes exceeds max_message_size {}; closing socket",
+                bytes.len(), max
+            ).into());
+            let _ = ws.close();
+            SEND_WORKER_MESSAGE.get().as_ref().map(|sender| {
+                sender.send(WorkerMessage::SocketClosed(SocketClosedMessage {
+                    reason: CloseReason::Protocol {
+                        reason: format!("inbound message of {} bytes exceeds max_message_size {}", bytes.len(), max),
+                    },
+                }));
+            });
+            return;
+        }
+    }
+    read_send.send(bytes).unwrap();
+}
+
 /// Creates and configures a WebSocket connection.
 ///
 /// This function:
@@ -284,18 +590,25 @@ async fn worker_thread(receiver: continue_stream::Receiver<WorkerMessage>) {
 ///
 /// # Arguments
 ///
+/// * `cfg` - The URL to connect to and resource limits to enforce
 /// * `read_send` - Channel for sending received data to the read adapter
 /// * `write_recv` - Channel for receiving data to send from the write adapter
+/// * `buffered_amount` - Updated with `WebSocket.bufferedAmount` after each
+///   send, so [`WriteAdapter::flush`] can apply backpressure against
+///   `cfg.max_send_queue`
 ///
 /// # Returns
 ///
 /// * `Ok(WebSocket)` - If the connection was successfully established
 /// * `Err(Error)` - If the connection failed
 async fn create_web_socket(
+    cfg: WebSocketConfig,
     read_send: std::sync::mpsc::Sender<Vec<u8>>,
     write_recv: continue_stream::Receiver<Vec<u8>>,
+    buffered_amount: Arc<AtomicU32>,
 ) -> Result<web_sys::WebSocket, Error> {
-    let ws = web_sys::WebSocket::new(ADDR);
+    let max_message_size = cfg.max_message_size;
+    let ws = web_sys::WebSocket::new(&cfg.url);
     log("WebSocket created");
     let (func_sender, func_fut) = r#continue::continuation::<Result<(), Error>>();
     let func_sender = OneShot::new(func_sender);
@@ -310,43 +623,76 @@ async fn create_web_socket(
             ws.set_onopen(Some(onopen_callback.as_ref().unchecked_ref()));
             onopen_callback.forget(); //leak the closure
 
+            // Shared with `onclose_callback` below so a `CloseEvent` with an
+            // empty `reason` can still report what went wrong -- per the
+            // WebSocket spec an `ErrorEvent` always fires immediately before
+            // the `close` that follows it.
+            let last_error: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
             let move_func_sender = func_sender.clone();
+            let move_last_error = last_error.clone();
             let onerror_callback = Closure::wrap(Box::new(move |event: web_sys::ErrorEvent| {
                 // .message seems problematic in some cases?
                 let error_description = event.type_();
                 let error_msg = format!("Websocket error: {}", error_description);
                 web_sys::console::log_1(&error_msg.into());
+                *move_last_error.lock().unwrap() = Some(error_description.clone());
                 move_func_sender.send_if_needed(Err(Error::CantConnect(error_description)));
             }) as Box<dyn FnMut(_)>);
             ws.set_onerror(Some(onerror_callback.as_ref().unchecked_ref()));
             onerror_callback.forget(); //leak the closure
 
-            let onclose_callback = Closure::wrap(Box::new(move |_event: web_sys::CloseEvent| {
-                web_sys::console::log_1(&"WebSocket closed!".into());
+            let onclose_callback = Closure::wrap(Box::new(move |event: web_sys::CloseEvent| {
+                let reason = CloseReason::from_event(&event, last_error.lock().unwrap().take());
+                web_sys::console::log_1(&format!("WebSocket closed: {}", reason).into());
                 SEND_WORKER_MESSAGE.get().as_ref().map(|sender| {
-                    sender.send(WorkerMessage::SocketClosed(SocketClosedMessage));
+                    sender.send(WorkerMessage::SocketClosed(SocketClosedMessage { reason }));
                 });
             }) as Box<dyn FnMut(_)>);
             ws.set_onclose(Some(onclose_callback.as_ref().unchecked_ref()));
             onclose_callback.forget(); //leak the closure
+            let onmessage_ws = ws.clone();
             let onmessage_callback = Closure::wrap(Box::new(move |event: web_sys::MessageEvent| {
-                if let Ok(abuf) = event.data().dyn_into::<web_sys::js_sys::ArrayBuffer>() {
+                if let Some(text) = event.data().as_string() {
+                    // A text frame, or `binaryType` wasn't honored by the peer.
+                    deliver_message(text.into_bytes(), max_message_size, &read_send, &onmessage_ws);
+                } else if let Ok(abuf) = event.data().dyn_into::<web_sys::js_sys::ArrayBuffer>() {
                     let u8_array = web_sys::js_sys::Uint8Array::new(&abuf);
                     let mut vec = vec![0; u8_array.length() as usize];
                     u8_array.copy_to(&mut vec[..]);
-                    read_send.send(vec).unwrap();
+                    deliver_message(vec, max_message_size, &read_send, &onmessage_ws);
+                } else if let Ok(blob) = event.data().dyn_into::<web_sys::Blob>() {
+                    // `binaryType` wasn't honored and the browser delivered a `Blob`
+                    // instead of an `ArrayBuffer`; pull the bytes out asynchronously.
+                    let reader = web_sys::FileReader::new().expect("failed to create FileReader");
+                    let reader_result = reader.clone();
+                    let read_send = read_send.clone();
+                    let onmessage_ws = onmessage_ws.clone();
+                    let onloadend_callback = Closure::wrap(Box::new(move |_event: web_sys::ProgressEvent| {
+                        if let Ok(abuf) = reader_result
+                            .result()
+                            .and_then(|r| r.dyn_into::<web_sys::js_sys::ArrayBuffer>())
+                        {
+                            let u8_array = web_sys::js_sys::Uint8Array::new(&abuf);
+                            let mut vec = vec![0; u8_array.length() as usize];
+                            u8_array.copy_to(&mut vec[..]);
+                            deliver_message(vec, max_message_size, &read_send, &onmessage_ws);
+                        }
+                    }) as Box<dyn FnMut(_)>);
+                    reader.set_onloadend(Some(onloadend_callback.as_ref().unchecked_ref()));
+                    onloadend_callback.forget(); //leak the closure
+                    let _ = reader.read_as_array_buffer(&blob);
                 } else {
-                    let str = format!("Received non-binary message: {:?}", event.data());
+                    let str = format!("Received unsupported message type: {:?}", event.data());
                     web_sys::console::log_1(&str.into());
-                    unimplemented!("This is not currently supported");
                 }
-                return;
             }) as Box<dyn FnMut(_)>);
             ws.set_onmessage(Some(onmessage_callback.as_ref().unchecked_ref()));
             onmessage_callback.forget(); //leak the closure
 
             //set up an async task to read from the stream / send to the websocket
             let move_socket = ws.clone();
+            let move_buffered_amount = buffered_amount;
             patch_close();
             wasm_bindgen_futures::spawn_local(async move {
                 loop {
@@ -361,6 +707,7 @@ async fn create_web_socket(
                     let msg = web_sys::js_sys::Uint8Array::from(msg.as_slice());
                     let msg = msg.buffer();
                     let op = move_socket.send_with_array_buffer(&msg);
+                    move_buffered_amount.store(move_socket.buffered_amount(), Ordering::Relaxed);
                     match op {
                         Ok(_) => {
                             // web_sys::console::log_1(&format!("WebSocketAdapter: sent {} bytes", len).into());
@@ -392,6 +739,10 @@ async fn create_web_socket(
 /// 3. Waits for the connection to be established
 /// 4. Returns a pair of adapters for reading and writing
 ///
+/// # Arguments
+///
+/// * `cfg` - The URL to connect to and resource limits to enforce; see [`WebSocketConfig`]
+///
 /// # Returns
 ///
 /// * `Ok((WriteAdapter, ReadApapter))` - A pair of adapters for bidirectional communication
@@ -428,7 +779,7 @@ async fn create_web_socket(
 /// # Ok(())
 /// # }
 /// ```
-pub async fn adapter() -> Result<(WriteAdapter, ReadApapter), Error> {
+pub async fn adapter_with_config(cfg: WebSocketConfig) -> Result<(WriteAdapter, ReadApapter), Error> {
     //put ws communication on its own thread
     //one thread only per process!
     SEND_WORKER_MESSAGE.try_get_or_init(move || {
@@ -448,6 +799,7 @@ pub async fn adapter() -> Result<(WriteAdapter, ReadApapter), Error> {
                 r#continue::continuation::<Result<(WriteAdapter, ReadApapter), Error>>();
             //send a reconnect message to the worker thread
             sender.send(WorkerMessage::Reconnect(ReconnectMessage {
+                cfg,
                 func_sender: func_send,
             }));
             func_recv.await
@@ -461,6 +813,15 @@ pub async fn adapter() -> Result<(WriteAdapter, ReadApapter), Error> {
     }
 }
 
+/// Creates a WebSocket adapter pair using [`WebSocketConfig::default`].
+///
+/// Kept as a zero-argument convenience wrapper around
+/// [`adapter_with_config`] for existing callers that don't need to override
+/// the endpoint or resource limits.
+pub async fn adapter() -> Result<(WriteAdapter, ReadApapter), Error> {
+    adapter_with_config(WebSocketConfig::default()).await
+}
+
 /// Patches the global `close` function to prevent thread termination.
 ///
 /// On WebAssembly platforms, calling `close()` would terminate the worker thread.
@@ -507,29 +868,77 @@ pub fn patch_close() {
 }
 
 impl WriteTransport for WriteAdapter {
-    /// Writes data to the WebSocket connection.
+    /// Appends data to the internal write buffer.
     ///
-    /// The data is sent asynchronously through a channel to the worker thread,
-    /// which handles the actual WebSocket transmission.
+    /// The data isn't sent until [`Self::flush`] is called, so a burst of
+    /// small writes is coalesced into a single WebSocket frame rather than
+    /// each paying its own frame of overhead.
     ///
     /// # Arguments
     ///
-    /// * `data` - The bytes to send through the WebSocket
+    /// * `data` - The bytes to append to the write buffer
     ///
     /// # Returns
     ///
-    /// Always returns `Ok(())` as sending to the channel is non-blocking.
-    fn write(&mut self, data: &[u8]) -> Result<(), crate::bidirectional_proxy::Error> {
-        // web_sys::console::log_1(&format!("WebsocketAdapter::write_block: sending {} bytes", data.len()).into());
-        self.send.send(data.to_vec());
-        Ok(())
+    /// `Ok(data.len())` once the data is buffered, or `Err(Error::IoError)` if the
+    /// connection has closed (whether gracefully or, after [`reconnect`]
+    /// exhausted [`RECONNECT_MAX_ATTEMPTS`], abnormally) and is not coming
+    /// back.
+    fn write(&mut self, data: &[u8]) -> Result<usize, crate::bidirectional_proxy::Error> {
+        use crate::bidirectional_proxy::IoContextExt;
+        if let Some(reason) = self.channels.closed.lock().unwrap().clone() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                format!("WebSocketAdapter: {}", reason),
+            ))
+            .io_context("writing to the WebSocket connection");
+        }
+        self.buf.extend_from_slice(data);
+        Ok(data.len())
     }
 
-    /// Flushes any buffered data.
+    /// Ships the accumulated write buffer as a single message through the
+    /// channel to the worker thread, which sends it as one WebSocket frame.
+    ///
+    /// A no-op if nothing has been written since the last flush.
     ///
-    /// For WebSocket connections, this is a no-op as data is sent immediately.
+    /// # Returns
+    ///
+    /// `Ok(())` once the buffer is handed to the channel, or
+    /// `Err(Error::IoError { error, .. })` with `error.kind() == ErrorKind::WouldBlock` if the browser's
+    /// outbound send queue (`WebSocket.bufferedAmount`) already exceeds
+    /// [`WebSocketConfig::max_send_queue`] -- the caller should back off
+    /// and retry rather than risk unbounded memory growth.
     fn flush(&mut self) -> Result<(), crate::bidirectional_proxy::Error> {
-        //nothing to do!
+        use crate::bidirectional_proxy::IoContextExt;
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        if let Some(reason) = self.channels.closed.lock().unwrap().clone() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::NotConnected,
+                format!("WebSocketAdapter: {}", reason),
+            ))
+            .io_context("flushing the WebSocket connection");
+        }
+        if let Some(max) = self.channels.max_send_queue {
+            let buffered = self.channels.buffered_amount.load(Ordering::Relaxed) as usize;
+            if buffered > max {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::WouldBlock,
+                    format!(
+                        "WebSocketAdapter: send queue full ({} bytes buffered, max {})",
+                        buffered, max
+                    ),
+                ))
+                .io_context("flushing the WebSocket connection");
+            }
+        }
+        self.channels
+            .write_send
+            .lock()
+            .unwrap()
+            .send(std::mem::take(&mut self.buf));
         Ok(())
     }
 }
@@ -548,20 +957,27 @@ impl ReadTransport for ReadApapter {
     ///
     /// # Returns
     ///
-    /// * `Ok(n)` - The number of bytes read (0 if no data available)
-    /// * `Err(_)` - If an error occurred (currently never returns errors)
-    fn read_nonblock(
+    /// * `Ok(Some(n))` - The number of bytes read
+    /// * `Ok(Some(0))` - A clean EOF, returned forever, once the connection
+    ///   has closed normally (`CloseReason::Normal`)
+    /// * `Ok(None)` - No data is available right now and the connection is
+    ///   still open; try again later
+    /// * `Err(Error::IoError)` - If the connection closed abnormally, or
+    ///   automatic reconnection (see [`reconnect`]) exhausted
+    ///   [`RECONNECT_MAX_ATTEMPTS`] and gave up
+    fn try_read(
         &mut self,
         buf: &mut [u8],
-    ) -> Result<usize, crate::bidirectional_proxy::Error> {
+    ) -> Result<Option<usize>, crate::bidirectional_proxy::Error> {
+        use crate::bidirectional_proxy::IoContextExt;
         //copy from self.buf first
         if !self.buf.is_empty() {
             let copy_bytes = std::cmp::min(self.buf.len(), buf.len());
             buf[..copy_bytes].copy_from_slice(&self.buf[..copy_bytes]);
             self.buf.drain(..copy_bytes);
-            return Ok(copy_bytes);
+            return Ok(Some(copy_bytes));
         }
-        match self.recv.try_recv() {
+        match self.channels.read_recv.lock().unwrap().try_recv() {
             Ok(data) => {
                 //copy the first part into buf
                 let copy_bytes = std::cmp::min(data.len(), buf.len());
@@ -570,9 +986,19 @@ impl ReadTransport for ReadApapter {
                 if data.len() > copy_bytes {
                     self.buf.extend_from_slice(&data[copy_bytes..]);
                 }
-                Ok(copy_bytes)
+                Ok(Some(copy_bytes))
             }
-            Err(_) => Ok(0),
+            Err(_) => match self.channels.closed.lock().unwrap().clone() {
+                // A clean close is EOF, not an error: no more data is
+                // coming, but nothing went wrong.
+                Some(reason) if reason.is_normal() => Ok(Some(0)),
+                Some(reason) => Err(std::io::Error::new(
+                    std::io::ErrorKind::NotConnected,
+                    format!("WebSocketAdapter: {}", reason),
+                ))
+                .io_context("reading from the WebSocket connection"),
+                None => Ok(None),
+            },
         }
     }
 }
@@ -277,7 +277,6 @@ impl Error {
     ///
     /// This error should be returned when the JSON sent is not a valid Request object.
     ///
-    #[cfg(feature = "transit")]
     pub fn invalid_request() -> Self {
         Self {
             code: -32600,
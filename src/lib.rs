@@ -209,18 +209,21 @@ with threads. Threads for everyone.
 
 - `transit` - Enables the transit proxy system for remote debugging (not available on wasm32)
 - `logwise` - Enables integration with the logwise logging framework for log capture
+- `tracing` - Enables integration with the `tracing` ecosystem for log capture
 
 # Module Organization
 
 - [`mcp`] - Model Context Protocol core implementation
+- [`jrpc`] - The underlying JSON-RPC 2.0 request/response/notification types
 - [`messages`] - Inter-component message types
 - `transit` - Transit proxy system (requires `transit` feature, not available on wasm32)
 - `logwise` - Logwise logging integration (requires `logwise` feature)
+- `tracing_bridge` - `tracing` ecosystem integration (requires `tracing` feature)
 
 */
 mod bidirectional_proxy;
 mod internal_proxy;
-mod jrpc;
+pub mod jrpc;
 mod logging;
 #[cfg(feature = "logwise")]
 pub mod logwise;
@@ -229,4 +232,6 @@ mod once_nonlock;
 mod sys;
 #[cfg(feature = "transit")]
 pub mod transit;
+#[cfg(feature = "tracing")]
+pub mod tracing_bridge;
 
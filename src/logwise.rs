@@ -103,7 +103,7 @@ impl Logger for ForwardingLogger {
         //presumably logwise can handle the print for us
         // crate::logging::log(&format!("Logwise: {}", record));
         let n = Notification::new("exfiltrate/logwise/record".to_string(), Some(record.into()));
-        InternalProxy::current().buffer_notification(n);
+        let _ = InternalProxy::current().buffer_notification(n);
     }
 
     /// Processes a completed log record asynchronously.
@@ -136,7 +136,7 @@ impl ForwardingLogger {
     /// the exfiltrate system.
     fn install() {
         let n = Notification::new("exfiltrate/logwise/new".to_string(),None);
-        InternalProxy::current().buffer_notification(n);
+        let _ = InternalProxy::current().buffer_notification(n);
         let f = ForwardingLogger{};
         logwise::add_global_logger(Arc::new(f));
     }
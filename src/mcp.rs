@@ -74,7 +74,13 @@
 //! ```
 
 use crate::jrpc::{Request, Response};
+use std::sync::{LazyLock, RwLock};
+pub mod interceptors;
 pub(crate) mod latest_tools;
+pub mod middleware;
+pub mod progress;
+pub mod providers;
+pub mod subscriptions;
 pub mod tools;
 
 /// Dispatches incoming JSON-RPC requests to appropriate handlers in the target application.
@@ -86,6 +92,8 @@ pub mod tools;
 ///
 /// - `tools/list`: Returns a list of all available tools with their schemas
 /// - `tools/call`: Invokes a specific tool with provided parameters
+/// - `subscribe`: Registers for server-initiated notifications on a channel
+/// - `unsubscribe`: Tears down a subscription created by `subscribe`
 ///
 /// # Arguments
 ///
@@ -100,7 +108,152 @@ pub(crate) fn dispatch_in_target(request: Request) -> Response<serde_json::Value
         tools::list_process(request).erase()
     } else if request.method == "tools/call" {
         tools::call(request).erase()
+    } else if request.method == "subscribe" {
+        subscriptions::subscribe_process(request)
+    } else if request.method == "unsubscribe" {
+        subscriptions::unsubscribe_process(request)
     } else {
         Response::err(super::jrpc::Error::method_not_found(), request.id)
     }
 }
+
+/// Dispatches a raw incoming JSON-RPC payload, which per the JSON-RPC 2.0
+/// spec may be either a single request object, a single notification, or a
+/// batch (a JSON array of either).
+///
+/// # Error Handling
+///
+/// - Bytes that aren't valid JSON at all get a `-32700` Parse error
+///   response, since there's no envelope to recover an `id` from.
+/// - Bytes that parse as JSON but aren't a request-shaped object (or a
+///   batch of them) get a `-32600` Invalid Request error, preserving `id`
+///   when the shape is close enough to recover one.
+///
+/// # Batch Handling
+///
+/// - Each array element is dispatched independently: a member that doesn't
+///   deserialize into a [`Request`] gets its own `-32600` Invalid Request
+///   error response instead of failing the whole batch.
+/// - An empty array is itself invalid per the spec, and yields a single
+///   `-32600` error (not wrapped in an array).
+/// - A member with no `id` field is a notification. Notifications get no
+///   response, so a batch made up entirely of them yields no response body
+///   at all.
+///
+/// # Notifications
+///
+/// A lone (non-batch) payload with no `id` field is likewise a
+/// notification: it gets no response at all, even if it doesn't dispatch
+/// to anything recognized, instead of being answered with an error.
+///
+/// # Returns
+///
+/// * `Some(bytes)` - the serialized response to send back (a single object
+///   for a single request, an array for a batch with at least one member
+///   needing a response)
+/// * `None` - there is nothing to send back, because the payload was a
+///   notification (or a batch made up entirely of them)
+pub(crate) fn dispatch_payload(payload: &[u8]) -> Option<Vec<u8>> {
+    let value: serde_json::Value = match serde_json::from_slice(payload) {
+        Ok(value) => value,
+        Err(_) => {
+            return Some(serde_json::to_vec(&parse_error(serde_json::Value::Null)).unwrap());
+        }
+    };
+    match value {
+        serde_json::Value::Array(items) => dispatch_batch(items),
+        serde_json::Value::Object(ref map) if !map.contains_key("id") => {
+            //no `id`: a notification, which gets no response even if it
+            //doesn't dispatch to anything
+            eprintln!("mcp: dropping notification-shaped payload with no response");
+            None
+        }
+        single => Some(serde_json::to_vec(&dispatch_value(single)).unwrap()),
+    }
+}
+
+/// Dispatches every element of a JSON-RPC batch; see
+/// [`dispatch_payload`] for the rules this follows.
+fn dispatch_batch(items: Vec<serde_json::Value>) -> Option<Vec<u8>> {
+    if items.is_empty() {
+        return Some(serde_json::to_vec(&invalid_request(serde_json::Value::Null)).unwrap());
+    }
+    let responses: Vec<Response<serde_json::Value>> = items
+        .into_iter()
+        .filter(|item| item.get("id").is_some()) // notifications get no response
+        .map(dispatch_value)
+        .collect();
+    if responses.is_empty() {
+        None
+    } else {
+        Some(serde_json::to_vec(&responses).unwrap())
+    }
+}
+
+/// Deserializes a single batch element (or the whole payload, for a
+/// non-batch request) into a [`Request`] and dispatches it, producing a
+/// `-32600` Invalid Request error if it doesn't parse.
+fn dispatch_value(value: serde_json::Value) -> Response<serde_json::Value> {
+    let id = value.get("id").cloned().unwrap_or(serde_json::Value::Null);
+    match serde_json::from_value::<Request>(value) {
+        Ok(request) if validation_mode() == ValidationMode::Strict && request.jsonrpc != "2.0" => {
+            invalid_request(id)
+        }
+        Ok(request) => interceptors::run_chain(request, dispatch_in_target),
+        Err(_) => invalid_request(id),
+    }
+}
+
+/// Builds a `-32600` Invalid Request error response for `id`.
+fn invalid_request(id: serde_json::Value) -> Response<serde_json::Value> {
+    Response::err(super::jrpc::Error::invalid_request(), id)
+}
+
+/// Builds a `-32700` Parse error response for `id`.
+///
+/// Unlike [`invalid_request`], this doesn't go through
+/// [`crate::jrpc::Error::parse_error`] since that constructor is only
+/// available with the `transit` feature enabled, and this module isn't.
+fn parse_error(id: serde_json::Value) -> Response<serde_json::Value> {
+    Response::err(
+        super::jrpc::Error {
+            code: -32700,
+            message: "Parse error".to_string(),
+            data: None,
+        },
+        id,
+    )
+}
+
+/// Controls how strictly [`dispatch_payload`] validates the `jsonrpc`
+/// envelope of incoming requests.
+///
+/// Neither mode currently relaxes the requirement that `method` is present:
+/// a request missing it already fails to deserialize into [`Request`] and
+/// gets the usual `-32600` response regardless of mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// Accept any `jsonrpc` value, matching this crate's historical
+    /// behavior. The default.
+    #[default]
+    Lenient,
+    /// Reject any request whose `jsonrpc` field isn't exactly `"2.0"` with
+    /// a `-32600` Invalid Request error, mirroring `jsonrpc-core`'s
+    /// `Compatibility::V2`.
+    Strict,
+}
+
+/// The process-wide [`ValidationMode`], set with [`set_validation_mode`].
+static VALIDATION_MODE: LazyLock<RwLock<ValidationMode>> =
+    LazyLock::new(|| RwLock::new(ValidationMode::Lenient));
+
+/// Sets how strictly incoming requests' `jsonrpc` field is validated; see
+/// [`ValidationMode`]. Lenient by default.
+pub fn set_validation_mode(mode: ValidationMode) {
+    *VALIDATION_MODE.write().unwrap() = mode;
+}
+
+/// Returns the current [`ValidationMode`]; see [`set_validation_mode`].
+fn validation_mode() -> ValidationMode {
+    *VALIDATION_MODE.read().unwrap()
+}
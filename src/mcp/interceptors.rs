@@ -0,0 +1,97 @@
+//! Request interceptor chain wrapping [`dispatch_in_target`](super::dispatch_in_target).
+//!
+//! The [`mcp::middleware`](crate::mcp::middleware) chain only wraps a
+//! `tools/call` invocation, so there's nowhere to hook a cross-cutting
+//! concern that should see *every* method — `subscribe`, `tools/list`, or a
+//! method nobody recognizes. This module adds a second, outer chain that
+//! wraps all of [`dispatch_in_target`](super::dispatch_in_target): each
+//! registered [`Interceptor`] can inspect a request before it's dispatched
+//! and short-circuit with its own response, then rewrite the response that
+//! comes back. Typical uses are things like logging every method name and
+//! its elapsed time, rejecting specific methods, or normalizing error codes
+//! before they reach the client.
+
+use crate::jrpc::{Request, Response};
+use std::sync::{LazyLock, RwLock};
+
+/// A layer that wraps every request passing through
+/// [`dispatch_in_target`](super::dispatch_in_target).
+///
+/// Implementations can run code before dispatch (and skip it entirely by
+/// returning `Some` from [`Self::on_request`]) and after dispatch (by
+/// rewriting the response in [`Self::on_response`]). Both methods have
+/// no-op defaults so an interceptor only needs to override the half it
+/// cares about.
+pub trait Interceptor: 'static + Send + Sync {
+    /// Inspects `request` before it reaches [`dispatch_in_target`](super::dispatch_in_target).
+    ///
+    /// Returning `Some(response)` short-circuits the chain: neither the
+    /// rest of the interceptors' `on_request` nor dispatch itself runs, and
+    /// that response (still passed through every registered
+    /// [`Self::on_response`], innermost first) is returned in its place.
+    fn on_request(&self, request: &Request) -> Option<Response<serde_json::Value>> {
+        let _ = request;
+        None
+    }
+
+    /// Rewrites `response` after dispatch (or after an earlier interceptor
+    /// short-circuited it).
+    ///
+    /// The default passes `response` through unchanged.
+    fn on_response(
+        &self,
+        request: &Request,
+        response: Response<serde_json::Value>,
+    ) -> Response<serde_json::Value> {
+        let _ = request;
+        response
+    }
+}
+
+/// The global, ordered chain of registered interceptors.
+static INTERCEPTORS: LazyLock<RwLock<Vec<Box<dyn Interceptor>>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Registers an interceptor at the end of the chain.
+///
+/// Interceptors run in registration order, outermost first: the first
+/// registered interceptor is the first to see the request and the last to
+/// see the response.
+///
+/// # Examples
+///
+/// ```
+/// use exfiltrate::mcp::interceptors::{Interceptor, add_interceptor};
+/// use exfiltrate::jrpc::Request;
+///
+/// struct LogMethods;
+/// impl Interceptor for LogMethods {
+///     fn on_request(&self, request: &Request) -> Option<exfiltrate::jrpc::Response<serde_json::Value>> {
+///         eprintln!("dispatching {}", request.method);
+///         None
+///     }
+/// }
+///
+/// add_interceptor(LogMethods);
+/// ```
+pub fn add_interceptor<I: Interceptor>(interceptor: I) {
+    INTERCEPTORS.write().unwrap().push(Box::new(interceptor));
+}
+
+/// Runs `request` through the full registered interceptor chain, finally
+/// calling `terminal` (ordinarily [`dispatch_in_target`](super::dispatch_in_target))
+/// if every interceptor's `on_request` returns `None`.
+pub(crate) fn run_chain(
+    request: Request,
+    terminal: impl Fn(Request) -> Response<serde_json::Value>,
+) -> Response<serde_json::Value> {
+    let chain = INTERCEPTORS.read().unwrap();
+    let response = match chain.iter().find_map(|i| i.on_request(&request)) {
+        Some(response) => response,
+        None => terminal(request.clone()),
+    };
+    chain
+        .iter()
+        .rev()
+        .fold(response, |response, i| i.on_response(&request, response))
+}
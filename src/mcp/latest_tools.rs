@@ -49,7 +49,9 @@
 //! // and invoke it using `run_latest_tool` with name "dynamic_tool"
 //! ```
 
-use crate::mcp::tools::{InputSchema, Tool, ToolCallError, ToolCallParams, ToolCallResponse};
+use crate::mcp::tools::{
+    InputSchema, Tool, ToolCallError, ToolCallParams, ToolCallResponse, ToolContent,
+};
 use serde_json::Value;
 use std::collections::HashMap;
 
@@ -82,20 +84,138 @@ impl Tool for LatestTools {
         are added or removed during a session.  This tool lists the tools that are current available
         at the time of the call, which may be more up-to-date than the cached tools.
 
+        Narrow the list with `name_prefix` and/or `tags`, or pass `tool_choice` to confirm a
+        specific tool exists and get back just its schema: \"auto\" (default) lists everything
+        matching `name_prefix`/`tags`, \"none\" returns an empty list, and either a tool name or
+        `{\"name\": \"...\"}` returns only that tool (erroring if it doesn't exist).
+
+        Every response carries a `revision` counter that bumps whenever a tool is added,
+        removed, or replaced. Pass the last `revision` you saw as `since_revision` to get back
+        only `{revision, added, removed}` -- the names added and removed since then -- instead
+        of the full list, which is cheap even after a long session with many tools.
+
         To run a tool discovered by this tool, use the `run_latest_tool` tool."
     }
 
     fn call(
         &self,
-        _params: std::collections::HashMap<String, serde_json::Value>,
+        params: std::collections::HashMap<String, serde_json::Value>,
     ) -> Result<crate::mcp::tools::ToolCallResponse, crate::mcp::tools::ToolCallError> {
-        let tools = crate::mcp::tools::list_int();
+        if let Some(choice) = params.get("tool_choice") {
+            match explicit_tool_choice(choice) {
+                ToolChoice::Auto => {}
+                ToolChoice::None => {
+                    let text = serde_json::to_string(&crate::mcp::tools::ToolList::empty()).unwrap();
+                    return Ok(ToolCallResponse::new(vec![text.into()]));
+                }
+                ToolChoice::Named(name) => {
+                    return match crate::mcp::tools::tool_info_by_name(&name) {
+                        Some(info) => {
+                            Ok(ToolCallResponse::new(vec![
+                                serde_json::to_string(&info).unwrap().into(),
+                            ]))
+                        }
+                        None => Err(ToolCallError::new(vec![
+                            format!("No tool named '{name}'").into(),
+                        ])),
+                    };
+                }
+            }
+        }
+
+        if let Some(since_revision) = params.get("since_revision").and_then(|v| v.as_u64()) {
+            let delta = crate::mcp::tools::changes_since(since_revision);
+            let body = serde_json::json!({
+                "revision": crate::mcp::tools::current_revision(),
+                "added": delta.added,
+                "removed": delta.removed,
+            });
+            return Ok(ToolCallResponse::new(vec![
+                serde_json::to_string(&body).unwrap().into(),
+            ]));
+        }
+
+        let name_prefix = params.get("name_prefix").and_then(|v| v.as_str());
+        let tags: Vec<String> = params
+            .get("tags")
+            .and_then(|v| v.as_array())
+            .map(|tags| {
+                tags.iter()
+                    .filter_map(|tag| tag.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let filter = crate::mcp::tools::ToolListFilter {
+            name_prefix,
+            tags: &tags,
+        };
+        let tools = crate::mcp::tools::list_int_filtered(&filter);
         let text = serde_json::to_string(&tools).unwrap();
         Ok(crate::mcp::tools::ToolCallResponse::new(vec![text.into()]))
     }
 
     fn input_schema(&self) -> crate::mcp::tools::InputSchema {
-        crate::mcp::tools::InputSchema::new(vec![])
+        crate::mcp::tools::InputSchema::new(vec![
+            crate::mcp::tools::Argument::new(
+                "name_prefix".to_string(),
+                "string".to_string(),
+                "Only list tools whose name starts with this prefix".to_string(),
+                false,
+            ),
+            crate::mcp::tools::Argument::new(
+                "tags".to_string(),
+                "array".to_string(),
+                "Only list tools carrying at least one of these tags".to_string(),
+                false,
+            )
+            .with_items(crate::mcp::tools::Argument::new(
+                "tag".to_string(),
+                "string".to_string(),
+                "A single tag".to_string(),
+                true,
+            )),
+            crate::mcp::tools::Argument::new(
+                "tool_choice".to_string(),
+                "string".to_string(),
+                "\"auto\" (default), \"none\", or a tool name (also accepted as {\"name\": ...}) \
+                 to return only that tool's schema"
+                    .to_string(),
+                false,
+            ),
+            crate::mcp::tools::Argument::new(
+                "since_revision".to_string(),
+                "integer".to_string(),
+                "If set, instead of the full list, return only the tool names added/removed \
+                 since this revision (ignored if tool_choice is \"none\" or names a tool)"
+                    .to_string(),
+                false,
+            ),
+        ])
+    }
+}
+
+/// The parsed form of `latest_tools`'s `tool_choice` parameter.
+enum ToolChoice {
+    /// List tools normally (subject to `name_prefix`/`tags`); the default.
+    Auto,
+    /// Return an empty list.
+    None,
+    /// Return only the named tool's schema.
+    Named(String),
+}
+
+/// Parses a `tool_choice` value: the strings `"auto"`/`"none"`, any other
+/// string as a tool name, or `{"name": "..."}` as the same.
+fn explicit_tool_choice(value: &Value) -> ToolChoice {
+    match value {
+        Value::String(s) if s == "auto" => ToolChoice::Auto,
+        Value::String(s) if s == "none" => ToolChoice::None,
+        Value::String(s) => ToolChoice::Named(s.clone()),
+        Value::Object(obj) => match obj.get("name").and_then(|v| v.as_str()) {
+            Some(name) => ToolChoice::Named(name.to_string()),
+            None => ToolChoice::Auto,
+        },
+        _ => ToolChoice::Auto,
     }
 }
 
@@ -107,18 +227,40 @@ impl Tool for LatestTools {
 ///
 /// # Parameters
 ///
-/// - `tool_name` (required): The name of the tool to execute
-/// - `params` (optional): Parameters to pass to the target tool as a JSON object
+/// - `tool_name` (optional): The name of a single tool to execute
+/// - `params` (optional): Parameters to pass to that tool as a JSON object
+/// - `steps` (optional): A sequence of `{tool_name, params, capture_as?}`
+///   objects to run instead of a single `tool_name`/`params` call -- see
+///   [`Self::call_steps`]
+/// - `parallel` (optional): If `true` and no step declares `capture_as`, runs
+///   every step in `steps` concurrently instead of in order
+///
+/// One of `tool_name` or `steps` must be given; if both are, `steps` runs
+/// and `tool_name`/`params` are ignored.
 ///
 /// # Error Handling
 ///
 /// Returns an error if:
-/// - The `tool_name` parameter is missing
+/// - Neither `tool_name` nor `steps` is given
 /// - The specified tool doesn't exist
-/// - The target tool returns an error
+/// - The target tool (or, for `steps`, any step) returns an error
 ///
 pub struct RunLatestTool;
 
+/// One invocation in a `steps` sequence passed to [`RunLatestTool`].
+#[derive(Debug, serde::Deserialize)]
+struct Step {
+    /// The name of the tool to run for this step.
+    tool_name: String,
+    /// Parameters for this step, with any `{{capture_as}}` tokens from an
+    /// earlier step substituted in first.
+    #[serde(default)]
+    params: serde_json::Map<String, Value>,
+    /// If set, this step's result text is stored under this name so later
+    /// steps can refer to it as `{{capture_as}}`.
+    capture_as: Option<String>,
+}
+
 impl Tool for RunLatestTool {
     fn name(&self) -> &str {
         "run_latest_tool"
@@ -128,6 +270,12 @@ impl Tool for RunLatestTool {
         "Runs a tool discovered by the `latest_tools` tool.
 
         This tool may be able to run tools that were added after the agent started.
+
+        Instead of a single `tool_name`/`params`, pass a `steps` array of
+        `{tool_name, params, capture_as?}` objects to chain several calls in one
+        round trip -- a later step's `params` may reference an earlier step's
+        output as `{{capture_as}}`. Set `parallel: true` to run `steps` that
+        don't use captures concurrently.
         "
     }
 
@@ -137,7 +285,7 @@ impl Tool for RunLatestTool {
                 "tool_name".to_string(),
                 "string".to_string(),
                 "Name of the tool to run".to_string(),
-                true,
+                false,
             ),
             crate::mcp::tools::Argument::new(
                 "params".to_string(),
@@ -145,18 +293,63 @@ impl Tool for RunLatestTool {
                 "Parameters for the tool".to_string(),
                 false,
             ),
+            crate::mcp::tools::Argument::new(
+                "steps".to_string(),
+                "array".to_string(),
+                "A sequence of tool calls to run instead of a single tool_name/params"
+                    .to_string(),
+                false,
+            )
+            .with_items(
+                crate::mcp::tools::Argument::new(
+                    "step".to_string(),
+                    "object".to_string(),
+                    "One step in the sequence".to_string(),
+                    true,
+                )
+                .with_properties(vec![
+                    crate::mcp::tools::Argument::new(
+                        "tool_name".to_string(),
+                        "string".to_string(),
+                        "Name of the tool to run for this step".to_string(),
+                        true,
+                    ),
+                    crate::mcp::tools::Argument::new(
+                        "params".to_string(),
+                        "object".to_string(),
+                        "Parameters for this step".to_string(),
+                        false,
+                    ),
+                    crate::mcp::tools::Argument::new(
+                        "capture_as".to_string(),
+                        "string".to_string(),
+                        "Name to store this step's result text under, for later steps to \
+                         reference as {{capture_as}}"
+                            .to_string(),
+                        false,
+                    ),
+                ]),
+            ),
+            crate::mcp::tools::Argument::new(
+                "parallel".to_string(),
+                "boolean".to_string(),
+                "Run steps concurrently instead of in order; ignored if any step uses capture_as"
+                    .to_string(),
+                false,
+            ),
         ])
     }
 
     fn call(&self, params: HashMap<String, Value>) -> Result<ToolCallResponse, ToolCallError> {
-        let tool_name;
-        if let Some(name) = params.get("tool_name").and_then(|v| v.as_str()) {
-            tool_name = name.to_string();
-        } else {
+        if let Some(steps) = params.get("steps") {
+            let parallel = params.get("parallel").and_then(|v| v.as_bool()).unwrap_or(false);
+            return Self::call_steps(steps, parallel);
+        }
+        let Some(tool_name) = params.get("tool_name").and_then(|v| v.as_str()) else {
             return Err(ToolCallError::new(vec![
-                "Missing required parameter: tool_name".into(),
+                "Missing required parameter: tool_name (or steps)".into(),
             ]));
-        }
+        };
         let tool_arguments = params
             .get("params")
             .and_then(|v| v.as_object())
@@ -166,8 +359,230 @@ impl Tool for RunLatestTool {
         //convert to hashmap
         let tool_arguments: HashMap<String, Value> = tool_arguments.into_iter().collect();
 
-        let tool_params = ToolCallParams::new(tool_name, tool_arguments);
-        let r = crate::mcp::tools::call_imp(tool_params);
+        validate_arguments(tool_name, &tool_arguments)?;
+
+        let tool_params = ToolCallParams::new(tool_name.to_string(), tool_arguments);
+        let reporter = crate::mcp::progress::ProgressReporter::new(None);
+        let r = crate::mcp::tools::call_imp(tool_params, &reporter);
+        r.map_err(|e| ToolCallError::new(vec![format!("Error calling tool: {:?}", e).into()]))
+    }
+
+    fn call_streaming(
+        &self,
+        params: HashMap<String, Value>,
+        reporter: &crate::mcp::progress::ProgressReporter,
+    ) -> Result<ToolCallResponse, ToolCallError> {
+        // A `steps` sequence has no single target tool to stream through, so
+        // only the plain `tool_name`/`params` form forwards `reporter` --
+        // falling back to `call` reports no progress for `steps`, same as
+        // any other tool that hasn't opted into streaming.
+        if params.contains_key("steps") {
+            return self.call(params);
+        }
+        let Some(tool_name) = params.get("tool_name").and_then(|v| v.as_str()) else {
+            return Err(ToolCallError::new(vec![
+                "Missing required parameter: tool_name (or steps)".into(),
+            ]));
+        };
+        let tool_arguments = params
+            .get("params")
+            .and_then(|v| v.as_object())
+            .cloned()
+            .unwrap_or_default();
+        let tool_arguments: HashMap<String, Value> = tool_arguments.into_iter().collect();
+
+        validate_arguments(tool_name, &tool_arguments)?;
+
+        let tool_params = ToolCallParams::new(tool_name.to_string(), tool_arguments);
+        let r = crate::mcp::tools::call_imp(tool_params, reporter);
         r.map_err(|e| ToolCallError::new(vec![format!("Error calling tool: {:?}", e).into()]))
     }
 }
+
+impl RunLatestTool {
+    /// Runs a `steps` array: either [`Self::call_steps_sequential`] (the
+    /// default, and the only option once any step declares `capture_as`) or
+    /// [`Self::call_steps_parallel`] when `parallel` asked for it and no step
+    /// has a capture to depend on.
+    fn call_steps(steps: &Value, parallel: bool) -> Result<ToolCallResponse, ToolCallError> {
+        let steps: Vec<Step> = serde_json::from_value(steps.clone())
+            .map_err(|e| ToolCallError::new(vec![format!("Invalid steps: {e}").into()]))?;
+        if steps.is_empty() {
+            return Err(ToolCallError::new(vec![
+                "steps must contain at least one entry".into(),
+            ]));
+        }
+        let any_captures = steps.iter().any(|step| step.capture_as.is_some());
+        if parallel && !any_captures {
+            Self::call_steps_parallel(steps)
+        } else {
+            Self::call_steps_sequential(steps)
+        }
+    }
+
+    /// Runs `steps` one at a time, substituting `{{capture_as}}` tokens from
+    /// earlier steps into each step's `params` before dispatching it, and
+    /// stopping at the first step that errors.
+    fn call_steps_sequential(steps: Vec<Step>) -> Result<ToolCallResponse, ToolCallError> {
+        let mut captures: HashMap<String, String> = HashMap::new();
+        let mut results = Vec::with_capacity(steps.len());
+        for (index, step) in steps.into_iter().enumerate() {
+            let substituted = substitute_captures(&Value::Object(step.params), &captures);
+            let tool_arguments: HashMap<String, Value> = substituted
+                .as_object()
+                .cloned()
+                .unwrap_or_default()
+                .into_iter()
+                .collect();
+            let result = run_step(index, &step.tool_name, tool_arguments)?;
+            if let Some(name) = step.capture_as {
+                captures.insert(name, result_text(&result));
+            }
+            results.push(step_result_json(index, &step.tool_name, &result));
+        }
+        Ok(ToolCallResponse::new(vec![
+            serde_json::to_string(&results).unwrap().into(),
+        ]))
+    }
+
+    /// Runs every step in `steps` concurrently (no step may use `capture_as`,
+    /// since there would be nothing to substitute it from yet), returning
+    /// results in the original input order and the first error encountered
+    /// scanning that order, regardless of which thread finished first.
+    fn call_steps_parallel(steps: Vec<Step>) -> Result<ToolCallResponse, ToolCallError> {
+        let outcomes: Vec<Result<ToolCallResponse, ToolCallError>> = std::thread::scope(|scope| {
+            let handles: Vec<_> = steps
+                .iter()
+                .enumerate()
+                .map(|(index, step)| {
+                    let tool_arguments: HashMap<String, Value> =
+                        step.params.clone().into_iter().collect();
+                    scope.spawn(move || run_step(index, &step.tool_name, tool_arguments))
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("tool step thread panicked"))
+                .collect()
+        });
+        let mut results = Vec::with_capacity(outcomes.len());
+        for (index, (step, outcome)) in steps.iter().zip(outcomes).enumerate() {
+            let result = outcome?;
+            results.push(step_result_json(index, &step.tool_name, &result));
+        }
+        Ok(ToolCallResponse::new(vec![
+            serde_json::to_string(&results).unwrap().into(),
+        ]))
+    }
+}
+
+/// Validates `arguments` against `tool_name`'s [`crate::mcp::tools::InputSchema`]
+/// before dispatch, so a proxied call that's missing a required argument or
+/// has the wrong type fails immediately with a clear message naming the tool,
+/// rather than waiting on whatever [`crate::mcp::tools::call_imp`] would do
+/// internally (which runs the same [`crate::mcp::tools::InputSchema::validate`]
+/// check, just after already committing to the dispatch).
+fn validate_arguments(
+    tool_name: &str,
+    arguments: &HashMap<String, Value>,
+) -> Result<(), ToolCallError> {
+    let Some(schema) = crate::mcp::tools::schema_by_name(tool_name) else {
+        return Err(ToolCallError::new(vec![
+            format!("No tool named '{tool_name}'").into(),
+        ]));
+    };
+    schema
+        .validate(arguments)
+        .map_err(|errors| ToolCallError::new(vec![errors.join("; ").into()]))
+}
+
+/// Dispatches a single step's tool call through [`crate::mcp::tools::call_imp`],
+/// turning either a dispatch error or a [`ToolCallResponse`] with
+/// [`ToolCallResponse::is_error`] set into a [`ToolCallError`] that names the
+/// failing step.
+fn run_step(
+    index: usize,
+    tool_name: &str,
+    arguments: HashMap<String, Value>,
+) -> Result<ToolCallResponse, ToolCallError> {
+    if let Err(e) = validate_arguments(tool_name, &arguments) {
+        return Err(ToolCallError::new(
+            e.content()
+                .iter()
+                .map(|c| match c {
+                    ToolContent::Text(text) => format!("step {index} ({tool_name}): {text}").into(),
+                    ToolContent::Json(json) => {
+                        format!("step {index} ({tool_name}): {json}").into()
+                    }
+                })
+                .collect(),
+        ));
+    }
+    let tool_params = ToolCallParams::new(tool_name.to_string(), arguments);
+    let reporter = crate::mcp::progress::ProgressReporter::new(None);
+    match crate::mcp::tools::call_imp(tool_params, &reporter) {
+        Ok(response) if response.is_error() => Err(ToolCallError::new(vec![
+            format!(
+                "step {index} ({tool_name}) failed: {}",
+                result_text(&response)
+            )
+            .into(),
+        ])),
+        Ok(response) => Ok(response),
+        Err(e) => Err(ToolCallError::new(vec![
+            format!("step {index} ({tool_name}): {e:?}").into(),
+        ])),
+    }
+}
+
+/// Flattens a [`ToolCallResponse`]'s content into one string, for use both as
+/// a `capture_as` value and in per-step error messages.
+fn result_text(response: &ToolCallResponse) -> String {
+    response
+        .content
+        .iter()
+        .map(|c| match c {
+            ToolContent::Text(text) => text.clone(),
+            ToolContent::Json(json) => json.to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Builds one `steps` result entry for the final JSON array `run_latest_tool`
+/// returns.
+fn step_result_json(index: usize, tool_name: &str, response: &ToolCallResponse) -> serde_json::Value {
+    serde_json::json!({
+        "step": index,
+        "tool_name": tool_name,
+        "result": result_text(response),
+    })
+}
+
+/// Replaces every `{{name}}` token appearing in a string value of `value`
+/// with `captures[name]`, recursing into arrays and objects. Tokens naming a
+/// capture that doesn't exist (e.g. a typo, or a step that hasn't run yet)
+/// are left untouched.
+fn substitute_captures(value: &Value, captures: &HashMap<String, String>) -> Value {
+    match value {
+        Value::String(s) => {
+            let mut result = s.clone();
+            for (name, captured) in captures {
+                result = result.replace(&format!("{{{{{name}}}}}"), captured);
+            }
+            Value::String(result)
+        }
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| substitute_captures(item, captures))
+                .collect(),
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(k, v)| (k.clone(), substitute_captures(v, captures)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
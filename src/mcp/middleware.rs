@@ -0,0 +1,216 @@
+//! Middleware chain wrapping tool invocation.
+//!
+//! Every call dispatched through [`crate::mcp::tools::call_imp`] runs through
+//! the registered middleware chain before reaching the matching
+//! [`Tool::call`](crate::mcp::tools::Tool::call). Middlewares are registered
+//! in order with [`add_middleware`] and wrap the call much like a
+//! `reqwest`-style middleware: each one receives the tool name and
+//! arguments plus a `next` closure representing the rest of the chain, and
+//! decides whether, how many times, and with what side effects to call it.
+
+use crate::mcp::tools::{ToolCallError, ToolCallResponse};
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+/// A layer that wraps tool invocation.
+///
+/// Implementations can run code before and after calling `next`, skip
+/// calling it entirely (e.g. to short-circuit with a cached response), or
+/// call it more than once (e.g. to retry a transient failure).
+pub trait Middleware: 'static + Send + Sync {
+    /// Handles a call to `name` with `arguments`, calling `next` to
+    /// continue the chain.
+    #[allow(clippy::type_complexity)]
+    fn handle(
+        &self,
+        name: &str,
+        arguments: &HashMap<String, serde_json::Value>,
+        next: &dyn Fn(
+            &str,
+            &HashMap<String, serde_json::Value>,
+        ) -> Result<ToolCallResponse, ToolCallError>,
+    ) -> Result<ToolCallResponse, ToolCallError>;
+}
+
+/// The global, ordered chain of registered middlewares.
+///
+/// Initialized with the built-in middlewares (timing, retry, panic guard);
+/// custom middlewares added with [`add_middleware`] run inside them.
+pub(crate) static MIDDLEWARE: LazyLock<RwLock<Vec<Box<dyn Middleware>>>> = LazyLock::new(|| {
+    RwLock::new(vec![
+        Box::new(TimingMiddleware),
+        Box::new(RetryMiddleware::new(3)),
+        Box::new(PanicGuardMiddleware),
+    ])
+});
+
+/// Registers a middleware at the end of the chain.
+///
+/// Middlewares run in registration order, outermost first: the first
+/// registered middleware is the first to see the call and the last to see
+/// the result.
+///
+/// # Examples
+///
+/// ```
+/// use exfiltrate::mcp::middleware::{Middleware, add_middleware};
+/// use exfiltrate::mcp::tools::{ToolCallError, ToolCallResponse};
+/// use std::collections::HashMap;
+///
+/// struct LogNames;
+/// impl Middleware for LogNames {
+///     fn handle(
+///         &self,
+///         name: &str,
+///         arguments: &HashMap<String, serde_json::Value>,
+///         next: &dyn Fn(&str, &HashMap<String, serde_json::Value>) -> Result<ToolCallResponse, ToolCallError>,
+///     ) -> Result<ToolCallResponse, ToolCallError> {
+///         eprintln!("about to call {}", name);
+///         next(name, arguments)
+///     }
+/// }
+///
+/// add_middleware(LogNames);
+/// ```
+pub fn add_middleware<M: Middleware>(middleware: M) {
+    MIDDLEWARE.write().unwrap().push(Box::new(middleware));
+}
+
+/// Runs a call to `name` with `arguments` through the full registered
+/// middleware chain, finally calling `terminal` if every middleware calls
+/// `next`.
+pub(crate) fn run_chain(
+    name: &str,
+    arguments: HashMap<String, serde_json::Value>,
+    terminal: impl Fn(&str, &HashMap<String, serde_json::Value>) -> Result<ToolCallResponse, ToolCallError>,
+) -> Result<ToolCallResponse, ToolCallError> {
+    #[allow(clippy::type_complexity)]
+    fn run(
+        chain: &[Box<dyn Middleware>],
+        name: &str,
+        arguments: &HashMap<String, serde_json::Value>,
+        terminal: &dyn Fn(
+            &str,
+            &HashMap<String, serde_json::Value>,
+        ) -> Result<ToolCallResponse, ToolCallError>,
+    ) -> Result<ToolCallResponse, ToolCallError> {
+        match chain.split_first() {
+            Some((first, rest)) => {
+                let next = move |n: &str, a: &HashMap<String, serde_json::Value>| {
+                    run(rest, n, a, terminal)
+                };
+                first.handle(name, arguments, &next)
+            }
+            None => terminal(name, arguments),
+        }
+    }
+    let chain = MIDDLEWARE.read().unwrap();
+    run(&chain, name, &arguments, &terminal)
+}
+
+/// Logs how long each tool call took to `stderr`.
+///
+/// Registered by default; see [`MIDDLEWARE`].
+struct TimingMiddleware;
+
+impl Middleware for TimingMiddleware {
+    fn handle(
+        &self,
+        name: &str,
+        arguments: &HashMap<String, serde_json::Value>,
+        next: &dyn Fn(
+            &str,
+            &HashMap<String, serde_json::Value>,
+        ) -> Result<ToolCallResponse, ToolCallError>,
+    ) -> Result<ToolCallResponse, ToolCallError> {
+        let start = std::time::Instant::now();
+        let result = next(name, arguments);
+        eprintln!("middleware: tool {} took {:?}", name, start.elapsed());
+        result
+    }
+}
+
+/// The delay before the first retry; each subsequent retry doubles it.
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(10);
+
+/// Retries a failing tool call with exponential backoff, up to a configured
+/// number of retries after the first attempt.
+///
+/// "Transient" is approximated as the call returning `Err`; a tool that
+/// deterministically fails will simply be retried and fail the same way
+/// each time, at the cost of the added latency.
+///
+/// Registered by default with `max_retries = 3`; see [`MIDDLEWARE`].
+struct RetryMiddleware {
+    max_retries: u32,
+}
+
+impl RetryMiddleware {
+    fn new(max_retries: u32) -> Self {
+        RetryMiddleware { max_retries }
+    }
+}
+
+impl Middleware for RetryMiddleware {
+    fn handle(
+        &self,
+        name: &str,
+        arguments: &HashMap<String, serde_json::Value>,
+        next: &dyn Fn(
+            &str,
+            &HashMap<String, serde_json::Value>,
+        ) -> Result<ToolCallResponse, ToolCallError>,
+    ) -> Result<ToolCallResponse, ToolCallError> {
+        let mut attempt = 0;
+        loop {
+            let result = next(name, arguments);
+            if result.is_ok() || attempt >= self.max_retries {
+                return result;
+            }
+            let delay = RETRY_BASE_DELAY * 2u32.pow(attempt);
+            eprintln!(
+                "middleware: tool {} failed (attempt {}), retrying in {:?}",
+                name,
+                attempt + 1,
+                delay
+            );
+            std::thread::sleep(delay);
+            attempt += 1;
+        }
+    }
+}
+
+/// Catches a panic from the rest of the chain and turns it into a
+/// [`ToolCallError`] instead of unwinding into the connection handler
+/// thread (which would otherwise tear it down and silently drop the reply).
+///
+/// Registered by default, innermost in the chain; see [`MIDDLEWARE`].
+struct PanicGuardMiddleware;
+
+impl Middleware for PanicGuardMiddleware {
+    fn handle(
+        &self,
+        name: &str,
+        arguments: &HashMap<String, serde_json::Value>,
+        next: &dyn Fn(
+            &str,
+            &HashMap<String, serde_json::Value>,
+        ) -> Result<ToolCallResponse, ToolCallError>,
+    ) -> Result<ToolCallResponse, ToolCallError> {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| next(name, arguments))) {
+            Ok(result) => result,
+            Err(payload) => {
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "tool panicked".to_string());
+                eprintln!("middleware: tool {} panicked: {}", name, message);
+                Err(ToolCallError::new(vec![
+                    format!("tool {} panicked: {}", name, message).into(),
+                ]))
+            }
+        }
+    }
+}
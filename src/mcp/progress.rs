@@ -0,0 +1,98 @@
+//! Incremental progress notifications for long-running tool calls.
+//!
+//! [`Tool::call`](crate::mcp::tools::Tool::call) is blocking and only ever
+//! produces one [`ToolCallResponse`](crate::mcp::tools::ToolCallResponse),
+//! which is fine for quick tools but leaves a client watching a long build
+//! or crawl with no feedback until it finishes. A tool can instead implement
+//! [`Tool::call_streaming`](crate::mcp::tools::Tool::call_streaming) and use
+//! the [`ProgressReporter`] it's given to send `notifications/progress`
+//! messages as it works, while still returning the full
+//! [`ToolCallResponse`](crate::mcp::tools::ToolCallResponse) at the end for
+//! clients that ignored the notifications.
+//!
+//! A reporter is only useful if the caller asked for progress updates by
+//! including a `progressToken` in the request's `_meta`; see
+//! [`token_from_params`]. A reporter built from no token silently drops
+//! everything sent to it, so [`Tool::call_streaming`] implementations don't
+//! need to special-case clients that didn't opt in.
+
+use crate::internal_proxy::InternalProxy;
+use crate::jrpc::Notification;
+use crate::mcp::tools::ToolContent;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Sends `notifications/progress` messages for a single in-flight tool call.
+///
+/// See the [module documentation](self) for how this fits into streaming
+/// tool calls.
+#[derive(Debug)]
+pub struct ProgressReporter {
+    token: Option<serde_json::Value>,
+    progress: AtomicU64,
+}
+
+impl ProgressReporter {
+    /// Creates a reporter that sends progress notifications carrying
+    /// `token`. If `token` is `None`, the reporter is inert: [`Self::emit`]
+    /// and [`Self::progress`] become no-ops.
+    pub(crate) fn new(token: Option<serde_json::Value>) -> Self {
+        ProgressReporter {
+            token,
+            progress: AtomicU64::new(0),
+        }
+    }
+
+    /// Sends an incremental chunk of tool output.
+    ///
+    /// Each call bumps the reporter's internal progress counter by one and
+    /// reports it alongside `content` (stringified, if it's JSON) as the
+    /// notification's `message` field. Use [`Self::progress`] instead if
+    /// the tool already knows a done/total count.
+    pub fn emit(&self, content: ToolContent) {
+        let message = match content {
+            ToolContent::Text(text) => text,
+            ToolContent::Json(json) => json.to_string(),
+        };
+        let progress = self.progress.fetch_add(1, Ordering::AcqRel) + 1;
+        self.send(progress, None, Some(message));
+    }
+
+    /// Reports `done` out of an optional `total` units of work completed so
+    /// far, with no accompanying message.
+    pub fn progress(&self, done: u64, total: Option<u64>) {
+        self.progress.store(done, Ordering::Release);
+        self.send(done, total, None);
+    }
+
+    fn send(&self, progress: u64, total: Option<u64>, message: Option<String>) {
+        let Some(token) = &self.token else {
+            return;
+        };
+        let mut params = serde_json::json!({
+            "progressToken": token,
+            "progress": progress,
+        });
+        if let Some(total) = total {
+            params["total"] = serde_json::json!(total);
+        }
+        if let Some(message) = message {
+            params["message"] = serde_json::json!(message);
+        }
+        let notification = Notification::new("notifications/progress".to_string(), Some(params));
+        // Benign if there's no connection yet; the client already knows it
+        // won't see progress updates in that case.
+        let _ = InternalProxy::current().send_notification(notification);
+    }
+}
+
+/// Extracts a `progressToken` from a `tools/call` request's raw `params`, if
+/// present, for building a [`ProgressReporter`].
+///
+/// Per the MCP spec, a request opts into progress notifications by setting
+/// `params._meta.progressToken`. [`crate::mcp::tools::ToolCallParams`]
+/// doesn't carry `_meta` (it only cares about `name`/`arguments`), so this
+/// reads directly from the request's `params` value before it's deserialized
+/// into [`crate::mcp::tools::ToolCallParams`].
+pub(crate) fn token_from_params(params: &serde_json::Value) -> Option<serde_json::Value> {
+    params.get("_meta")?.get("progressToken").cloned()
+}
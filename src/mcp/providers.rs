@@ -0,0 +1,277 @@
+//! External tool providers: registering tools backed by executables instead
+//! of compiled-in Rust structs.
+//!
+//! [`add_tool`](crate::mcp::tools::add_tool) requires a `Tool` implementation
+//! compiled into this crate. That's fine for tools written in Rust, but it
+//! shuts out tools a user wants to write in whatever language is convenient
+//! (a shell script, a Python one-liner, an existing binary). The
+//! [`ToolProvider`] trait is the extension point for that: a provider is
+//! asked [`ToolProvider::tool_names`] for what it can produce and
+//! [`ToolProvider::get_tool`] to build one of them on demand.
+//!
+//! [`ProcessToolProvider`] is the concrete provider this module ships: point
+//! it at a manifest directory and it scans for executables with a sidecar
+//! `<name>.json` metadata file describing the tool's name, description, and
+//! [`InputSchema`]. The resulting [`Tool::call`] spawns the executable,
+//! writes `params` as JSON to its stdin, and maps a clean exit's stdout into
+//! a [`ToolCallResponse`] (as [`ToolContent::Json`] if it parses as JSON,
+//! [`ToolContent::Text`] otherwise) or a nonzero exit's stderr into a
+//! [`ToolCallError`].
+//!
+//! Registered providers are merged into [`crate::mcp::tools::list_int`] and
+//! friends alongside in-process tools, so `latest_tools` surfaces them and
+//! `run_latest_tool` can invoke them without the caller needing to know a
+//! tool came from a provider at all.
+
+use crate::mcp::tools::{InputSchema, Tool, ToolCallError, ToolCallResponse, ToolContent};
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{LazyLock, RwLock};
+
+/// A source of tools that aren't compiled into this crate.
+///
+/// See the [module documentation](self) for the motivating use case.
+pub trait ToolProvider: Send + Sync {
+    /// Names of every tool this provider currently knows about.
+    ///
+    /// Called whenever [`crate::mcp::tools::list_int`] (and thus
+    /// `latest_tools`) needs to enumerate available tools, so this should be
+    /// cheap -- ideally just a cached list built when the provider was
+    /// constructed, not something that re-scans external state each call.
+    fn tool_names(&self) -> Vec<String>;
+
+    /// Builds the tool named `name`, if this provider has it.
+    ///
+    /// Returns `None` rather than an error for an unknown name, the same
+    /// convention [`crate::mcp::tools::tool_info_by_name`] uses for
+    /// in-process tools, so a caller checking multiple providers can just
+    /// try each in turn.
+    fn get_tool(&self, name: &str) -> Option<Box<dyn Tool>>;
+}
+
+/// Providers registered with [`add_provider`].
+///
+/// Mirrors [`crate::mcp::tools::TOOLS`]'s `RwLock<Vec<_>>` shape: providers
+/// are expected to be registered once at startup and read often, not mutated
+/// concurrently with lookups.
+static PROVIDERS: LazyLock<RwLock<Vec<Box<dyn ToolProvider>>>> =
+    LazyLock::new(|| RwLock::new(vec![]));
+
+/// Registers `provider` so its tools are merged into
+/// [`crate::mcp::tools::list_int`] and reachable by `run_latest_tool`.
+pub fn add_provider(provider: Box<dyn ToolProvider>) {
+    PROVIDERS.write().unwrap().push(provider);
+}
+
+/// Builds the [`Tool`] named `name` from the first registered provider that
+/// has it, for callers (e.g. [`crate::mcp::tools::call_imp`]) that already
+/// checked [`crate::mcp::tools::TOOLS`] and [`crate::mcp::tools::SHARED_TOOLS`]
+/// and came up empty.
+pub(crate) fn find_tool(name: &str) -> Option<Box<dyn Tool>> {
+    PROVIDERS
+        .read()
+        .unwrap()
+        .iter()
+        .find_map(|provider| provider.get_tool(name))
+}
+
+/// Lists [`crate::mcp::tools::ToolInfo`] for every provider-discovered tool
+/// matching `filter`, for [`crate::mcp::tools::list_int_filtered`] to chain
+/// onto its in-process tools.
+pub(crate) fn list_tool_infos(
+    filter: &crate::mcp::tools::ToolListFilter,
+) -> Vec<crate::mcp::tools::ToolInfo> {
+    PROVIDERS
+        .read()
+        .unwrap()
+        .iter()
+        .flat_map(|provider| {
+            provider
+                .tool_names()
+                .into_iter()
+                .filter_map(|name| provider.get_tool(&name))
+        })
+        .filter(|tool| filter.matches(tool.as_ref()))
+        .map(|tool| crate::mcp::tools::ToolInfo::from_tool(tool.as_ref()))
+        .collect()
+}
+
+/// One executable discovered by [`ProcessToolProvider::scan`]: its path plus
+/// the metadata read from its sidecar `<name>.json` file.
+struct ProcessToolEntry {
+    /// Path to the executable to spawn for this tool.
+    executable: PathBuf,
+    /// Human-readable description, taken from the sidecar metadata.
+    description: String,
+    /// Raw JSON Schema object from the sidecar metadata, deserialized into
+    /// an [`InputSchema`] fresh on each [`Tool::input_schema`] call.
+    schema: serde_json::Value,
+}
+
+/// A [`ToolProvider`] backed by executables in a manifest directory.
+///
+/// See the [module documentation](self) for the sidecar metadata format and
+/// the stdin/stdout/stderr convention used to invoke each tool.
+pub struct ProcessToolProvider {
+    tools: HashMap<String, ProcessToolEntry>,
+}
+
+/// The sidecar `<name>.json` metadata format [`ProcessToolProvider::scan`]
+/// expects next to each executable.
+#[derive(serde::Deserialize)]
+struct ProcessToolMetadata {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+}
+
+impl ProcessToolProvider {
+    /// Scans `manifest_dir` for executables with a sidecar `<name>.json`
+    /// metadata file and builds a provider over whatever it finds.
+    ///
+    /// An executable missing its sidecar, or one whose sidecar doesn't parse
+    /// as [`ProcessToolMetadata`], is skipped rather than failing the whole
+    /// scan -- a single malformed entry in the manifest directory shouldn't
+    /// prevent the rest of the tools in it from loading.
+    pub fn scan(manifest_dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let manifest_dir = manifest_dir.as_ref();
+        let mut tools = HashMap::new();
+        for entry in std::fs::read_dir(manifest_dir)? {
+            let path = entry?.path();
+            if !is_executable(&path) {
+                continue;
+            }
+            let metadata_path = path.with_extension("json");
+            let Ok(raw) = std::fs::read_to_string(&metadata_path) else {
+                eprintln!(
+                    "mcp::providers: no metadata sidecar for {}, skipping",
+                    path.display()
+                );
+                continue;
+            };
+            let metadata: ProcessToolMetadata = match serde_json::from_str(&raw) {
+                Ok(metadata) => metadata,
+                Err(err) => {
+                    eprintln!(
+                        "mcp::providers: invalid metadata in {}: {err}",
+                        metadata_path.display()
+                    );
+                    continue;
+                }
+            };
+            tools.insert(
+                metadata.name,
+                ProcessToolEntry {
+                    executable: path,
+                    description: metadata.description,
+                    schema: metadata.input_schema,
+                },
+            );
+        }
+        Ok(ProcessToolProvider { tools })
+    }
+}
+
+impl ToolProvider for ProcessToolProvider {
+    fn tool_names(&self) -> Vec<String> {
+        self.tools.keys().cloned().collect()
+    }
+
+    fn get_tool(&self, name: &str) -> Option<Box<dyn Tool>> {
+        let entry = self.tools.get(name)?;
+        Some(Box::new(ProcessTool {
+            name: name.to_string(),
+            description: entry.description.clone(),
+            schema: entry.schema.clone(),
+            executable: entry.executable.clone(),
+        }))
+    }
+}
+
+/// Returns whether `path` is a regular file with at least one executable
+/// permission bit set.
+///
+/// There's no portable notion of "executable" outside Unix permission bits,
+/// so this crate's non-Unix targets (wasm32 among them) simply treat nothing
+/// as executable, leaving [`ProcessToolProvider::scan`] to find zero tools
+/// there rather than fail.
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    let Ok(metadata) = path.metadata() else {
+        return false;
+    };
+    metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+}
+
+#[cfg(not(unix))]
+fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+/// A [`Tool`] that dispatches to an external executable, built by
+/// [`ProcessToolProvider::get_tool`].
+struct ProcessTool {
+    name: String,
+    description: String,
+    schema: serde_json::Value,
+    executable: PathBuf,
+}
+
+impl Tool for ProcessTool {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn input_schema(&self) -> InputSchema {
+        serde_json::from_value(self.schema.clone()).unwrap_or_else(|_| InputSchema::new(vec![]))
+    }
+
+    fn call(
+        &self,
+        params: HashMap<String, serde_json::Value>,
+    ) -> Result<ToolCallResponse, ToolCallError> {
+        let input = serde_json::Value::Object(params.into_iter().collect()).to_string();
+        let mut child = Command::new(&self.executable)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|err| {
+                ToolCallError::new(vec![
+                    format!("failed to launch {}: {err}", self.executable.display()).into(),
+                ])
+            })?;
+        let mut stdin = child.stdin.take().expect("stdin was piped");
+        if let Err(err) = stdin.write_all(input.as_bytes()) {
+            return Err(ToolCallError::new(vec![
+                format!("failed to write params to {}: {err}", self.executable.display()).into(),
+            ]));
+        }
+        drop(stdin); // close stdin so the child sees EOF
+        let output = child.wait_with_output().map_err(|err| {
+            ToolCallError::new(vec![
+                format!("failed to run {}: {err}", self.executable.display()).into(),
+            ])
+        })?;
+        if output.status.success() {
+            let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+            let content = match serde_json::from_str::<serde_json::Value>(stdout.trim()) {
+                Ok(json) => ToolContent::Json(json),
+                Err(_) => ToolContent::Text(stdout),
+            };
+            Ok(ToolCallResponse::new(vec![content]))
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+            Err(ToolCallError::new(vec![
+                format!("{} exited with {}: {stderr}", self.executable.display(), output.status).into(),
+            ]))
+        }
+    }
+}
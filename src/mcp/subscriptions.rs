@@ -0,0 +1,131 @@
+//! Subscription/streaming subsystem for server-initiated notifications.
+//!
+//! [`dispatch_in_target`](super::dispatch_in_target) only answers
+//! request/response calls, so an agent that wants a live tail of activity
+//! (log lines captured via the `logwise` feature, or state changes emitted
+//! by a custom command) would otherwise have to poll. This module adds a
+//! `subscribe`/`unsubscribe` pair of methods, modeled on the usual
+//! JSON-RPC pub-sub pattern: `subscribe` allocates a [`SubscriptionId`] and
+//! returns it, and from then on matching [`publish`] calls push
+//! server-initiated `notifications/subscription` messages tagged with that
+//! ID back over the wire, with no request involved. `unsubscribe` tears
+//! the registration down.
+//!
+//! # Architecture
+//!
+//! There's no async runtime here, so the registry is just a
+//! `Mutex`-guarded map from [`SubscriptionId`] to the channel name it's
+//! listening on, and [`publish`] pushes notifications directly from
+//! whichever thread calls it.
+
+use crate::internal_proxy::InternalProxy;
+use crate::jrpc::{Notification, Request, Response};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+/// Identifies a single subscription, returned by `subscribe` and passed to
+/// `unsubscribe`.
+pub(crate) type SubscriptionId = u32;
+
+/// The next subscription ID to hand out. Monotonically increasing and never
+/// reused, even after `unsubscribe`.
+static NEXT_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Active subscriptions, keyed by [`SubscriptionId`], each recording the
+/// channel it listens on.
+static SUBSCRIPTIONS: LazyLock<Mutex<HashMap<SubscriptionId, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Registers a new subscription to `channel` and returns its ID.
+fn subscribe(channel: String) -> SubscriptionId {
+    let id = NEXT_ID.fetch_add(1, Ordering::AcqRel);
+    SUBSCRIPTIONS.lock().unwrap().insert(id, channel);
+    id
+}
+
+/// Removes a subscription. Returns `true` if `id` was actually registered.
+fn unsubscribe(id: SubscriptionId) -> bool {
+    SUBSCRIPTIONS.lock().unwrap().remove(&id).is_some()
+}
+
+/// Pushes `payload` to every subscriber of `channel` as a
+/// `notifications/subscription` server notification carrying the
+/// subscription ID and the channel name.
+///
+/// A no-op if nothing is subscribed to `channel`. Benign (like any other
+/// notification) if the connection is currently down.
+pub fn publish(channel: &str, payload: serde_json::Value) {
+    let ids: Vec<SubscriptionId> = SUBSCRIPTIONS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|(_, c)| c.as_str() == channel)
+        .map(|(id, _)| *id)
+        .collect();
+    for id in ids {
+        let params = serde_json::json!({
+            "subscriptionId": id,
+            "channel": channel,
+            "payload": payload,
+        });
+        let notification =
+            Notification::new("notifications/subscription".to_string(), Some(params));
+        let _ = InternalProxy::current().send_notification(notification);
+    }
+}
+
+/// Parameters for a `subscribe` request.
+#[derive(Debug, serde::Deserialize)]
+struct SubscribeParams {
+    /// The channel to listen on (e.g. `"logs"`).
+    channel: String,
+}
+
+/// Parameters for an `unsubscribe` request.
+#[derive(Debug, serde::Deserialize)]
+struct UnsubscribeParams {
+    /// The ID returned from the matching `subscribe` call.
+    #[serde(rename = "subscriptionId")]
+    subscription_id: SubscriptionId,
+}
+
+/// Handles a `subscribe` request, returning `{"subscriptionId": <id>}`.
+pub(crate) fn subscribe_process(request: Request) -> Response<serde_json::Value> {
+    let params = match request.params.map(serde_json::from_value::<SubscribeParams>) {
+        Some(Ok(params)) => params,
+        Some(Err(err)) => {
+            return Response::err(crate::jrpc::Error::invalid_params(err.to_string()), request.id);
+        }
+        None => {
+            return Response::err(
+                crate::jrpc::Error::invalid_params("Missing 'channel'".to_string()),
+                request.id,
+            );
+        }
+    };
+    let id = subscribe(params.channel);
+    Response::new(serde_json::json!({ "subscriptionId": id }), request.id)
+}
+
+/// Handles an `unsubscribe` request, returning the removed subscription's
+/// existence as a boolean result.
+pub(crate) fn unsubscribe_process(request: Request) -> Response<serde_json::Value> {
+    let params = match request
+        .params
+        .map(serde_json::from_value::<UnsubscribeParams>)
+    {
+        Some(Ok(params)) => params,
+        Some(Err(err)) => {
+            return Response::err(crate::jrpc::Error::invalid_params(err.to_string()), request.id);
+        }
+        None => {
+            return Response::err(
+                crate::jrpc::Error::invalid_params("Missing 'subscriptionId'".to_string()),
+                request.id,
+            );
+        }
+    };
+    let removed = unsubscribe(params.subscription_id);
+    Response::new(serde_json::json!(removed), request.id)
+}
@@ -102,7 +102,8 @@ use serde::de::{MapAccess, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fmt;
-use std::sync::{LazyLock, RwLock};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex, RwLock};
 
 /// Trait for implementing MCP tools.
 ///
@@ -183,6 +184,78 @@ pub trait Tool: Send + Sync {
         &self,
         params: HashMap<String, serde_json::Value>,
     ) -> Result<ToolCallResponse, ToolCallError>;
+
+    /// Executes the tool with the provided parameters, reporting
+    /// incremental progress through `reporter` as it goes.
+    ///
+    /// Long-running tools (builds, crawls, multi-step jobs) can override
+    /// this to call [`ProgressReporter::emit`](crate::mcp::progress::ProgressReporter::emit)
+    /// or [`ProgressReporter::progress`](crate::mcp::progress::ProgressReporter::progress)
+    /// as work completes, while still returning the full
+    /// [`ToolCallResponse`] at the end for clients that ignore the
+    /// notifications. The default implementation ignores `reporter` and
+    /// simply delegates to [`Self::call`].
+    fn call_streaming(
+        &self,
+        params: HashMap<String, serde_json::Value>,
+        reporter: &crate::mcp::progress::ProgressReporter,
+    ) -> Result<ToolCallResponse, ToolCallError> {
+        let _ = reporter;
+        self.call(params)
+    }
+
+    /// Returns the schema describing the `structuredContent` this tool
+    /// returns, if any.
+    ///
+    /// Tools that return [`ToolContent::Json`] should override this so
+    /// clients know the shape of that JSON ahead of time; it's surfaced to
+    /// them as `outputSchema` in [`ToolInfo`]. The default implementation
+    /// returns `None`, meaning the tool has no structured output.
+    fn output_schema(&self) -> Option<InputSchema> {
+        None
+    }
+
+    /// Returns the categories this tool belongs to, for callers (e.g.
+    /// [`crate::mcp::latest_tools::LatestTools`]'s `tags` filter) that want
+    /// to discover only tools of a certain kind. The default implementation
+    /// returns no tags.
+    fn tags(&self) -> Vec<&str> {
+        Vec::new()
+    }
+}
+
+/// Deserializes a tool's `params` into `T`, sparing [`Tool::call`]
+/// implementations the boilerplate of pulling each argument out of the raw
+/// `HashMap` by hand and checking its presence and type.
+///
+/// A serde failure (missing field, wrong type, ...) is turned into a
+/// [`ToolCallError`] whose message names the offending field and the type
+/// expected, the same information an `-32602` "Invalid params"
+/// [`Error::invalid_params`](crate::jrpc::Error::invalid_params) would
+/// carry in its `data`.
+///
+/// # Examples
+///
+/// ```
+/// use exfiltrate::mcp::tools::{from_params, ToolCallError, ToolCallResponse};
+/// use std::collections::HashMap;
+///
+/// #[derive(serde::Deserialize)]
+/// struct EchoParams {
+///     message: String,
+/// }
+///
+/// fn call(params: HashMap<String, serde_json::Value>) -> Result<ToolCallResponse, ToolCallError> {
+///     let params: EchoParams = from_params(params)?;
+///     Ok(ToolCallResponse::new(vec![format!("Echo: {}", params.message).into()]))
+/// }
+/// ```
+pub fn from_params<T: serde::de::DeserializeOwned>(
+    params: HashMap<String, serde_json::Value>,
+) -> Result<T, ToolCallError> {
+    let value = serde_json::Value::Object(params.into_iter().collect());
+    serde_json::from_value(value)
+        .map_err(|err| ToolCallError::new(vec![format!("Invalid params (-32602): {err}").into()]))
 }
 
 /// Tools available in the target application.
@@ -226,6 +299,10 @@ pub(crate) static SHARED_TOOLS: LazyLock<Vec<Box<dyn Tool>>> = LazyLock::new(||
 pub struct ToolList {
     /// The list of available tools with their metadata
     pub(crate) tools: Vec<ToolInfo>,
+    /// The registry revision at the time this list was built; see
+    /// [`current_revision`]. A caller can pass this back as `latest_tools`'s
+    /// `since_revision` parameter to get only the delta on a later call.
+    pub(crate) revision: u64,
 }
 
 impl ToolList {
@@ -242,10 +319,122 @@ impl ToolList {
     /// assert!(json.contains("\"tools\":[]"));
     /// ```
     pub fn empty() -> Self {
-        ToolList { tools: Vec::new() }
+        ToolList {
+            tools: Vec::new(),
+            revision: current_revision(),
+        }
+    }
+}
+
+/// A change to the [`TOOLS`] registry, observed via
+/// [`subscribe_tool_changes`].
+#[derive(Debug, Clone)]
+pub enum ToolChange {
+    /// A tool with this name was newly registered via [`add_tool`].
+    Added(String),
+    /// A tool with this name was unregistered via [`remove_tool`].
+    Removed(String),
+    /// A tool with this name replaced an earlier registration of the same
+    /// name, via [`add_tool`].
+    Replaced(String),
+}
+
+/// Monotonically increasing counter bumped by every [`add_tool`]/
+/// [`remove_tool`] mutation of [`TOOLS`]. Surfaced to callers through
+/// [`ToolList::revision`](ToolList) so a long-lived agent can ask
+/// `latest_tools` for just the delta since its last known revision instead
+/// of the whole tool list; see [`changes_since`].
+static REVISION: AtomicU64 = AtomicU64::new(0);
+
+/// The full history of [`TOOLS`] mutations, each tagged with the revision it
+/// bumped to. Read by [`changes_since`] to compute a delta; never trimmed,
+/// on the assumption that tool churn over a session is small compared to the
+/// cost of re-listing every tool on every poll.
+static CHANGE_LOG: LazyLock<Mutex<Vec<(u64, ToolChange)>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Subscribers registered with [`subscribe_tool_changes`]. A subscriber
+/// whose `send` fails (its `Receiver` was dropped) is pruned on the next
+/// mutation.
+static CHANGE_SUBSCRIBERS: LazyLock<Mutex<Vec<std::sync::mpsc::Sender<ToolChange>>>> =
+    LazyLock::new(|| Mutex::new(Vec::new()));
+
+/// Returns the current registry revision; see [`REVISION`].
+pub(crate) fn current_revision() -> u64 {
+    REVISION.load(Ordering::Acquire)
+}
+
+/// Records `change` at the next revision, notifies every
+/// [`subscribe_tool_changes`] subscriber, and sends the usual
+/// `notifications/tools/list_changed` wire notification -- called by
+/// [`add_tool`] and [`remove_tool`] after they've mutated [`TOOLS`].
+fn record_change(change: ToolChange) {
+    let revision = REVISION.fetch_add(1, Ordering::AcqRel) + 1;
+    CHANGE_LOG.lock().unwrap().push((revision, change.clone()));
+    CHANGE_SUBSCRIBERS
+        .lock()
+        .unwrap()
+        .retain(|sender| sender.send(change.clone()).is_ok());
+
+    let n = Notification::new("notifications/tools/list_changed".to_string(), None);
+    let r = InternalProxy::current().send_notification(n);
+    match r {
+        Ok(_) => {}
+        Err(crate::internal_proxy::Error::NotConnected) => {
+            //benign
+        }
+        Err(err) => {
+            eprintln!("tools: failed to send tools/list_changed: {:?}", err);
+        }
     }
 }
 
+/// The net set of tool names added/removed since some earlier revision,
+/// computed by [`changes_since`].
+pub(crate) struct ToolChangeDelta {
+    pub(crate) added: Vec<String>,
+    pub(crate) removed: Vec<String>,
+}
+
+/// Folds [`CHANGE_LOG`] entries after revision `since` into the net set of
+/// names added and removed: a name added then later removed cancels out,
+/// while a name removed then added again, like a [`ToolChange::Replaced`],
+/// counts as an add, since the caller should re-fetch its schema.
+pub(crate) fn changes_since(since: u64) -> ToolChangeDelta {
+    let mut added = std::collections::BTreeSet::new();
+    let mut removed = std::collections::BTreeSet::new();
+    for (revision, change) in CHANGE_LOG.lock().unwrap().iter() {
+        if *revision <= since {
+            continue;
+        }
+        match change {
+            ToolChange::Added(name) | ToolChange::Replaced(name) => {
+                removed.remove(name);
+                added.insert(name.clone());
+            }
+            ToolChange::Removed(name) => {
+                if !added.remove(name) {
+                    removed.insert(name.clone());
+                }
+            }
+        }
+    }
+    ToolChangeDelta {
+        added: added.into_iter().collect(),
+        removed: removed.into_iter().collect(),
+    }
+}
+
+/// Subscribes to [`TOOLS`] registry mutations, returning a `Receiver` that
+/// yields a [`ToolChange`] for each `add_tool`/`remove_tool` from this point
+/// forward. Dropping the receiver unsubscribes (detected and pruned lazily,
+/// on the next mutation).
+pub fn subscribe_tool_changes() -> std::sync::mpsc::Receiver<ToolChange> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    CHANGE_SUBSCRIBERS.lock().unwrap().push(sender);
+    receiver
+}
+
 /// Metadata about a tool.
 ///
 /// Contains all the information needed for an agent to understand
@@ -259,6 +448,9 @@ pub(crate) struct ToolInfo {
     /// Schema defining the tool's input parameters
     #[serde(rename = "inputSchema")]
     input_schema: InputSchema,
+    /// Schema describing the tool's `structuredContent`, if it returns one
+    #[serde(rename = "outputSchema", skip_serializing_if = "Option::is_none")]
+    output_schema: Option<InputSchema>,
 }
 
 impl ToolInfo {
@@ -270,8 +462,24 @@ impl ToolInfo {
             name: tool.name().to_string(),
             description: tool.description().to_string(),
             input_schema: tool.input_schema(),
+            output_schema: tool.output_schema(),
         }
     }
+
+    /// The tool's name, as reported by `tools/list`.
+    pub(crate) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Overrides the reported name, keeping everything else as-is.
+    ///
+    /// Used by the transit proxy to namespace a connected target's tools
+    /// with its connection id (`{id}::{name}`) when aggregating `tools/list`
+    /// across more than one simultaneously-connected target.
+    pub(crate) fn renamed(mut self, name: String) -> Self {
+        self.name = name;
+        self
+    }
 }
 
 /// Schema defining a tool's input parameters.
@@ -303,8 +511,11 @@ impl ToolInfo {
 pub struct InputSchema {
     /// The schema type (always "object" for tool parameters)
     r#type: String,
-    /// Map of parameter names to their schema definitions
-    properties: HashMap<String, HashMap<String, serde_json::Value>>,
+    /// Map of parameter names to their schema definitions. Each value is a
+    /// JSON Schema property object, which may itself carry `"enum"`,
+    /// `"default"`, `"items"`, or nested `"properties"`/`"required"` (see
+    /// [`Argument`]'s builder methods).
+    properties: HashMap<String, serde_json::Value>,
     /// List of required parameter names
     required: Vec<String>,
 }
@@ -334,6 +545,15 @@ pub struct Argument {
     description: String,
     /// Whether this parameter is required
     required: bool,
+    /// Restricts the value to one of a fixed set, emitted as `"enum"`
+    r#enum: Option<Vec<serde_json::Value>>,
+    /// Value to use when the argument is omitted, emitted as `"default"`
+    default: Option<serde_json::Value>,
+    /// Element type for an `"array"`-typed argument, emitted as `"items"`
+    items: Option<Box<Argument>>,
+    /// Nested properties for an `"object"`-typed argument, emitted as
+    /// nested `"properties"`/`"required"`
+    properties: Option<Vec<Argument>>,
 }
 
 impl Argument {
@@ -371,8 +591,151 @@ impl Argument {
             r#type,
             description,
             required,
+            r#enum: None,
+            default: None,
+            items: None,
+            properties: None,
+        }
+    }
+
+    /// Restricts this argument to one of `values`, emitted as JSON Schema's
+    /// `"enum"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exfiltrate::mcp::tools::Argument;
+    /// use serde_json::json;
+    ///
+    /// let arg = Argument::new(
+    ///     "unit".to_string(),
+    ///     "string".to_string(),
+    ///     "Temperature unit".to_string(),
+    ///     true
+    /// ).with_enum(vec![json!("celsius"), json!("fahrenheit")]);
+    /// ```
+    pub fn with_enum(mut self, values: Vec<serde_json::Value>) -> Self {
+        self.r#enum = Some(values);
+        self
+    }
+
+    /// Sets the value used when this argument is omitted, emitted as JSON
+    /// Schema's `"default"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exfiltrate::mcp::tools::Argument;
+    /// use serde_json::json;
+    ///
+    /// let arg = Argument::new(
+    ///     "limit".to_string(),
+    ///     "number".to_string(),
+    ///     "Maximum results".to_string(),
+    ///     false
+    /// ).with_default(json!(10));
+    /// ```
+    pub fn with_default(mut self, default: serde_json::Value) -> Self {
+        self.default = Some(default);
+        self
+    }
+
+    /// Declares the element type for an `"array"`-typed argument, emitted
+    /// as JSON Schema's `"items"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exfiltrate::mcp::tools::Argument;
+    ///
+    /// let arg = Argument::new(
+    ///     "tags".to_string(),
+    ///     "array".to_string(),
+    ///     "Tags to apply".to_string(),
+    ///     false
+    /// ).with_items(Argument::new(
+    ///     "tag".to_string(),
+    ///     "string".to_string(),
+    ///     "A single tag".to_string(),
+    ///     true
+    /// ));
+    /// ```
+    pub fn with_items(mut self, items: Argument) -> Self {
+        self.items = Some(Box::new(items));
+        self
+    }
+
+    /// Declares the nested properties for an `"object"`-typed argument,
+    /// emitted as JSON Schema's nested `"properties"`/`"required"`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exfiltrate::mcp::tools::Argument;
+    ///
+    /// let arg = Argument::new(
+    ///     "address".to_string(),
+    ///     "object".to_string(),
+    ///     "Mailing address".to_string(),
+    ///     true
+    /// ).with_properties(vec![
+    ///     Argument::new("city".to_string(), "string".to_string(), "City".to_string(), true),
+    /// ]);
+    /// ```
+    pub fn with_properties(mut self, properties: Vec<Argument>) -> Self {
+        self.properties = Some(properties);
+        self
+    }
+}
+
+/// Builds the JSON Schema property object for a single argument, recursing
+/// into `"items"` and nested `"properties"` as declared.
+fn argument_schema(argument: &Argument) -> serde_json::Value {
+    let mut schema = serde_json::Map::new();
+    schema.insert("type".to_string(), argument.r#type.clone().into());
+    schema.insert(
+        "description".to_string(),
+        argument.description.clone().into(),
+    );
+    if let Some(values) = &argument.r#enum {
+        schema.insert("enum".to_string(), serde_json::Value::Array(values.clone()));
+    }
+    if let Some(default) = &argument.default {
+        schema.insert("default".to_string(), default.clone());
+    }
+    if let Some(items) = &argument.items {
+        schema.insert("items".to_string(), argument_schema(items));
+    }
+    if let Some(properties) = &argument.properties {
+        let (nested_properties, nested_required) = properties_schema(properties);
+        schema.insert(
+            "properties".to_string(),
+            serde_json::Value::Object(nested_properties),
+        );
+        if !nested_required.is_empty() {
+            schema.insert(
+                "required".to_string(),
+                nested_required.into_iter().map(serde_json::Value::String).collect(),
+            );
         }
     }
+    serde_json::Value::Object(schema)
+}
+
+/// Builds the `properties` map and `required` list for a set of arguments,
+/// shared by [`InputSchema::new`] and nested `"object"`/`"array"` arguments.
+fn properties_schema(
+    arguments: &[Argument],
+) -> (serde_json::Map<String, serde_json::Value>, Vec<String>) {
+    let mut properties = serde_json::Map::new();
+    let mut required = Vec::new();
+    for argument in arguments {
+        if argument.required {
+            required.push(argument.name.clone());
+        }
+        properties.insert(argument.name.clone(), argument_schema(argument));
+    }
+    (properties, required)
 }
 
 impl InputSchema {
@@ -406,38 +769,158 @@ impl InputSchema {
     /// ]);
     /// ```
     pub fn new<A: IntoIterator<Item = Argument>>(arguments: A) -> Self {
-        let mut properties = HashMap::new();
-        let mut required = Vec::new();
-        for argument in arguments {
-            let mut inner_map: HashMap<String, serde_json::Value> = HashMap::new();
-            inner_map.insert("type".to_string(), argument.r#type.into());
-            inner_map.insert("description".to_string(), argument.description.into());
-            if argument.required {
-                required.push(argument.name.clone());
-            }
-            properties.insert(argument.name, inner_map);
-        }
+        let arguments: Vec<Argument> = arguments.into_iter().collect();
+        let (properties, required) = properties_schema(&arguments);
         InputSchema {
             r#type: "object".to_string(),
-            properties,
+            properties: properties.into_iter().collect(),
             required,
         }
     }
+
+    /// Validates `arguments` against this schema: every name in `required`
+    /// must be present, and every supplied argument must match its declared
+    /// `"type"` (`"string"`, `"number"`, `"integer"`, `"boolean"`, `"object"`,
+    /// or `"array"`).
+    ///
+    /// Collects every failure found, not just the first, so a caller (e.g.
+    /// an LLM agent) can correct all of them in one pass instead of
+    /// discovering them one at a time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use exfiltrate::mcp::tools::{Argument, InputSchema};
+    /// use std::collections::HashMap;
+    ///
+    /// let schema = InputSchema::new(vec![
+    ///     Argument::new("name".to_string(), "string".to_string(), "".to_string(), true),
+    /// ]);
+    ///
+    /// assert!(schema.validate(&HashMap::new()).is_err());
+    /// ```
+    pub fn validate(&self, arguments: &HashMap<String, serde_json::Value>) -> Result<(), Vec<String>> {
+        let mut errors = Vec::new();
+        for name in &self.required {
+            if !arguments.contains_key(name) {
+                errors.push(format!("Missing required argument: {name}"));
+            }
+        }
+        for (name, value) in arguments {
+            let Some(property) = self.properties.get(name) else {
+                continue; // arguments outside the declared schema are tolerated
+            };
+            let Some(expected_type) = property.get("type").and_then(|v| v.as_str()) else {
+                continue;
+            };
+            let matches = match expected_type {
+                "string" => value.as_str().is_some(),
+                "number" => value.as_f64().is_some(),
+                "integer" => value.as_i64().is_some(),
+                "boolean" => value.as_bool().is_some(),
+                "object" => value.as_object().is_some(),
+                "array" => value.as_array().is_some(),
+                _ => true, // unrecognized declared types aren't checked
+            };
+            if !matches {
+                errors.push(format!(
+                    "Argument '{name}' must be of type {expected_type}, got: {value}"
+                ));
+            }
+        }
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// Narrows [`list_int_filtered`] to tools whose name starts with
+/// `name_prefix` (if set) and which carry at least one of `tags` (if
+/// non-empty); either left at its default admits everything.
+#[derive(Default)]
+pub(crate) struct ToolListFilter<'a> {
+    pub(crate) name_prefix: Option<&'a str>,
+    pub(crate) tags: &'a [String],
+}
+
+impl ToolListFilter<'_> {
+    pub(crate) fn matches(&self, tool: &dyn Tool) -> bool {
+        if let Some(prefix) = self.name_prefix
+            && !tool.name().starts_with(prefix)
+        {
+            return false;
+        }
+        if !self.tags.is_empty() {
+            let tool_tags = tool.tags();
+            if !self.tags.iter().any(|tag| tool_tags.contains(&tag.as_str())) {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 /// Internal function to list all available tools.
 ///
 /// Combines tools from both [`TOOLS`] and [`SHARED_TOOLS`] collections.
 pub(crate) fn list_int() -> ToolList {
-    let tool_infos: Vec<ToolInfo> = TOOLS
+    list_int_filtered(&ToolListFilter::default())
+}
+
+/// Like [`list_int`], but only including tools [`ToolListFilter::matches`].
+///
+/// Merges in tools discovered by registered
+/// [`crate::mcp::providers::ToolProvider`]s alongside [`TOOLS`] and
+/// [`SHARED_TOOLS`], so a provider-backed executable shows up in
+/// `latest_tools` the same as any in-process tool.
+pub(crate) fn list_int_filtered(filter: &ToolListFilter) -> ToolList {
+    let mut tool_infos: Vec<ToolInfo> = TOOLS
         .read()
         .unwrap()
         .iter()
         .chain(SHARED_TOOLS.iter())
+        .filter(|tool| filter.matches(tool.as_ref()))
         .map(|tool| ToolInfo::from_tool(tool.as_ref()))
         .collect();
-    let tool_list = ToolList { tools: tool_infos };
-    tool_list
+    tool_infos.extend(crate::mcp::providers::list_tool_infos(filter));
+    ToolList {
+        tools: tool_infos,
+        revision: current_revision(),
+    }
+}
+
+/// Looks up a single tool's [`ToolInfo`] by exact name, across [`TOOLS`],
+/// [`SHARED_TOOLS`], and registered
+/// [`crate::mcp::providers::ToolProvider`]s. Used by `latest_tools`'s
+/// `tool_choice` parameter to confirm a specific tool exists and hand back
+/// just its schema.
+pub(crate) fn tool_info_by_name(name: &str) -> Option<ToolInfo> {
+    TOOLS
+        .read()
+        .unwrap()
+        .iter()
+        .chain(SHARED_TOOLS.iter())
+        .find(|tool| tool.name() == name)
+        .map(|tool| ToolInfo::from_tool(tool.as_ref()))
+        .or_else(|| {
+            crate::mcp::providers::find_tool(name).map(|tool| ToolInfo::from_tool(tool.as_ref()))
+        })
+}
+
+/// Looks up a single tool's [`InputSchema`] by exact name, across [`TOOLS`],
+/// [`SHARED_TOOLS`], and registered
+/// [`crate::mcp::providers::ToolProvider`]s. Used by `run_latest_tool` to
+/// validate arguments against [`InputSchema::validate`] before dispatch, the
+/// same check [`call_imp`] runs for a direct `tools/call`, just surfaced
+/// earlier so a bad proxied call fails before reaching the target tool at
+/// all.
+pub(crate) fn schema_by_name(name: &str) -> Option<InputSchema> {
+    TOOLS
+        .read()
+        .unwrap()
+        .iter()
+        .chain(SHARED_TOOLS.iter())
+        .find(|tool| tool.name() == name)
+        .map(|tool| tool.input_schema())
+        .or_else(|| crate::mcp::providers::find_tool(name).map(|tool| tool.input_schema()))
 }
 
 /// Processes a `tools/list` request.
@@ -487,17 +970,51 @@ pub(crate) fn list_process(request: Request) -> Response<ToolList> {
 ///
 /// add_tool(Box::new(MyTool));
 /// ```
-pub fn add_tool(tool: Box<dyn Tool>) {
-    TOOLS.write().unwrap().push(tool);
-    //create a tool changed message
-    let n = Notification::new("notifications/tools/list_changed".to_string(), None);
-    let r = InternalProxy::current().send_notification(n);
-    match r {
-        Ok(_) => {}
-        Err(crate::internal_proxy::Error::NotConnected) => {
-            //benign
+///
+/// # Returns
+///
+/// `true` if `tool` replaced an existing registration of the same name,
+/// `false` if it was newly added.
+pub fn add_tool(tool: Box<dyn Tool>) -> bool {
+    let name = tool.name().to_string();
+    let mut tools = TOOLS.write().unwrap();
+    let replaced = match tools.iter_mut().find(|t| t.name() == name) {
+        Some(existing) => {
+            *existing = tool;
+            true
         }
-    }
+        None => {
+            tools.push(tool);
+            false
+        }
+    };
+    drop(tools);
+    record_change(if replaced {
+        ToolChange::Replaced(name)
+    } else {
+        ToolChange::Added(name)
+    });
+    replaced
+}
+
+/// Unregisters the tool named `name` from [`TOOLS`].
+///
+/// Only tools added via [`add_tool`] can be removed this way --
+/// [`SHARED_TOOLS`] (e.g. `latest_tools` itself) aren't affected.
+///
+/// # Returns
+///
+/// `true` if a tool was actually removed, `false` if no tool by that name
+/// was registered.
+pub fn remove_tool(name: &str) -> bool {
+    let mut tools = TOOLS.write().unwrap();
+    let Some(index) = tools.iter().position(|t| t.name() == name) else {
+        return false;
+    };
+    tools.remove(index);
+    drop(tools);
+    record_change(ToolChange::Removed(name.to_string()));
+    true
 }
 
 /// Parameters for invoking a tool.
@@ -516,6 +1033,109 @@ impl ToolCallParams {
     pub(crate) fn new(name: String, arguments: HashMap<String, serde_json::Value>) -> Self {
         ToolCallParams { name, arguments }
     }
+
+    /// Deserializes `value` into [`ToolCallParams`], tolerating a truncated
+    /// or malformed `arguments` field.
+    ///
+    /// Some clients stream tool-call arguments as raw, not-yet-fully-formed
+    /// JSON text (e.g. the call is dispatched before the model has finished
+    /// emitting the argument object). In that case `arguments` arrives as a
+    /// JSON *string* containing malformed JSON, rather than as a structured
+    /// object. This first tries the strict deserialization; if that fails
+    /// and `value` looks like `{"name": ..., "arguments": "<text>"}`, it
+    /// applies [`repair_partial_json`] to the string and retries. If neither
+    /// succeeds, the original strict-parse error is returned so callers see
+    /// the same [`Error::invalid_params`](crate::jrpc::Error::invalid_params)
+    /// behavior as before.
+    pub(crate) fn parse_lenient(value: serde_json::Value) -> Result<Self, serde_json::Error> {
+        match serde_json::from_value::<ToolCallParams>(value.clone()) {
+            Ok(params) => Ok(params),
+            Err(err) => Self::repair_and_parse(&value).ok_or(err),
+        }
+    }
+
+    /// Attempts the repair path described in [`Self::parse_lenient`]. Returns
+    /// `None` if `value` doesn't have the expected shape or the repaired
+    /// text still doesn't parse.
+    fn repair_and_parse(value: &serde_json::Value) -> Option<Self> {
+        let object = value.as_object()?;
+        let name = object.get("name")?.as_str()?.to_string();
+        let raw_arguments = object.get("arguments")?.as_str()?;
+        let repaired = repair_partial_json(raw_arguments);
+        let arguments = serde_json::from_str(&repaired).ok()?;
+        Some(ToolCallParams { name, arguments })
+    }
+}
+
+/// Repairs truncated or trailing-comma JSON text well enough to parse,
+/// closing whatever strings and brackets were left open.
+///
+/// This is deliberately narrow: it does not attempt to fix arbitrary
+/// malformed JSON, only the shape produced by a client that stopped
+/// streaming mid-value. It walks `text` once, tracking whether each
+/// character is inside a string, then:
+///
+/// - strips a trailing comma that directly precedes a closing bracket
+/// - closes an unterminated string
+/// - appends closing brackets for anything left open, in reverse order
+fn repair_partial_json(text: &str) -> String {
+    let mut repaired = String::with_capacity(text.len());
+    let mut stack = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for ch in text.chars() {
+        if in_string {
+            repaired.push(ch);
+            if escaped {
+                escaped = false;
+            } else if ch == '\\' {
+                escaped = true;
+            } else if ch == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match ch {
+            '"' => {
+                in_string = true;
+                repaired.push(ch);
+            }
+            '{' => {
+                stack.push('}');
+                repaired.push(ch);
+            }
+            '[' => {
+                stack.push(']');
+                repaired.push(ch);
+            }
+            '}' | ']' => {
+                strip_trailing_comma(&mut repaired);
+                stack.pop();
+                repaired.push(ch);
+            }
+            _ => repaired.push(ch),
+        }
+    }
+
+    if in_string {
+        repaired.push('"');
+    }
+    strip_trailing_comma(&mut repaired);
+    while let Some(closer) = stack.pop() {
+        repaired.push(closer);
+    }
+    repaired
+}
+
+/// Removes a trailing `,` (and any whitespace after it) from `text`, if
+/// present. Used by [`repair_partial_json`] before closing a bracket, since
+/// `{"a": 1,}` and `{"a": 1,` are both invalid JSON once closed.
+fn strip_trailing_comma(text: &mut String) {
+    let trimmed = text.trim_end();
+    if trimmed.ends_with(',') {
+        text.truncate(trimmed.len() - 1);
+    }
 }
 
 /// Response from a successful tool invocation.
@@ -538,6 +1158,13 @@ pub struct ToolCallResponse {
     pub(crate) content: Vec<ToolContent>,
     /// Whether this response represents an error
     is_error: bool,
+    /// Machine-readable echo of the tool's JSON content, if any.
+    ///
+    /// Set from the first [`ToolContent::Json`] entry in `content`, so
+    /// clients that understand structured output don't have to parse it
+    /// back out of the text fallback.
+    #[serde(rename = "structuredContent", skip_serializing_if = "Option::is_none")]
+    structured_content: Option<serde_json::Value>,
 }
 
 impl ToolCallResponse {
@@ -557,11 +1184,27 @@ impl ToolCallResponse {
     /// ]);
     /// ```
     pub fn new(content: Vec<ToolContent>) -> Self {
+        let structured_content = content.iter().find_map(|c| match c {
+            ToolContent::Json(value) => Some(value.clone()),
+            ToolContent::Text(_) => None,
+        });
         ToolCallResponse {
             content,
             is_error: false,
+            structured_content,
         }
     }
+
+    /// Whether this response represents a tool-level error.
+    ///
+    /// [`call_imp`] folds a [`ToolCallError`] into an `Ok(ToolCallResponse)`
+    /// (via [`ToolCallError::into_response`]) rather than an `Err`, so a
+    /// caller that dispatches through it -- e.g. [`crate::mcp::latest_tools::RunLatestTool`]
+    /// chaining several calls -- needs this to tell a failed step from a
+    /// successful one.
+    pub(crate) fn is_error(&self) -> bool {
+        self.is_error
+    }
 }
 
 /// Error response from a failed tool invocation.
@@ -616,28 +1259,41 @@ impl ToolCallError {
         ToolCallResponse {
             content: self.content,
             is_error: true,
+            structured_content: None,
         }
     }
+
+    /// This error's messages, for a caller (e.g.
+    /// [`crate::mcp::latest_tools::RunLatestTool`]) that wants to re-wrap
+    /// them with extra context -- which step of a chain produced them --
+    /// rather than just propagating them as-is.
+    pub(crate) fn content(&self) -> &[ToolContent] {
+        &self.content
+    }
 }
 
 /// Content returned by a tool.
 ///
-/// Currently supports text content, but marked as `non_exhaustive`
-/// to allow for future content types (e.g., images, structured data).
+/// Supports text and structured JSON content, but marked as
+/// `non_exhaustive` to allow for future content types (e.g., images).
 ///
 /// # Examples
 ///
 /// ```
 /// use exfiltrate::mcp::tools::ToolContent;
+/// use serde_json::json;
 ///
 /// let text_content = ToolContent::from("Hello, world!");
 /// let string_content = ToolContent::from(String::from("Dynamic content"));
+/// let json_content = ToolContent::from(json!({"result": 42}));
 /// ```
 #[derive(Debug)]
 #[non_exhaustive]
 pub enum ToolContent {
     /// Text content
     Text(String),
+    /// Structured JSON content
+    Json(serde_json::Value),
 }
 impl ToolContent {
     /// Returns the content as a string slice if it's text content.
@@ -645,11 +1301,12 @@ impl ToolContent {
     /// # Returns
     ///
     /// * `Some(&str)` if the content is text
-    /// * `None` for other content types (when added in the future)
+    /// * `None` for other content types
     #[cfg(feature="transit")]
     pub(crate) fn as_str(&self) -> Option<&str> {
         match self {
             ToolContent::Text(text) => Some(text),
+            ToolContent::Json(_) => None,
         }
     }
 }
@@ -667,6 +1324,13 @@ impl Serialize for ToolContent {
                 s.serialize_field("text", text)?;
                 s.end()
             }
+            ToolContent::Json(json) => {
+                let mut s = serializer.serialize_struct("ToolContent", 2)?;
+
+                s.serialize_field("type", "json")?;
+                s.serialize_field("json", json)?;
+                s.end()
+            }
         }
     }
 }
@@ -692,6 +1356,7 @@ impl<'de> Deserialize<'de> for ToolContent {
             {
                 let mut content_type: Option<String> = None;
                 let mut text: Option<String> = None;
+                let mut json: Option<serde_json::Value> = None;
 
                 while let Some(key) = map.next_key::<String>()? {
                     match key.as_str() {
@@ -707,6 +1372,12 @@ impl<'de> Deserialize<'de> for ToolContent {
                             }
                             text = Some(map.next_value()?);
                         }
+                        "json" => {
+                            if json.is_some() {
+                                return Err(de::Error::duplicate_field("json"));
+                            }
+                            json = Some(map.next_value()?);
+                        }
                         _ => {
                             let _: de::IgnoredAny = map.next_value()?;
                         }
@@ -718,7 +1389,11 @@ impl<'de> Deserialize<'de> for ToolContent {
                         let text = text.ok_or_else(|| de::Error::missing_field("text"))?;
                         Ok(ToolContent::Text(text))
                     }
-                    Some(other) => Err(de::Error::unknown_variant(other, &["text"])),
+                    Some("json") => {
+                        let json = json.ok_or_else(|| de::Error::missing_field("json"))?;
+                        Ok(ToolContent::Json(json))
+                    }
+                    Some(other) => Err(de::Error::unknown_variant(other, &["text", "json"])),
                     None => Err(de::Error::missing_field("type")),
                 }
             }
@@ -740,26 +1415,68 @@ impl From<&str> for ToolContent {
     }
 }
 
+impl From<serde_json::Value> for ToolContent {
+    fn from(value: serde_json::Value) -> Self {
+        ToolContent::Json(value)
+    }
+}
+
 /// Internal implementation for calling a tool.
 ///
 /// Looks up the tool by name and invokes it with the provided arguments.
-/// Searches both [`TOOLS`] and [`SHARED_TOOLS`] collections.
-pub(crate) fn call_imp(params: ToolCallParams) -> Result<ToolCallResponse, crate::jrpc::Error> {
+/// Searches both [`TOOLS`] and [`SHARED_TOOLS`] collections. Arguments are
+/// validated against the tool's [`InputSchema`] first, so a call with a
+/// missing required field or a wrongly-typed value fails fast with one
+/// message per offending field instead of reaching [`Tool::call`].
+///
+/// `reporter` is forwarded to the matched tool's
+/// [`Tool::call_streaming`]; pass a reporter built from `None` (see
+/// [`crate::mcp::progress::ProgressReporter::new`]) for callers with no
+/// progress token to report against.
+pub(crate) fn call_imp(
+    params: ToolCallParams,
+    reporter: &crate::mcp::progress::ProgressReporter,
+) -> Result<ToolCallResponse, crate::jrpc::Error> {
     let tools = TOOLS.read().unwrap();
-    let tool = tools
+    let schema = tools
         .iter()
         .chain(SHARED_TOOLS.iter())
         .find(|t| t.name() == params.name)
-        .map(|t| t.as_ref());
-    match tool {
-        Some(tool) => {
-            let call = tool.call(params.arguments);
-            match call {
-                Ok(response) => Ok(response),
-                Err(err) => Ok(err.into_response()),
-            }
+        .map(|t| t.input_schema());
+    drop(tools);
+    // Not an in-process tool: fall back to the registered providers (see
+    // `crate::mcp::providers`) before giving up, building the provider's
+    // `Tool` once so both the schema check and the call below use the same
+    // instance.
+    let provider_tool = if schema.is_none() {
+        crate::mcp::providers::find_tool(&params.name)
+    } else {
+        None
+    };
+    let schema = schema.or_else(|| provider_tool.as_ref().map(|t| t.input_schema()));
+    let Some(schema) = schema else {
+        return Err(Error::unknown_tool(params.name));
+    };
+    if let Err(errors) = schema.validate(&params.arguments) {
+        let content = errors.into_iter().map(ToolContent::from).collect();
+        return Ok(ToolCallError::new(content).into_response());
+    }
+    let call = crate::mcp::middleware::run_chain(&params.name, params.arguments, |name, arguments| {
+        if let Some(tool) = &provider_tool {
+            return tool.call_streaming(arguments.clone(), reporter);
         }
-        None => Err(Error::unknown_tool(params.name)),
+        let tools = TOOLS.read().unwrap();
+        let tool = tools
+            .iter()
+            .chain(SHARED_TOOLS.iter())
+            .find(|t| t.name() == name)
+            .map(|t| t.as_ref())
+            .expect("tool presence already checked by call_imp");
+        tool.call_streaming(arguments.clone(), reporter)
+    });
+    match call {
+        Ok(response) => Ok(response),
+        Err(err) => Ok(err.into_response()),
     }
 }
 
@@ -775,11 +1492,18 @@ pub(crate) fn call_imp(params: ToolCallParams) -> Result<ToolCallResponse, crate
 ///
 /// A response containing either the tool's output or an error
 pub(crate) fn call(request: Request) -> Response<ToolCallResponse> {
-    let params = match request.params {
-        Some(params) => match serde_json::from_value::<ToolCallParams>(params) {
-            Ok(params) => params,
-            Err(err) => return Response::err(Error::invalid_params(err.to_string()), request.id),
-        },
+    let (params, reporter) = match request.params {
+        Some(params) => {
+            let reporter = crate::mcp::progress::ProgressReporter::new(
+                crate::mcp::progress::token_from_params(&params),
+            );
+            match ToolCallParams::parse_lenient(params) {
+                Ok(params) => (params, reporter),
+                Err(err) => {
+                    return Response::err(Error::invalid_params(err.to_string()), request.id);
+                }
+            }
+        }
         None => {
             return Response::err(
                 Error::invalid_params("No parameters provided".to_string()),
@@ -787,7 +1511,7 @@ pub(crate) fn call(request: Request) -> Response<ToolCallResponse> {
             );
         }
     };
-    let r = call_imp(params);
+    let r = call_imp(params, &reporter);
     match r {
         Ok(r) => Response::new(r, request.id),
         Err(e) => Response::err(e, request.id),
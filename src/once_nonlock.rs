@@ -361,6 +361,64 @@ const ONCE_INITIAL: u8 = 0;
 const ONCE_IN_PROGRESS: u8 = 1;
 const ONCE_DONE: u8 = 2;
 
+/// The busy-wait strategy used by [`OnceNonLock::get_or_init`] and
+/// [`OnceNonLock::wait`] while another thread's initialization is in
+/// progress.
+///
+/// This mirrors `spin::Once<T, R>`'s `RelaxStrategy` so `no_std`/bare-metal
+/// callers can swap in something other than a raw spin loop (e.g. a strategy
+/// that yields to an RTOS scheduler) without `OnceNonLock` depending on
+/// `std::thread::yield_now`.
+pub trait RelaxStrategy {
+    /// Called once per iteration of the wait loop.
+    fn relax();
+}
+
+/// The default [`RelaxStrategy`]: a pure busy-wait via `core::hint::spin_loop()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Spin;
+
+impl RelaxStrategy for Spin {
+    fn relax() {
+        core::hint::spin_loop();
+    }
+}
+
+/// Resets `once` back to `ONCE_INITIAL` on drop, unless [`Self::disarm`] was
+/// called first.
+///
+/// Without this, a panicking initializer closure would leave `self.once`
+/// stuck at `ONCE_IN_PROGRESS` forever: every future `get`/`try_get_or_init`
+/// would return `None`, and `Drop` would `panic!("Dropping while still in
+/// progress")` (see the module's `# Internal States` docs). Arming this
+/// guard before calling `f()` and disarming it right after `f()` returns
+/// normally means an unwind out of `f()` restores `ONCE_INITIAL` so another
+/// caller can retry, while the panic itself propagates unhindered.
+struct ResetOnPanic<'a> {
+    once: &'a AtomicU8,
+    armed: bool,
+}
+
+impl<'a> ResetOnPanic<'a> {
+    fn new(once: &'a AtomicU8) -> Self {
+        ResetOnPanic { once, armed: true }
+    }
+
+    /// Disarms the guard so its `Drop` impl becomes a no-op; call this once
+    /// `f()` has returned without unwinding.
+    fn disarm(mut self) {
+        self.armed = false;
+    }
+}
+
+impl Drop for ResetOnPanic<'_> {
+    fn drop(&mut self) {
+        if self.armed {
+            self.once.store(ONCE_INITIAL, Ordering::Release);
+        }
+    }
+}
+
 /// A non-blocking, thread-safe cell that can be written to only once.
 ///
 /// Unlike `std::sync::OnceLock`, `OnceNonLock` never blocks threads. When initialization
@@ -371,22 +429,25 @@ const ONCE_DONE: u8 = 2;
 ///
 /// - `T`: The type of value stored in the cell. Must be `Send` to share between threads
 ///   and `Sync` for concurrent access.
+/// - `R`: The [`RelaxStrategy`] used by the blocking [`Self::get_or_init`]/[`Self::wait`]
+///   while another thread's initialization is in progress. Defaults to [`Spin`].
 ///
 /// # Memory Management
 ///
 /// The implementation uses `ManuallyDrop` to ensure proper cleanup of the stored value
 /// during drop, preventing double-free issues while maintaining safe memory management.
-#[derive(Debug)]
-pub struct OnceNonLock<T> {
+pub struct OnceNonLock<T, R = Spin> {
     /// Atomic state tracker using the ONCE_* constants to coordinate initialization
     once: AtomicU8, //the ONCE constants
     /// The actual storage for the optional value, wrapped for interior mutability
     value: UnsafeCell<ManuallyDrop<Option<T>>>,
     //explain to Rust we will be dropping this manually
     _marker: std::marker::PhantomData<T>,
+    /// Which [`RelaxStrategy`] `get_or_init`/`wait` busy-wait with; zero-sized, never actually stored
+    _relax: std::marker::PhantomData<R>,
 }
 
-impl<T> OnceNonLock<T> {
+impl<T, R> OnceNonLock<T, R> {
     /// Creates a new, uninitialized `OnceNonLock`.
     ///
     /// The cell starts in the `INITIAL` state and can be initialized later using
@@ -432,6 +493,7 @@ impl<T> OnceNonLock<T> {
             once: AtomicU8::new(ONCE_INITIAL),
             value: UnsafeCell::new(ManuallyDrop::new(None)),
             _marker: std::marker::PhantomData,
+            _relax: std::marker::PhantomData,
         }
     }
 
@@ -513,8 +575,12 @@ impl<T> OnceNonLock<T> {
             Ordering::Relaxed,
         ) {
             Ok(_) => {
-                // We are the first to call get_or_init, so we initialize the value
+                // We are the first to call get_or_init, so we initialize the value.
+                // Armed so a panic inside f() resets to ONCE_INITIAL instead of
+                // leaving the cell stuck at ONCE_IN_PROGRESS forever.
+                let guard = ResetOnPanic::new(&self.once);
                 let value = f();
+                guard.disarm();
                 unsafe {
                     if let Some(value) = value {
                         // SAFETY: We have exclusive access to the value
@@ -731,8 +797,454 @@ impl<T> OnceNonLock<T> {
             _ => panic!("OnceNonLock: Invalid state on get"),
         }
     }
+
+    /// Returns `true` if the value has been initialized.
+    ///
+    /// `ONCE_IN_PROGRESS` counts as not-yet-initialized here, the same as
+    /// [`Self::get`] treats it -- this lets callers cheaply check completion
+    /// without racing through `get()` for a value they're going to discard.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // ALLOW_IGNORE_DOCTEST: once_nonlock is a private module; see the
+    /// // other doctests in this file for the inline-redefinition pattern
+    /// // this example would otherwise need to repeat.
+    /// let once = OnceNonLock::new();
+    /// assert!(!once.is_initialized());
+    ///
+    /// once.set(42).unwrap();
+    /// assert!(once.is_initialized());
+    /// ```
+    pub fn is_initialized(&self) -> bool {
+        self.once.load(Ordering::Acquire) == ONCE_DONE
+    }
+
+    /// Takes the value out, resetting the cell to `INITIAL` so a later
+    /// `try_get_or_init` can replace it.
+    ///
+    /// This is how callers that need to tear down and replace an
+    /// initialized connection (see `InternalProxy::disconnect`) get rid of
+    /// the old value -- `OnceNonLock` otherwise only ever moves forward,
+    /// `INITIAL` -> `DONE`.
+    ///
+    /// Note this takes `&self`, not `&mut self`: `InternalProxy::disconnect`
+    /// only ever holds this cell behind an `Arc`, so a `&mut self` overload
+    /// (which Rust wouldn't let coexist with this one under the same name
+    /// anyway) isn't useful here the way [`Self::get_mut`]/[`Self::into_inner`]
+    /// are for owned cells.
+    ///
+    /// # Returns
+    ///
+    /// - `Some(T)` if the cell was `DONE`, now reset to `INITIAL`
+    /// - `None` if the cell was `INITIAL` already, or another thread is
+    ///   concurrently initializing or taking it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # mod once_nonlock {
+    /// #     use std::cell::UnsafeCell;
+    /// #     use std::mem::ManuallyDrop;
+    /// #     use std::sync::atomic::{AtomicU8, Ordering};
+    /// #
+    /// #     const ONCE_INITIAL: u8 = 0;
+    /// #     const ONCE_IN_PROGRESS: u8 = 1;
+    /// #     const ONCE_DONE: u8 = 2;
+    /// #
+    /// #     pub struct OnceNonLock<T> {
+    /// #         once: AtomicU8,
+    /// #         value: UnsafeCell<ManuallyDrop<Option<T>>>,
+    /// #         _marker: std::marker::PhantomData<T>,
+    /// #     }
+    /// #
+    /// #     impl<T> OnceNonLock<T> {
+    /// #         pub const fn new() -> Self {
+    /// #             OnceNonLock {
+    /// #                 once: AtomicU8::new(ONCE_INITIAL),
+    /// #                 value: UnsafeCell::new(ManuallyDrop::new(None)),
+    /// #                 _marker: std::marker::PhantomData,
+    /// #             }
+    /// #         }
+    /// #
+    /// #         pub fn try_get_or_init<F>(&self, f: F) -> Option<&T>
+    /// #         where
+    /// #             F: FnOnce() -> Option<T>,
+    /// #         {
+    /// #             match self.once.compare_exchange(ONCE_INITIAL, ONCE_IN_PROGRESS, Ordering::AcqRel, Ordering::Relaxed) {
+    /// #                 Ok(_) => {
+    /// #                     let value = f();
+    /// #                     unsafe {
+    /// #                         if let Some(value) = value {
+    /// #                             *self.value.get() = ManuallyDrop::new(Some(value));
+    /// #                             self.once.store(ONCE_DONE, Ordering::Release);
+    /// #                         } else {
+    /// #                             self.once.store(ONCE_INITIAL, Ordering::Release);
+    /// #                         }
+    /// #                     }
+    /// #                     unsafe {
+    /// #                         let f = self.value.get();
+    /// #                         let value = &*f;
+    /// #                         value.as_ref()
+    /// #                     }
+    /// #                 }
+    /// #                 Err(ONCE_IN_PROGRESS) => None,
+    /// #                 Err(ONCE_DONE) => unsafe {
+    /// #                     let f = self.value.get();
+    /// #                     let value = &*f;
+    /// #                     value.as_ref()
+    /// #                 },
+    /// #                 Err(_) => panic!("Invalid state"),
+    /// #             }
+    /// #         }
+    /// #
+    /// #         pub fn take(&self) -> Option<T> {
+    /// #             match self.once.compare_exchange(ONCE_DONE, ONCE_IN_PROGRESS, Ordering::AcqRel, Ordering::Relaxed) {
+    /// #                 Ok(_) => {
+    /// #                     let taken = unsafe { ManuallyDrop::take(&mut *self.value.get()) };
+    /// #                     self.once.store(ONCE_INITIAL, Ordering::Release);
+    /// #                     taken
+    /// #                 }
+    /// #                 Err(_) => None,
+    /// #             }
+    /// #         }
+    /// #     }
+    /// #
+    /// #     unsafe impl<T: Send> Send for OnceNonLock<T> {}
+    /// #     unsafe impl<T: Sync> Sync for OnceNonLock<T> {}
+    /// # }
+    /// # use once_nonlock::OnceNonLock;
+    ///
+    /// let once = OnceNonLock::new();
+    /// once.try_get_or_init(|| Some(42));
+    ///
+    /// assert_eq!(once.take(), Some(42));
+    /// assert_eq!(once.get(), None);
+    ///
+    /// // Now it can be re-initialized
+    /// once.try_get_or_init(|| Some(43));
+    /// assert_eq!(once.get(), Some(&43));
+    /// ```
+    pub fn take(&self) -> Option<T> {
+        match self.once.compare_exchange(
+            ONCE_DONE,
+            ONCE_IN_PROGRESS,
+            Ordering::AcqRel,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => {
+                // SAFETY: we hold IN_PROGRESS, so we have exclusive access
+                // Leave `None` behind, as `new` does, so a later failed
+                // `try_get_or_init` can't hand out the moved-out value.
+                let taken = unsafe {
+                    ManuallyDrop::into_inner(std::mem::replace(
+                        &mut *self.value.get(),
+                        ManuallyDrop::new(None),
+                    ))
+                };
+                self.once.store(ONCE_INITIAL, Ordering::Release);
+                taken
+            }
+            Err(_) => None, // already INITIAL, or another thread is mid-operation
+        }
+    }
+
+    /// Attempts to set the cell's value, without running an initializer
+    /// closure.
+    ///
+    /// Attempts the `INITIAL -> IN_PROGRESS -> DONE` transition directly; if
+    /// the cell was already (or concurrently) initialized, hands `value`
+    /// back in `Err` instead of discarding it -- matching
+    /// `std::sync::OnceLock::set`.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // ALLOW_IGNORE_DOCTEST: once_nonlock is a private module; see the
+    /// // other doctests in this file for the inline-redefinition pattern
+    /// // this example would otherwise need to repeat.
+    /// let once = OnceNonLock::new();
+    /// assert_eq!(once.set(42), Ok(()));
+    /// assert_eq!(once.set(43), Err(43));
+    /// assert_eq!(once.get(), Some(&42));
+    /// ```
+    pub fn set(&self, value: T) -> Result<(), T> {
+        match self.once.compare_exchange(
+            ONCE_INITIAL,
+            ONCE_IN_PROGRESS,
+            Ordering::AcqRel,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => {
+                unsafe {
+                    // SAFETY: We hold IN_PROGRESS, so we have exclusive access
+                    *self.value.get() = ManuallyDrop::new(Some(value));
+                }
+                self.once.store(ONCE_DONE, Ordering::Release);
+                Ok(())
+            }
+            Err(_) => Err(value), // already DONE, or another thread is mid-operation
+        }
+    }
+
+    /// Returns a mutable reference to the value, if initialized.
+    ///
+    /// Takes `&mut self`, so no other thread can be concurrently reading or
+    /// writing the cell; this lets the state check use a plain `Relaxed`
+    /// load instead of the CAS/`Acquire` dance [`Self::get`] needs.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // ALLOW_IGNORE_DOCTEST: once_nonlock is a private module; see the
+    /// // other doctests in this file for the inline-redefinition pattern
+    /// // this example would otherwise need to repeat.
+    /// let mut once = OnceNonLock::new();
+    /// assert_eq!(once.get_mut(), None);
+    ///
+    /// once.set(42).unwrap();
+    /// *once.get_mut().unwrap() += 1;
+    /// assert_eq!(once.get(), Some(&43));
+    /// ```
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        match *self.once.get_mut() {
+            ONCE_DONE => unsafe {
+                // SAFETY: &mut self guarantees exclusive access
+                (&mut *self.value.get()).as_mut()
+            },
+            _ => None,
+        }
+    }
+
+    /// Consumes the cell, returning the value if it was initialized.
+    ///
+    /// Takes `self` by value, so -- like [`Self::get_mut`] -- the state
+    /// check is a plain `Relaxed` load. The cell's own `Drop` impl is
+    /// suppressed (via `ManuallyDrop`) so the value is moved out rather
+    /// than dropped twice.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // ALLOW_IGNORE_DOCTEST: once_nonlock is a private module; see the
+    /// // other doctests in this file for the inline-redefinition pattern
+    /// // this example would otherwise need to repeat.
+    /// let once = OnceNonLock::new();
+    /// assert_eq!(once.into_inner(), None);
+    ///
+    /// let once = OnceNonLock::new();
+    /// once.set(42).unwrap();
+    /// assert_eq!(once.into_inner(), Some(42));
+    /// ```
+    pub fn into_inner(self) -> Option<T> {
+        let this = ManuallyDrop::new(self);
+        match this.once.load(Ordering::Relaxed) {
+            ONCE_DONE => unsafe {
+                // SAFETY: `self` was consumed by value, so no other reference
+                // to it exists; reading out the value and skipping `Drop`
+                // (via the outer `ManuallyDrop`) avoids a double-drop.
+                ManuallyDrop::into_inner(std::ptr::read(this.value.get()))
+            },
+            _ => None,
+        }
+    }
+}
+
+impl<T, R: RelaxStrategy> OnceNonLock<T, R> {
+    /// Initializes the value if it hasn't been already, blocking the calling
+    /// thread if another thread is currently initializing it.
+    ///
+    /// Unlike [`Self::try_get_or_init`], this never returns without a value:
+    /// if `compare_exchange` loses the race and observes `IN_PROGRESS`, this
+    /// busy-waits (via `R::relax()` on each iteration) until the other
+    /// thread's initialization completes, then returns the result --
+    /// matching the contract of `spin::Once::call_once` and
+    /// `std::sync::OnceLock::get_or_init`. Exactly one `f` ever runs, enforced
+    /// by the same `INITIAL -> IN_PROGRESS` CAS `try_get_or_init` uses.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // ALLOW_IGNORE_DOCTEST: once_nonlock is a private module; see the
+    /// // other doctests in this file for the inline-redefinition pattern
+    /// // this example would otherwise need to repeat.
+    /// let once = OnceNonLock::new();
+    /// let value = once.get_or_init(|| 42);
+    /// assert_eq!(*value, 42);
+    ///
+    /// // Already initialized, so the closure isn't called again.
+    /// let value = once.get_or_init(|| panic!("not called"));
+    /// assert_eq!(*value, 42);
+    /// ```
+    pub fn get_or_init<F>(&self, f: F) -> &T
+    where
+        F: FnOnce() -> T,
+    {
+        match self.once.compare_exchange(
+            ONCE_INITIAL,
+            ONCE_IN_PROGRESS,
+            Ordering::AcqRel,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => {
+                // We are the first to call get_or_init, so we initialize the value.
+                // Armed so a panic inside f() resets to ONCE_INITIAL instead of
+                // leaving the cell stuck at ONCE_IN_PROGRESS forever.
+                let guard = ResetOnPanic::new(&self.once);
+                let value = f();
+                guard.disarm();
+                unsafe {
+                    // SAFETY: We have exclusive access to the value
+                    *self.value.get() = ManuallyDrop::new(Some(value));
+                }
+                self.once.store(ONCE_DONE, Ordering::Release);
+            }
+            Err(ONCE_DONE) => {
+                // Already initialized, nothing to do
+            }
+            Err(ONCE_IN_PROGRESS) => {
+                // Another thread is initializing; block until it's done
+                self.spin_until_done();
+            }
+            Err(other) => {
+                panic!("OnceNonLock: get_or_init with value {:?}", other);
+            }
+        }
+        unsafe {
+            // SAFETY: self.once == ONCE_DONE at this point, so the value is initialized
+            let f = self.value.get();
+            (&*f)
+                .as_ref()
+                .expect("OnceNonLock: value missing despite ONCE_DONE")
+        }
+    }
+
+    /// Blocks the calling thread until the value is initialized (by any
+    /// thread), then returns a reference to it.
+    ///
+    /// Unlike [`Self::get_or_init`], this never runs an initializer itself --
+    /// if the cell is still `INITIAL`, this spins (via `R::relax()`) waiting
+    /// for some other call to `get_or_init`/`try_get_or_init` to start and
+    /// finish one.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // ALLOW_IGNORE_DOCTEST: once_nonlock is a private module; see the
+    /// // other doctests in this file for the inline-redefinition pattern
+    /// // this example would otherwise need to repeat.
+    /// let once = Arc::new(OnceNonLock::new());
+    /// let once_clone = once.clone();
+    /// let handle = thread::spawn(move || {
+    ///     once_clone.get_or_init(|| 42);
+    /// });
+    ///
+    /// // Blocks until the spawned thread finishes initializing.
+    /// assert_eq!(*once.wait(), 42);
+    /// handle.join().unwrap();
+    /// ```
+    pub fn wait(&self) -> &T {
+        self.spin_until_done();
+        unsafe {
+            // SAFETY: self.once == ONCE_DONE at this point, so the value is initialized
+            let f = self.value.get();
+            (&*f)
+                .as_ref()
+                .expect("OnceNonLock: value missing despite ONCE_DONE")
+        }
+    }
+
+    /// Initializes the value from a fallible closure, returning the failure
+    /// instead of swallowing it the way [`Self::try_get_or_init`] does.
+    ///
+    /// Mirrors `once_cell`'s `OnceCell::get_or_try_init`: on winning the
+    /// `INITIAL -> IN_PROGRESS` CAS, runs `f`. `Ok(v)` stores `v` and
+    /// publishes `DONE`; `Err(e)` restores `INITIAL` so a later caller can
+    /// retry, and returns `Err(e)` unchanged. A caller that loses the CAS and
+    /// observes `DONE` returns `Ok(&value)`; one that observes `IN_PROGRESS`
+    /// blocks via `R::relax()` (the same as [`Self::get_or_init`]) rather
+    /// than returning a spurious error.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // ALLOW_IGNORE_DOCTEST: once_nonlock is a private module; see the
+    /// // other doctests in this file for the inline-redefinition pattern
+    /// // this example would otherwise need to repeat.
+    /// let once = OnceNonLock::new();
+    ///
+    /// let result: Result<&i32, &str> = once.get_or_try_init(|| Err("boom"));
+    /// assert_eq!(result, Err("boom"));
+    ///
+    /// // Failure didn't stick: INITIAL, so a later call can retry.
+    /// let result = once.get_or_try_init(|| Ok::<_, &str>(42));
+    /// assert_eq!(result, Ok(&42));
+    /// ```
+    pub fn get_or_try_init<F, E>(&self, f: F) -> Result<&T, E>
+    where
+        F: FnOnce() -> Result<T, E>,
+    {
+        match self.once.compare_exchange(
+            ONCE_INITIAL,
+            ONCE_IN_PROGRESS,
+            Ordering::AcqRel,
+            Ordering::Relaxed,
+        ) {
+            Ok(_) => {
+                // Armed so a panic inside f() resets to ONCE_INITIAL instead of
+                // leaving the cell stuck at ONCE_IN_PROGRESS forever.
+                let guard = ResetOnPanic::new(&self.once);
+                let result = f();
+                guard.disarm();
+                match result {
+                    Ok(value) => {
+                        unsafe {
+                            // SAFETY: We have exclusive access to the value
+                            *self.value.get() = ManuallyDrop::new(Some(value));
+                        }
+                        self.once.store(ONCE_DONE, Ordering::Release);
+                    }
+                    Err(e) => {
+                        // go back to initial state so a later caller can retry
+                        self.once.store(ONCE_INITIAL, Ordering::Release);
+                        return Err(e);
+                    }
+                }
+            }
+            Err(ONCE_DONE) => {
+                // Already initialized, nothing to do
+            }
+            Err(ONCE_IN_PROGRESS) => {
+                // Another thread is initializing; block until it's done
+                self.spin_until_done();
+            }
+            Err(other) => {
+                panic!("OnceNonLock: get_or_try_init with value {:?}", other);
+            }
+        }
+        unsafe {
+            // SAFETY: self.once == ONCE_DONE at this point, so the value is initialized
+            let f = self.value.get();
+            Ok((&*f)
+                .as_ref()
+                .expect("OnceNonLock: value missing despite ONCE_DONE"))
+        }
+    }
+
+    /// Busy-waits, relaxing via `R::relax()` each iteration, until `self.once`
+    /// reaches `ONCE_DONE`.
+    fn spin_until_done(&self) {
+        loop {
+            match self.once.load(Ordering::Acquire) {
+                ONCE_DONE => return,
+                ONCE_INITIAL | ONCE_IN_PROGRESS => R::relax(),
+                other => panic!("OnceNonLock: Invalid state {:?} while waiting", other),
+            }
+        }
+    }
 }
-impl<T> Drop for OnceNonLock<T> {
+
+impl<T, R> Drop for OnceNonLock<T, R> {
     /// Drops the `OnceNonLock` and its contained value if initialized.
     ///
     /// # Panics
@@ -765,11 +1277,68 @@ impl<T> Drop for OnceNonLock<T> {
 // SAFETY: OnceNonLock can be sent between threads if T can be sent.
 // The atomic state management ensures proper synchronization when the value
 // is transferred between threads.
-unsafe impl<T: Send> Send for OnceNonLock<T> {}
+unsafe impl<T: Send, R> Send for OnceNonLock<T, R> {}
 
 // SAFETY: OnceNonLock can be shared between threads if T can be shared.
 // The atomic operations and UnsafeCell usage ensure that:
 // - Only one thread can initialize the value (via compare_exchange)
 // - Once initialized, the value is immutable and can be safely shared
 // - Memory ordering (Acquire/Release) ensures proper visibility across threads
-unsafe impl<T: Sync> Sync for OnceNonLock<T> {}
+unsafe impl<T: Sync, R> Sync for OnceNonLock<T, R> {}
+
+impl<T: std::fmt::Debug, R> std::fmt::Debug for OnceNonLock<T, R> {
+    /// Prints `OnceNonLock { data: <value> }` when initialized, or
+    /// `OnceNonLock { <uninitialized> }` otherwise -- matching `spin::Once`'s
+    /// `Debug` output, so that format doesn't change underneath callers who
+    /// already depend on it.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.get() {
+            Some(data) => f.debug_struct("OnceNonLock").field("data", data).finish(),
+            None => write!(f, "OnceNonLock {{ <uninitialized> }}"),
+        }
+    }
+}
+
+impl<T, R> Default for OnceNonLock<T, R> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// SAFETY: matches std::sync::OnceLock's UnwindSafe impls -- a panic during
+// initialization can't leave T half-written (the value is only ever stored
+// by a `ManuallyDrop::new(Some(value))` after `f()` has already returned
+// successfully; see `ResetOnPanic`), so an OnceNonLock is as unwind-safe as
+// the T/R it might end up holding.
+impl<T: std::panic::RefUnwindSafe, R: std::panic::RefUnwindSafe> std::panic::RefUnwindSafe
+    for OnceNonLock<T, R>
+{
+}
+impl<T: std::panic::UnwindSafe, R: std::panic::UnwindSafe> std::panic::UnwindSafe
+    for OnceNonLock<T, R>
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OnceNonLock;
+    use std::panic::{self, AssertUnwindSafe};
+
+    /// A panic inside the initializer must not poison the cell: the guard
+    /// from `ResetOnPanic` should restore `ONCE_INITIAL` on unwind, so a
+    /// later call can successfully initialize the same cell.
+    #[test]
+    fn panic_in_initializer_does_not_poison_cell() {
+        let once = OnceNonLock::new();
+
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            once.try_get_or_init(|| -> Option<i32> { panic!("boom") })
+        }));
+        assert!(result.is_err());
+
+        // The cell is still usable: it wasn't left stuck at ONCE_IN_PROGRESS.
+        let value = once.try_get_or_init(|| Some(42));
+        assert_eq!(value, Some(&42));
+        assert_eq!(once.get(), Some(&42));
+    }
+}
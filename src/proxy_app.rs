@@ -27,8 +27,11 @@
 //! cargo run --bin proxy --features transit
 //! ```
 //!
-//! The proxy will start an HTTP server on port 1984 by default. To use stdio
-//! mode instead, uncomment the stdio line and comment out the HTTP line.
+//! The proxy will start an HTTP server on port 1984 by default. Pass
+//! `--stdio` to communicate over stdin/stdout instead:
+//! ```bash
+//! cargo run --bin proxy --features transit -- --stdio
+//! ```
 //!
 //! # Examples
 //!
@@ -144,31 +147,70 @@
 
 #![cfg(feature="transit")]
 
+use exfiltrate::transit::child::{ChildTarget, ChildTargetConfig};
 use exfiltrate::transit::transit_proxy::TransitProxy;
 
 /// Main entry point for the exfiltrate proxy server.
 ///
-/// Creates a transit proxy and starts an HTTP server listening on port 1984.
-/// The server runs on background threads, so the main thread is parked to
-/// keep the application alive.
+/// Creates a transit proxy and starts either the HTTP/SSE/WebSocket server
+/// (listening on 127.0.0.1:1984) or the stdio server, depending on whether
+/// `--stdio` was passed. Either way the server runs on background threads,
+/// so the main thread is parked to keep the application alive.
 ///
 /// # Configuration Options
 ///
-/// The function includes commented code for alternative transport modes:
-/// - HTTP server mode (default): Listens on 127.0.0.1:1984
-/// - Stdio mode: Communicates via standard input/output
+/// - Default: HTTP server mode, listening on 127.0.0.1:1984
+/// - `--stdio`: communicate via standard input/output instead, for MCP
+///   hosts that launch the proxy as a subprocess and speak the protocol
+///   over its pipes rather than a socket
 ///
-/// To switch modes, comment/uncomment the appropriate lines.
+/// Pass `--launch COMMAND [ARGS...]` (optionally followed by
+/// `--restart-on-exit`) to have the proxy launch the target application
+/// itself instead of waiting for it to connect; see
+/// [`exfiltrate::transit::child`].
 ///
 /// # Panics
 ///
 /// The application will panic if:
-/// - The specified port is already in use
+/// - The specified port is already in use (HTTP mode)
 /// - The server fails to start
-/// - Network permissions are insufficient
+/// - Network permissions are insufficient (HTTP mode)
+/// - `--launch` was passed and the target program fails to start
 fn main() {
     let transit_proxy = TransitProxy::new();
-    let _proxy = exfiltrate::transit::http::Server::new("127.0.0.1:1984", transit_proxy);
-    // let _proxy = exfiltrate::transit::stdio::Server::new(transit_proxy);
-    std::thread::park();
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let stdio = match args.iter().position(|a| a == "--stdio") {
+        Some(pos) => {
+            args.remove(pos);
+            true
+        }
+        None => false,
+    };
+    let _child_target = parse_child_target_args(&args)
+        .map(|config| ChildTarget::spawn(config).expect("failed to launch target process"));
+    if stdio {
+        let _proxy = exfiltrate::transit::stdio::Server::new(transit_proxy);
+        std::thread::park();
+    } else {
+        let _proxy = exfiltrate::transit::http::Server::new("127.0.0.1:1984", transit_proxy);
+        std::thread::park();
+    }
+}
+
+/// Parses `--launch COMMAND [ARGS...] [--restart-on-exit]` out of the
+/// proxy's own command-line arguments, if present. Everything after
+/// `COMMAND` is forwarded to it verbatim, except the first
+/// `--restart-on-exit` after `--launch`, wherever it appears.
+fn parse_child_target_args(args: &[String]) -> Option<ChildTargetConfig> {
+    let launch_pos = args.iter().position(|a| a == "--launch")?;
+    let mut rest = args[launch_pos + 1..].to_vec();
+    let restart_on_exit = match rest.iter().position(|a| a == "--restart-on-exit") {
+        Some(pos) => {
+            rest.remove(pos);
+            true
+        }
+        None => false,
+    };
+    let command = rest.remove(0);
+    Some(ChildTargetConfig::new(command, rest).with_restart_on_exit(restart_on_exit))
 }
\ No newline at end of file
@@ -120,6 +120,7 @@ pub use web_time as time;
 /// - `JoinHandle` - Handle for joining spawned threads
 /// - `ThreadId` - Unique thread identifier
 /// - `current` - Get current thread information
+/// - [`thread::capabilities`] - probe whether real parallelism is actually available
 ///
 /// # Examples
 ///
@@ -159,13 +160,118 @@ pub use web_time as time;
 /// - Maximum thread count may be limited by browser
 /// - Shared memory requires specific CORS headers
 ///
+/// `wasm_thread::spawn` silently *requires* `SharedArrayBuffer` plus
+/// cross-origin-isolation (COOP/COEP) headers on the hosting page; without
+/// them it either traps or never makes progress. Call
+/// [`thread::capabilities`] before relying on `spawn` in code that might run
+/// embedded in an arbitrary, not-necessarily-isolated page, and fall back to
+/// running cooperatively on the current thread when it reports
+/// [`thread::ThreadMode::Cooperative`].
+///
 /// # Design Rationale
 ///
 /// This abstraction supports the project's "threads for everyone" philosophy,
 /// enabling consistent multi-threaded programming across all platforms without
 /// requiring async/await or runtime dependencies like tokio.
-#[cfg(target_arch = "wasm32")]
-pub use wasm_thread as thread;
+pub mod thread {
+    #[cfg(target_arch = "wasm32")]
+    pub use wasm_thread::*;
 
-#[cfg(not(target_arch = "wasm32"))]
-pub use std::thread;
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use std::thread::*;
+
+    /// Whether real parallelism (OS threads, or Web Workers backed by
+    /// `SharedArrayBuffer`) is actually available, and the mode the caller
+    /// should therefore run in.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct ThreadCapabilities {
+        /// `true` if the page is cross-origin isolated (`self.crossOriginIsolated`).
+        /// Always `true` on native platforms, where the concept doesn't apply.
+        pub cross_origin_isolated: bool,
+        /// `true` if `SharedArrayBuffer` is defined in the global scope.
+        /// Always `true` on native platforms, where the concept doesn't apply.
+        pub shared_memory_available: bool,
+    }
+
+    impl ThreadCapabilities {
+        /// The mode callers should run in given these capabilities.
+        pub fn mode(&self) -> ThreadMode {
+            if self.cross_origin_isolated && self.shared_memory_available {
+                ThreadMode::MultiThreaded
+            } else {
+                ThreadMode::Cooperative
+            }
+        }
+    }
+
+    /// How a caller should schedule work given the current [`ThreadCapabilities`].
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum ThreadMode {
+        /// Real OS threads (native) or cross-origin-isolated Web Workers
+        /// (WASM): `thread::spawn` can be used freely.
+        MultiThreaded,
+        /// `thread::spawn` would trap or hang (WASM without
+        /// `SharedArrayBuffer`/cross-origin isolation); the caller must run
+        /// cooperatively on the current thread instead of spawning.
+        Cooperative,
+    }
+
+    /// Probes whether real parallelism is available on this platform/page.
+    ///
+    /// Native platforms always report [`ThreadMode::MultiThreaded`]. On
+    /// WebAssembly, checks `crossOriginIsolated` and `SharedArrayBuffer` in
+    /// the current global scope (`window` or worker), since `wasm_thread`
+    /// needs both to actually spawn a Web Worker backed by shared memory.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capabilities() -> ThreadCapabilities {
+        ThreadCapabilities {
+            cross_origin_isolated: true,
+            shared_memory_available: true,
+        }
+    }
+
+    /// See the native overload's documentation.
+    #[cfg(target_arch = "wasm32")]
+    pub fn capabilities() -> ThreadCapabilities {
+        let global = web_sys::js_sys::global();
+        let cross_origin_isolated =
+            web_sys::js_sys::Reflect::get(&global, &"crossOriginIsolated".into())
+                .ok()
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+        let shared_memory_available =
+            web_sys::js_sys::Reflect::get(&global, &"SharedArrayBuffer".into())
+                .map(|v| !v.is_undefined())
+                .unwrap_or(false);
+        ThreadCapabilities {
+            cross_origin_isolated,
+            shared_memory_available,
+        }
+    }
+}
+
+/// Platform-appropriate networking API.
+///
+/// - **Native platforms and `wasm32-wasip2`**: re-exports `std::net`, since
+///   WASI preview 2 wires TCP sockets through wasi-libc via `sys_common`,
+///   making a real listening socket possible there too.
+/// - **`wasm32-unknown-unknown` (browsers)**: no POSIX socket API exists at
+///   all, so this module has no `net` re-export on that target. Code that
+///   needs to run there instead accepts an already-established duplex
+///   transport directly (e.g. a WebSocket to a relay) rather than listening
+///   for connections.
+///
+/// # Examples
+///
+/// ```
+/// # mod sys {
+/// #     pub use std::net;
+/// # }
+/// use sys::net::{TcpListener, ToSocketAddrs};
+///
+/// fn bind<A: ToSocketAddrs>(addr: A) -> std::io::Result<TcpListener> {
+///     TcpListener::bind(addr)
+/// }
+/// ```
+#[cfg(any(not(target_arch = "wasm32"), target_os = "wasi"))]
+pub use std::net;
@@ -0,0 +1,108 @@
+//! Bridges the `tracing` ecosystem into exfiltrate's log capture.
+//!
+//! [`crate::logwise`] only sees log records from crates that call
+//! `logwise::info_sync!` and friends, which excludes the large population of
+//! crates that emit diagnostics through `tracing` instead. This module
+//! reformats `tracing` events into the exact text shape `logwise_grep`
+//! already documents and forwards them the same way
+//! [`crate::logwise::begin_capture`] forwards a logwise `LogRecord`, so
+//! existing `logwise_read`/`logwise_grep` tooling works unchanged against
+//! tracing-instrumented programs.
+
+use crate::internal_proxy::InternalProxy;
+use crate::jrpc::Notification;
+use crate::sys::time::Instant;
+use std::sync::LazyLock;
+use tracing::Subscriber;
+use tracing::field::{Field, Visit};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+
+/// Process-start reference point for the `[<elapsed>]` field of a formatted
+/// line, pulled through [`crate::sys::time`] so this also works in WASM.
+static START: LazyLock<Instant> = LazyLock::new(Instant::now);
+
+/// Renders a `tracing::Event`'s fields into a single string.
+///
+/// The `message` field (if present) is rendered bare, matching the primary
+/// text of a logwise record; any other fields are appended as `key=value`
+/// pairs.
+#[derive(Default)]
+struct FieldVisitor {
+    message: Option<String>,
+    fields: Vec<String>,
+}
+
+impl Visit for FieldVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.message = Some(format!("{:?}", value));
+        } else {
+            self.fields.push(format!("{}={:?}", field.name(), value));
+        }
+    }
+}
+
+impl FieldVisitor {
+    fn into_text(self) -> String {
+        let mut parts = Vec::new();
+        parts.extend(self.message);
+        parts.extend(self.fields);
+        parts.join(" ")
+    }
+}
+
+/// A [`tracing_subscriber::Layer`] that reformats every `tracing::Event`
+/// into the exact text shape `logwise_grep` documents —
+/// `<task_id> <LEVEL>: <file>:<line>:<col> [<elapsed>] <message>` — and
+/// forwards it as an `exfiltrate/logwise/record` notification, the same way
+/// [`crate::logwise::ForwardingLogger`] forwards a logwise `LogRecord`.
+///
+/// `tracing` has no direct equivalent of logwise's task ID, so the current
+/// span's ID is used instead (`0` if there is none); it likewise carries no
+/// column number in its metadata, so the column is always rendered as `0`.
+#[derive(Debug, Default)]
+pub struct ExfiltrateTracingLayer;
+
+impl<S: Subscriber> Layer<S> for ExfiltrateTracingLayer {
+    fn on_event(&self, event: &tracing::Event<'_>, ctx: Context<'_, S>) {
+        let mut visitor = FieldVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let task_id = ctx.current_span().id().map(|id| id.into_u64()).unwrap_or(0);
+        let line = format!(
+            "{} {}: {}:{}:{} [{:?}] {}",
+            task_id,
+            metadata.level(),
+            metadata.file().unwrap_or("<unknown>"),
+            metadata.line().unwrap_or(0),
+            0, // tracing's metadata has no column number
+            START.elapsed(),
+            visitor.into_text(),
+        );
+        let n = Notification::new("exfiltrate/logwise/record".to_string(), Some(line.into()));
+        let _ = InternalProxy::current().buffer_notification(n);
+    }
+}
+
+/// Begins capturing `tracing` events for forwarding through exfiltrate.
+///
+/// Installs an [`ExfiltrateTracingLayer`] on the global default
+/// [`tracing::Subscriber`] via `tracing_subscriber`'s registry, so
+/// `logwise_read`/`logwise_grep` also see events from crates that emit
+/// diagnostics through `tracing` rather than (or in addition to) `logwise`.
+///
+/// Like [`crate::logwise::begin_capture`], this should typically be called
+/// once at application startup.
+///
+/// # Panics
+///
+/// Panics if a global `tracing` subscriber has already been installed.
+pub fn begin_capture_tracing() {
+    use tracing_subscriber::layer::SubscriberExt;
+    let subscriber = tracing_subscriber::registry().with(ExfiltrateTracingLayer);
+    tracing::subscriber::set_global_default(subscriber)
+        .expect("begin_capture_tracing must be called before any other tracing subscriber is installed");
+    eprintln!("Tracing capture started");
+}
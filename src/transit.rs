@@ -73,6 +73,9 @@ compile_error!("The `transit` feature is not supported on wasm32 targets. Build
 pub mod http;
 pub mod transit_proxy;
 pub mod stdio;
+pub mod child;
+pub mod transport;
+pub mod upstream;
 mod log_proxy;
 mod builtin_tools;
 
@@ -40,7 +40,10 @@
 //! - `logwise`: Enables log capture and inspection tools (`LogwiseRead`, `LogwiseGrep`)
 //!
 
-use crate::tools::{Tool, ToolCallParams, ToolCallResponse, ToolInfo, ToolList};
+use crate::tools::{
+    Argument, InputSchema, Tool, ToolCallError, ToolCallParams, ToolCallResponse, ToolInfo,
+    ToolList,
+};
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
@@ -55,9 +58,77 @@ static PROXY_ONLY_TOOLS: LazyLock<Vec<Box<dyn Tool>>> = LazyLock::new(|| {
         Box::new(crate::transit::log_proxy::LogwiseRead),
         #[cfg(feature = "logwise")]
         Box::new(crate::transit::log_proxy::LogwiseGrep),
+        Box::new(ListTargets),
+        Box::new(SelectTarget),
     ]
 });
 
+/// Describes the `list_targets` and `select_target` proxy-only tools for
+/// `tools/list`.
+///
+/// Both names are intercepted directly by
+/// [`crate::transit::transit_proxy::TransitProxy::send_request`], the same
+/// way `run_latest_tool` is, because they need live access to which targets
+/// are currently connected -- state that lives on the `TransitProxy`
+/// instance, not in a [`Tool`] impl's stateless `call`. These structs exist
+/// purely so the tools are discoverable; their `call` is never reached.
+struct ListTargets;
+
+impl Tool for ListTargets {
+    fn name(&self) -> &str {
+        "list_targets"
+    }
+
+    fn description(&self) -> &str {
+        "Lists every target application currently connected to the transit proxy. Each entry \
+        reports the connection id used to namespace that target's tools (as `{id}::{tool_name}`) \
+        and to route calls to it, plus which one is currently selected for unprefixed `tools/call` \
+        (see `select_target`)."
+    }
+
+    fn input_schema(&self) -> InputSchema {
+        InputSchema::new(vec![])
+    }
+
+    fn call(&self, _params: HashMap<String, serde_json::Value>) -> Result<ToolCallResponse, ToolCallError> {
+        Err(ToolCallError::new(vec![
+            "list_targets must be invoked through the transit proxy, which intercepts it directly"
+                .into(),
+        ]))
+    }
+}
+
+/// See [`ListTargets`].
+struct SelectTarget;
+
+impl Tool for SelectTarget {
+    fn name(&self) -> &str {
+        "select_target"
+    }
+
+    fn description(&self) -> &str {
+        "Pins which connected target an unprefixed `tools/call` routes to. Takes a \
+        `connection_id` argument matching one reported by `list_targets`. Calls that name their \
+        target explicitly (`{id}::{tool_name}`) ignore the pinned selection."
+    }
+
+    fn input_schema(&self) -> InputSchema {
+        InputSchema::new(vec![Argument::new(
+            "connection_id".to_string(),
+            "string".to_string(),
+            "Connection id to select, as reported by list_targets".to_string(),
+            true,
+        )])
+    }
+
+    fn call(&self, _params: HashMap<String, serde_json::Value>) -> Result<ToolCallResponse, ToolCallError> {
+        Err(ToolCallError::new(vec![
+            "select_target must be invoked through the transit proxy, which intercepts it directly"
+                .into(),
+        ]))
+    }
+}
+
 /// Returns a list of all tools available in the proxy application.
 ///
 /// This function combines proxy-only tools with shared tools to provide the complete
@@ -77,7 +148,10 @@ pub fn proxy_tools() -> ToolList {
         .chain(PROXY_ONLY_TOOLS.iter())
         .map(|tool| ToolInfo::from_tool(tool.as_ref()))
         .collect::<Vec<_>>();
-    ToolList { tools }
+    ToolList {
+        tools,
+        revision: crate::tools::current_revision(),
+    }
 }
 
 /// Returns a list of tools that are exclusive to the proxy application.
@@ -96,7 +170,10 @@ pub fn proxy_only_tools() -> ToolList {
         .iter()
         .map(|tool| ToolInfo::from_tool(tool.as_ref()))
         .collect::<Vec<_>>();
-    ToolList { tools }
+    ToolList {
+        tools,
+        revision: crate::tools::current_revision(),
+    }
 }
 
 /// Calls a tool on the proxy application.
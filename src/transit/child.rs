@@ -0,0 +1,223 @@
+//! Child-process target management for the transit proxy.
+//!
+//! A common debugging workflow is "launch the program under the proxy": rather
+//! than waiting for a target application to dial in to
+//! [`TransitProxy`](crate::transit::transit_proxy::TransitProxy), the proxy
+//! can spawn it directly as a child process. [`ChildTarget`] manages that
+//! child's lifecycle:
+//!
+//! - Its stdout/stderr are forwarded, line by line, to this process's own
+//!   stdout/stderr (prefixed with `[target]`/`[target stderr]`) so the
+//!   child's ordinary program output stays visible.
+//! - [`ChildTarget::shutdown`] (also called from `Drop`, making it a scope
+//!   guard) sends the child a kill signal and then waits on it, guaranteeing
+//!   it's reaped so no orphaned process or zombie is left behind, regardless
+//!   of whether the caller shuts down cleanly or is dropped on an error path.
+//! - Setting [`ChildTargetConfig::restart_on_exit`] relaunches the child
+//!   whenever it exits, so workflows like dynamic tool discovery (where the
+//!   target program itself restarts repeatedly) can keep a single, stable
+//!   proxy running underneath it.
+//!
+//! Note that this only supervises the child process itself; the target's
+//! exfiltrate-wire connection to the proxy (see
+//! [`internal_proxy`](crate::internal_proxy)) is independent and reconnects
+//! on its own whenever the restarted child comes back up.
+
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How often the supervisor thread polls the child for exit.
+const CHILD_POLL_INTERVAL: Duration = Duration::from_millis(20);
+
+/// Configuration for a child-process target.
+#[derive(Debug, Clone)]
+pub struct ChildTargetConfig {
+    /// The program to launch.
+    pub command: String,
+    /// Arguments to pass to `command`.
+    pub args: Vec<String>,
+    /// If `true`, the child is relaunched whenever it exits, until
+    /// [`ChildTarget::shutdown`] is called.
+    pub restart_on_exit: bool,
+}
+
+impl ChildTargetConfig {
+    /// Creates a configuration that launches `command` with `args` and does
+    /// not restart it when it exits.
+    pub fn new(command: impl Into<String>, args: Vec<String>) -> Self {
+        ChildTargetConfig {
+            command: command.into(),
+            args,
+            restart_on_exit: false,
+        }
+    }
+
+    /// Sets whether the child is relaunched when it exits.
+    pub fn with_restart_on_exit(mut self, restart_on_exit: bool) -> Self {
+        self.restart_on_exit = restart_on_exit;
+        self
+    }
+}
+
+/// A running child process plus the threads forwarding its output.
+struct ChildHandle {
+    child: Child,
+    stdout_thread: JoinHandle<()>,
+    stderr_thread: JoinHandle<()>,
+}
+
+/// Manages the lifecycle of a target application launched as a child process.
+///
+/// See the [module documentation](self) for the cleanup and restart
+/// guarantees this provides.
+pub struct ChildTarget {
+    shutdown: Arc<AtomicBool>,
+    current_child: Arc<Mutex<Option<ChildHandle>>>,
+    supervisor: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ChildTarget {
+    /// Spawns `config.command` and begins supervising it.
+    pub fn spawn(config: ChildTargetConfig) -> std::io::Result<ChildTarget> {
+        let handle = Self::launch(&config)?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let current_child = Arc::new(Mutex::new(Some(handle)));
+
+        let supervisor_shutdown = shutdown.clone();
+        let supervisor_current_child = current_child.clone();
+        let supervisor = std::thread::Builder::new()
+            .name("exfiltrate::transit::child".to_string())
+            .spawn(move || {
+                Self::supervise(config, supervisor_shutdown, supervisor_current_child);
+            })
+            .unwrap();
+
+        Ok(ChildTarget {
+            shutdown,
+            current_child,
+            supervisor: Mutex::new(Some(supervisor)),
+        })
+    }
+
+    fn launch(config: &ChildTargetConfig) -> std::io::Result<ChildHandle> {
+        let mut child = Command::new(&config.command)
+            .args(&config.args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        let stdout = child.stdout.take().expect("stdout was piped");
+        let stderr = child.stderr.take().expect("stderr was piped");
+        Ok(ChildHandle {
+            child,
+            stdout_thread: forward_lines(stdout, "[target] ", false),
+            stderr_thread: forward_lines(stderr, "[target stderr] ", true),
+        })
+    }
+
+    /// Runs on a dedicated thread: polls the current child for exit, and
+    /// either relaunches it (if `restart_on_exit`) or returns.
+    fn supervise(
+        config: ChildTargetConfig,
+        shutdown: Arc<AtomicBool>,
+        current_child: Arc<Mutex<Option<ChildHandle>>>,
+    ) {
+        loop {
+            loop {
+                if shutdown.load(Ordering::Acquire) {
+                    return;
+                }
+                let mut guard = current_child.lock().unwrap();
+                let Some(handle) = guard.as_mut() else {
+                    // shutdown() already took the child out from under us.
+                    return;
+                };
+                match handle.child.try_wait() {
+                    Ok(Some(status)) => {
+                        eprintln!("transit: child target exited: {status}");
+                        break;
+                    }
+                    Ok(None) => {}
+                    Err(e) => {
+                        eprintln!("transit: failed to poll child target: {e}");
+                        break;
+                    }
+                }
+                drop(guard);
+                std::thread::sleep(CHILD_POLL_INTERVAL);
+            }
+            if let Some(handle) = current_child.lock().unwrap().take() {
+                let _ = handle.stdout_thread.join();
+                let _ = handle.stderr_thread.join();
+            }
+            if shutdown.load(Ordering::Acquire) || !config.restart_on_exit {
+                return;
+            }
+            match Self::launch(&config) {
+                Ok(handle) => *current_child.lock().unwrap() = Some(handle),
+                Err(e) => {
+                    eprintln!("transit: failed to restart child target: {e}");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Terminates the child (if still running) and reaps it, then waits for
+    /// the supervisor thread to notice and stop.
+    ///
+    /// Safe to call more than once; also called from `Drop`, so a
+    /// `ChildTarget` going out of scope on any exit path (including a panic
+    /// unwind) cleans up its child.
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Release);
+        if let Some(mut handle) = self.current_child.lock().unwrap().take() {
+            let _ = handle.child.kill();
+            let _ = handle.child.wait();
+            let _ = handle.stdout_thread.join();
+            let _ = handle.stderr_thread.join();
+        }
+        if let Some(supervisor) = self.supervisor.lock().unwrap().take() {
+            let _ = supervisor.join();
+        }
+    }
+}
+
+impl Drop for ChildTarget {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Spawns a thread that reads `reader` line by line and forwards each line,
+/// prefixed with `prefix`, to this process's stdout or stderr.
+fn forward_lines(
+    reader: impl std::io::Read + Send + 'static,
+    prefix: &'static str,
+    to_stderr: bool,
+) -> JoinHandle<()> {
+    std::thread::Builder::new()
+        .name("exfiltrate::transit::child::forward".to_string())
+        .spawn(move || {
+            let mut reader = BufReader::new(reader);
+            let mut line = String::new();
+            loop {
+                line.clear();
+                match reader.read_line(&mut line) {
+                    Ok(0) | Err(_) => break,
+                    Ok(_) => {
+                        if to_stderr {
+                            eprint!("{prefix}{line}");
+                        } else {
+                            print!("{prefix}{line}");
+                        }
+                    }
+                }
+            }
+        })
+        .unwrap()
+}
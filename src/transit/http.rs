@@ -1,9 +1,10 @@
-use crate::bidirectional_proxy::{Error, ReadTransport, WriteTransport};
+use crate::bidirectional_proxy::{Error, IoContextExt, ReadTransport, WriteTransport};
 use crate::transit::transit_proxy::TransitProxy;
 use base64::Engine;
 use std::collections::HashMap;
 use std::io::{BufRead, Read, Write};
 use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 struct HTTPParser {
@@ -144,6 +145,24 @@ impl HTTPParser {
             HTTPParseResult::SSE
         } else if method == b"POST" {
             //we need to read the body
+            let chunked = headers
+                .get("transfer-encoding")
+                .map(|s| s.to_lowercase())
+                .as_deref()
+                == Some("chunked");
+            if chunked {
+                return match Self::dechunk(&self.buf[pos..]) {
+                    Ok(Some(body)) => {
+                        self.buf.clear();
+                        HTTPParseResult::Post(body)
+                    }
+                    Ok(None) => HTTPParseResult::NotReady, //not enough data yet
+                    Err(reason) => {
+                        self.buf.clear();
+                        HTTPParseResult::Rejected(reason)
+                    }
+                };
+            }
             let content_length = match headers.get("content-length") {
                 Some(len) => match len.parse::<usize>() {
                     Ok(len) => len,
@@ -186,6 +205,65 @@ impl HTTPParser {
             HTTPParseResult::Rejected(f)
         }
     }
+
+    /// Decodes a `Transfer-Encoding: chunked` body
+    /// (https://datatracker.ietf.org/doc/html/rfc7230#section-4.1) starting
+    /// right after the request headers.
+    ///
+    /// Returns `Ok(None)` if `body` doesn't yet contain the terminating
+    /// zero-length chunk (the caller should wait for more bytes), `Ok(Some(bytes))`
+    /// with the reassembled, de-chunked payload once it does, and `Err` if a
+    /// chunk's size line isn't valid hex. Chunk extensions and trailer
+    /// headers are accepted but ignored.
+    fn dechunk(body: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        let mut decoded = Vec::new();
+        let mut pos = 0;
+        loop {
+            let Some(line_end) = find_crlf(&body[pos..]) else {
+                return Ok(None); //chunk-size line not fully arrived yet
+            };
+            let size_line = &body[pos..pos + line_end];
+            //ignore chunk extensions (";...") per RFC 7230 section 4.1.1
+            let size_str = size_line
+                .split(|&b| b == b';')
+                .next()
+                .unwrap_or(size_line);
+            let size_str = String::from_utf8_lossy(size_str);
+            let chunk_size = match usize::from_str_radix(size_str.trim(), 16) {
+                Ok(size) => size,
+                Err(_) => {
+                    return Err(format!("Invalid chunk size: {}", size_str));
+                }
+            };
+            let chunk_start = pos + line_end + 2; //past the size line's CRLF
+            if chunk_size == 0 {
+                //terminating chunk; trailers (if any, ignored) end with a blank line
+                if find_crlf_crlf(&body[chunk_start..]).is_none() {
+                    return Ok(None);
+                }
+                return Ok(Some(decoded));
+            }
+            if body.len() < chunk_start + chunk_size + 2 {
+                return Ok(None); //chunk data/trailing CRLF not fully arrived yet
+            }
+            decoded.extend_from_slice(&body[chunk_start..chunk_start + chunk_size]);
+            pos = chunk_start + chunk_size + 2; //past the chunk's trailing CRLF
+        }
+    }
+}
+
+/// Finds the byte offset of the next `\r\n` in `buf`, if any.
+fn find_crlf(buf: &[u8]) -> Option<usize> {
+    buf.windows(2).position(|w| w == b"\r\n")
+}
+
+/// Finds the byte offset just past the next blank line (`\r\n\r\n`, or a bare
+/// `\r\n` immediately at `buf`'s start) in `buf`, if any.
+fn find_crlf_crlf(buf: &[u8]) -> Option<usize> {
+    if buf.starts_with(b"\r\n") {
+        return Some(2);
+    }
+    buf.windows(4).position(|w| w == b"\r\n\r\n").map(|i| i + 4)
 }
 
 #[derive(Debug)]
@@ -196,6 +274,10 @@ pub(crate) struct WebsocketWriteStream {
 pub(crate) struct WebsocketReadStream {
     tcp: TcpStream,
     tcp_layer_buf: Vec<u8>,
+    /// Payload accumulated so far for a fragmented message (FIN=0 frames),
+    /// `None` when no such message is in progress.
+    reassembly: Option<Vec<u8>>,
+    limits: WebsocketFrameLimits,
 }
 
 impl WebsocketWriteStream {
@@ -205,52 +287,78 @@ impl WebsocketWriteStream {
 }
 
 impl WebsocketReadStream {
-    fn new(tcp: TcpStream, in_buf: Vec<u8>) -> Self {
+    fn new(tcp: TcpStream, in_buf: Vec<u8>, limits: WebsocketFrameLimits) -> Self {
         WebsocketReadStream {
             tcp,
             tcp_layer_buf: in_buf,
+            reassembly: None,
+            limits,
         }
     }
 }
 
-/// Read transport that can handle either WebSocket or plain TCP stream connections.
+/// Read transport that can handle either WebSocket, plain TCP, or Unix
+/// domain socket stream connections.
 ///
-/// This enum provides a unified interface for reading data from either WebSocket
-/// connections (which require frame parsing) or plain TCP streams.
+/// This enum provides a unified interface for reading data from either
+/// WebSocket connections (which require frame parsing) or plain stream
+/// connections, whatever [`crate::transit::transport::Transport`] produced
+/// them.
 #[derive(Debug)]
 pub(crate) enum ReadWebSocketOrStream {
     /// WebSocket connection requiring frame parsing
     WebSocket(WebsocketReadStream),
     /// Plain TCP stream connection
     Stream(TcpStream),
+    /// Plain Unix domain socket stream connection
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixStream),
 }
-/// Write transport that can handle either WebSocket or plain TCP stream connections.
+/// Write transport that can handle either WebSocket, plain TCP, or Unix
+/// domain socket stream connections.
 ///
-/// This enum provides a unified interface for writing data to either WebSocket
-/// connections (which require frame encoding) or plain TCP streams.
+/// This enum provides a unified interface for writing data to either
+/// WebSocket connections (which require frame encoding) or plain stream
+/// connections, whatever [`crate::transit::transport::Transport`] produced
+/// them.
 #[derive(Debug)]
 pub(crate) enum WriteWebSocketOrStream {
     /// WebSocket connection requiring frame encoding
     WebSocket(WebsocketWriteStream),
     /// Plain TCP stream connection
     Stream(TcpStream),
+    /// Plain Unix domain socket stream connection
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixStream),
 }
 impl WriteTransport for WriteWebSocketOrStream {
-    fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+    fn write(&mut self, data: &[u8]) -> Result<usize, Error> {
         match self {
-            Self::Stream(stream) => {
-                WriteTransport::write(stream, data)?;
-                Ok(())
-            }
+            Self::Stream(stream) => WriteTransport::write(stream, data),
+            #[cfg(unix)]
+            Self::Unix(stream) => WriteTransport::write(stream, data),
             Self::WebSocket(stream) => {
                 // eprintln!("WebSocket write_block: data_len={}, first 10 bytes: {:?}",
                 //     data.len(), &data[..data.len().min(10)]);
-                let frame = WebsocketFrame::new(data.to_vec(), false);
-                let bytes = frame.to_bytes();
-                // eprintln!("WebSocket frame bytes: len={}, first 20 bytes: {:?}",
-                //     bytes.len(), &bytes[..bytes.len().min(20)]);
-                WriteTransport::write(&mut stream.tcp, &bytes)?;
-                Ok(())
+                //avoid cloning `data` into an owned frame just to copy it
+                //again in to_bytes: write the small header, then the
+                //caller's payload buffer directly
+                let parts = WebsocketFrame::binary_parts(data);
+                //a frame can't be resumed halfway, so report either nothing
+                //sent (header refused outright) or the whole of `data`
+                let header_written = WriteTransport::write(&mut stream.tcp, &parts.header)?;
+                if header_written == 0 {
+                    return Ok(0);
+                }
+                let payload_written = WriteTransport::write(&mut stream.tcp, parts.payload)?;
+                if header_written < parts.header.len() || payload_written < parts.payload.len() {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::WriteZero,
+                        "WebSocket frame only partially written",
+                    ))
+                    .io_context("writing to the WebSocket connection");
+                }
+                Ok(data.len())
             }
         }
     }
@@ -261,6 +369,11 @@ impl WriteTransport for WriteWebSocketOrStream {
                 WriteTransport::flush(stream)?;
                 Ok(())
             }
+            #[cfg(unix)]
+            Self::Unix(stream) => {
+                WriteTransport::flush(stream)?;
+                Ok(())
+            }
             Self::WebSocket(stream) => {
                 WriteTransport::flush(&mut stream.tcp)?;
                 Ok(())
@@ -269,66 +382,157 @@ impl WriteTransport for WriteWebSocketOrStream {
     }
 }
 impl ReadTransport for ReadWebSocketOrStream {
-    fn read_nonblock(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+    fn try_read(&mut self, buf: &mut [u8]) -> Result<Option<usize>, Error> {
         match self {
-            ReadWebSocketOrStream::Stream(stream) => {
-                let bytes_read = stream.read_nonblock(buf)?;
-                Ok(bytes_read)
-            }
+            ReadWebSocketOrStream::Stream(stream) => stream.try_read(buf),
+            #[cfg(unix)]
+            ReadWebSocketOrStream::Unix(stream) => stream.try_read(buf),
             ReadWebSocketOrStream::WebSocket(stream) => {
                 //see if we can parse a frame with no read
                 if let Ok(bytes) = stream.try_parse_frame(buf)
                     && bytes > 0
                 {
                     // eprintln!("WebSocket read_nonblock: parsed {} bytes from buffer", bytes);
-                    return Ok(bytes);
+                    return Ok(Some(bytes));
                 }
                 //if we can't parse a frame, we need to read more data
                 //we can abuse the input buf for this
-                let bytes = stream.tcp.read_nonblock(buf).unwrap();
+                let Some(bytes) = stream.tcp.try_read(buf)? else {
+                    return Ok(None);
+                };
                 //put into the ws buffer
                 stream.tcp_layer_buf.extend_from_slice(&buf[..bytes]);
                 // try to parse a frame again
-                stream.try_parse_frame(buf)
+                stream.try_parse_frame(buf).map(Some)
             }
         }
     }
 }
 
 impl WebsocketReadStream {
+    /// Copies a fully-reassembled message's `data` into `buf`, stashing any
+    /// overflow back into `tcp_layer_buf` the same way a single-frame
+    /// message always has, and returns the number of bytes copied.
+    fn deliver(&mut self, data: Vec<u8>, buf: &mut [u8]) -> usize {
+        let bytes_to_copy = data.len().min(buf.len());
+        buf[..bytes_to_copy].copy_from_slice(&data[..bytes_to_copy]);
+        if data.len() > bytes_to_copy {
+            self.tcp_layer_buf.extend_from_slice(&data[bytes_to_copy..]);
+        }
+        bytes_to_copy
+    }
+
+    /// Sends a server-originated control frame (pong/close) on this
+    /// connection's socket.
+    fn send_control(&mut self, opcode: WebsocketOpcode, data: Vec<u8>) -> Result<(), Error> {
+        let frame = WebsocketFrame::control(opcode, data);
+        WriteTransport::write(&mut self.tcp, &frame.to_bytes())?;
+        WriteTransport::flush(&mut self.tcp)
+    }
+
+    /// Replies with a close frame carrying `code` and builds the
+    /// [`Error`] that terminates the connection for a protocol violation.
+    /// Best-effort: if the close frame itself fails to send, the
+    /// connection is torn down anyway.
+    fn protocol_error(&mut self, code: CloseCode, message: String) -> Error {
+        let _ = self.send_control(WebsocketOpcode::Close, (code as u16).to_be_bytes().to_vec());
+        std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+            .io_context("parsing the WebSocket frame stream")
+            .unwrap_err()
+    }
+
+    /// Rejects the in-progress reassembly with a `MessageTooBig` close once
+    /// it grows past [`WebsocketFrameLimits::max_message_size`].
+    fn enforce_max_message_size(&mut self) -> Result<(), Error> {
+        if self
+            .reassembly
+            .as_ref()
+            .is_some_and(|partial| partial.len() > self.limits.max_message_size)
+        {
+            self.reassembly = None;
+            return Err(self.protocol_error(
+                CloseCode::MessageTooBig,
+                "Reassembled message exceeds max_message_size".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     fn try_parse_frame(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
-        //try to parse a frame
-        // eprintln!("try_parse_frame: stream_buf len={}", self.tcp_layer_buf.len());
-        match WebsocketFrame::from_bytes(&self.tcp_layer_buf) {
-            Ok((frame, size)) => {
-                // eprintln!("WebSocket Frame Parsed with size {}",size);
-                // eprintln!("WebSocket frame parsed: frame_size={}, data_len={}, first_10_bytes={:?}",
-                //     size, frame.data.len(),
-                //     &frame.data[..frame.data.len().min(10)]);
-                //copy the data to the output buffer
-                let bytes_to_copy = frame.data.len().min(buf.len());
-                buf[..bytes_to_copy].copy_from_slice(&frame.data[..bytes_to_copy]);
-                //remove the bytes from the input buffer
-                self.tcp_layer_buf.drain(..size);
-                //place additional bytes in the output buffer
-                if frame.data.len() > bytes_to_copy {
-                    // eprintln!("WebSocket frame data larger than buffer: data_len={}, buf_len={}, overflow={}",
-                    //     frame.data.len(), buf.len(), frame.data.len() - bytes_to_copy);
-                    self.tcp_layer_buf
-                        .extend_from_slice(&frame.data[bytes_to_copy..]);
+        //a single read may contain several buffered wire frames (e.g. every
+        //fragment of a message arriving in one TCP read), so keep parsing
+        //until we either deliver a complete message or run out of bytes
+        loop {
+            // eprintln!("try_parse_frame: stream_buf len={}", self.tcp_layer_buf.len());
+            match WebsocketFrame::from_bytes(&self.tcp_layer_buf, self.limits.max_frame_size) {
+                Ok((frame, size)) => {
+                    self.tcp_layer_buf.drain(..size);
+                    match frame.opcode {
+                        WebsocketOpcode::Ping => {
+                            self.send_control(WebsocketOpcode::Pong, frame.data)?;
+                        }
+                        WebsocketOpcode::Pong => {
+                            //unsolicited pong; nothing to reply to
+                        }
+                        WebsocketOpcode::Close => {
+                            let _ = self.send_control(
+                                WebsocketOpcode::Close,
+                                (CloseCode::Normal as u16).to_be_bytes().to_vec(),
+                            );
+                            return Err(std::io::Error::new(
+                                std::io::ErrorKind::ConnectionAborted,
+                                "WebSocket closing handshake received",
+                            ))
+                            .io_context("reading from the WebSocket connection");
+                        }
+                        WebsocketOpcode::Continuation => {
+                            let Some(partial) = &mut self.reassembly else {
+                                return Err(self.protocol_error(
+                                    CloseCode::ProtocolError,
+                                    "Continuation frame received with no fragmented message in progress".to_string(),
+                                ));
+                            };
+                            partial.extend_from_slice(&frame.data);
+                            self.enforce_max_message_size()?;
+                            if frame.fin {
+                                let data = self.reassembly.take().unwrap();
+                                return Ok(self.deliver(data, buf));
+                            }
+                            //fragment accumulated, keep waiting for more
+                        }
+                        WebsocketOpcode::Binary => {
+                            if self.reassembly.is_some() {
+                                self.reassembly = None;
+                                return Err(self.protocol_error(
+                                    CloseCode::ProtocolError,
+                                    "New data frame received while a fragmented message was in progress".to_string(),
+                                ));
+                            } else if frame.fin {
+                                return Ok(self.deliver(frame.data, buf));
+                            } else {
+                                //start of a fragmented message
+                                self.reassembly = Some(frame.data);
+                                self.enforce_max_message_size()?;
+                            }
+                        }
+                    }
+                }
+                Err(WebsocketFrameError::FrameTooShort) => {
+                    return Ok(0); //not enough data to parse a frame
+                }
+                Err(WebsocketFrameError::Rejected(code, reason)) => {
+                    eprintln!("WebSocket Frame Rejected: {}", reason);
+                    self.tcp_layer_buf.drain(..);
+                    return Err(self.protocol_error(code, reason));
+                }
+                Err(WebsocketFrameError::TooLarge) => {
+                    eprintln!("WebSocket Frame Rejected: declared length exceeds max_frame_size");
+                    self.tcp_layer_buf.drain(..);
+                    return Err(self.protocol_error(
+                        CloseCode::MessageTooBig,
+                        "Frame length exceeds max_frame_size".to_string(),
+                    ));
                 }
-                Ok(bytes_to_copy)
-            }
-            Err(WebsocketFrameError::FrameTooShort) => {
-                Ok(0) //not enough data to parse a frame
-            }
-            Err(WebsocketFrameError::Rejected(reason)) => {
-                eprintln!("WebSocket Frame Rejected: {}", reason);
-                self.tcp_layer_buf.drain(..);
-                Err(Error::IoError(std::io::Error::new(
-                    std::io::ErrorKind::InvalidData,
-                    reason,
-                )))
             }
         }
     }
@@ -361,7 +565,18 @@ impl WebsocketReadStream {
 /// # }
 /// ```
 #[derive(Debug)]
-pub struct Server {}
+pub struct Server {
+    /// Set by [`Server::shutdown`] to tell the accept loop to stop accepting
+    /// connections and exit; session threads never read it.
+    shutdown: Arc<AtomicBool>,
+    /// The bound address, used to unblock the accept loop's blocking
+    /// `accept()` call by connecting to ourselves.
+    local_addr: std::net::SocketAddr,
+    /// Handle for the accept loop thread, taken and joined by `shutdown`.
+    accept_thread: Mutex<Option<std::thread::JoinHandle<()>>>,
+    /// Handles for every in-flight per-connection session thread.
+    connections: Arc<Mutex<Vec<std::thread::JoinHandle<()>>>>,
+}
 
 /// Queue for sending Server-Sent Events (SSE) messages to connected clients.
 ///
@@ -377,14 +592,19 @@ impl MessageQueue {
     }
 
     fn send(&mut self, message: &[u8]) -> Result<(), std::io::Error> {
+        let mut event = Vec::new();
         for line in message.lines() {
             let line = line.unwrap();
-            std::io::Write::write(&mut self.stream, "data: ".as_bytes()).unwrap();
-            std::io::Write::write(&mut self.stream, line.as_bytes()).unwrap();
-            std::io::Write::write(&mut self.stream, "\r\n".as_bytes()).unwrap();
-            // eprintln!("Sent message to {:?}: {}", self.stream.peer_addr(),format!("data: {}", line));
+            event.extend_from_slice(b"data: ");
+            event.extend_from_slice(line.as_bytes());
+            event.extend_from_slice(b"\r\n");
         }
-        std::io::Write::write(&mut self.stream, "\r\n\r\n".as_bytes()).unwrap(); // End of message
+        event.extend_from_slice(b"\r\n\r\n"); // End of message
+        //the response is chunked transfer encoding (see the SSE upgrade in
+        //Session::run), so every push has to be wrapped as its own chunk
+        std::io::Write::write(&mut self.stream, format!("{:x}\r\n", event.len()).as_bytes())?;
+        std::io::Write::write(&mut self.stream, &event)?;
+        std::io::Write::write(&mut self.stream, b"\r\n")?;
         std::io::Write::flush(&mut self.stream)?;
         Ok(())
     }
@@ -394,6 +614,7 @@ struct Session {
     stream: Option<TcpStream>,
     proxy: Arc<Mutex<TransitProxy>>,
     active_session: Arc<Mutex<Option<MessageQueue>>>,
+    frame_limits: WebsocketFrameLimits,
 }
 
 impl Session {
@@ -401,11 +622,13 @@ impl Session {
         stream: std::net::TcpStream,
         proxy: Arc<Mutex<TransitProxy>>,
         active_session: Arc<Mutex<Option<MessageQueue>>>,
+        frame_limits: WebsocketFrameLimits,
     ) -> Self {
         Session {
             stream: Some(stream),
             proxy,
             active_session,
+            frame_limits,
         }
     }
 
@@ -425,8 +648,10 @@ impl Session {
             }
             match parser.pop() {
                 HTTPParseResult::SSE => {
-                    //begin response
-                    let response = b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\r\n";
+                    //begin response; no Content-Length since this connection
+                    //stays open and streams events as they occur, so the
+                    //body has to be framed as chunked transfer encoding
+                    let response = b"HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\nTransfer-Encoding: chunked\r\n\r\n";
                     std::io::Write::write(self.stream.as_mut().unwrap(), response)
                         .expect("Failed to write to stream");
                     std::io::Write::flush(self.stream.as_mut().unwrap())
@@ -500,13 +725,14 @@ impl Session {
                     let stream = self.stream.take().unwrap();
                     let write_stream = WebsocketWriteStream::new(stream.try_clone().unwrap());
                     let write_stream = WriteWebSocketOrStream::WebSocket(write_stream);
-                    let read_stream = WebsocketReadStream::new(stream, info.leftover_bytes);
+                    let read_stream =
+                        WebsocketReadStream::new(stream, info.leftover_bytes, self.frame_limits);
                     let read_stream = ReadWebSocketOrStream::WebSocket(read_stream);
 
                     self.proxy
                         .lock()
                         .unwrap()
-                        .change_accept(Some((write_stream, read_stream)));
+                        .change_accept((write_stream, read_stream));
                     return; //promoted to transit proxy
                 }
             }
@@ -516,8 +742,7 @@ impl Session {
     fn handle_body(&mut self, body: &[u8]) {
         let r = self.proxy.lock().unwrap().received_data(body);
         match r {
-            Some(response) => {
-                let as_bytes = serde_json::to_vec(&response).unwrap();
+            Some(as_bytes) => {
                 let stream = self.stream.as_mut().unwrap();
                 // Write the response back to the stream
                 std::io::Write::write(
@@ -564,16 +789,34 @@ impl Server {
     /// # }
     /// ```
     ///
+    /// Dropping the returned `Server` (or calling [`Server::shutdown`]
+    /// explicitly) stops the accept loop, then waits for every session
+    /// thread. Sessions don't check for shutdown: a plain HTTP session keeps
+    /// serving requests on its connection until the peer disconnects, so
+    /// the drop blocks until then.
+    ///
     /// # Panics
     ///
     /// Panics if the server cannot bind to the specified address.
     pub fn new<A: ToSocketAddrs>(addr: A, proxy: TransitProxy) -> Self {
+        Self::with_frame_limits(addr, proxy, WebsocketFrameLimits::default())
+    }
+
+    /// Like [`Server::new`], but overrides the limits enforced on incoming
+    /// WebSocket frames/messages (see [`WebsocketFrameLimits`]) instead of
+    /// taking its 64 KiB / 1 MiB defaults.
+    pub fn with_frame_limits<A: ToSocketAddrs>(
+        addr: A,
+        proxy: TransitProxy,
+        frame_limits: WebsocketFrameLimits,
+    ) -> Self {
         //listen on a tcp socket
         eprintln!(
             "http: starting MCP server on {}",
             addr.to_socket_addrs().unwrap().next().unwrap()
         );
         let listener = std::net::TcpListener::bind(addr).unwrap();
+        let local_addr = listener.local_addr().expect("bound listener has no local address");
         let active_session = Arc::new(Mutex::new(None::<MessageQueue>));
         let move_active_session = active_session.clone();
         proxy.bind(move |notification| {
@@ -598,19 +841,72 @@ impl Server {
                 );
             }
         });
+        let move_active_session = active_session.clone();
+        proxy.bind_client(move |data| {
+            let mut sessions = move_active_session.lock().unwrap();
+            if let Some(ref mut session) = *sessions {
+                if let Err(e) = session.send(data) {
+                    eprintln!("http: failed to send proxy-initiated request: {}", e);
+                    //if we fail to send, we should remove the session
+                    *sessions = None;
+                }
+            } else {
+                eprintln!(
+                    "http: no active session for proxy-initiated request: {:?}",
+                    String::from_utf8_lossy(data)
+                );
+            }
+        });
         let proxy = Arc::new(Mutex::new(proxy));
 
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let connections = Arc::new(Mutex::new(Vec::new()));
+        let accept_shutdown = shutdown.clone();
+        let accept_connections = connections.clone();
         let move_proxy = proxy.clone();
-        std::thread::Builder::new()
+        let accept_thread = std::thread::Builder::new()
             .name("exfiltrate-server".to_string())
             .spawn(move || {
                 loop {
                     let (stream, addr) = listener.accept().unwrap();
-                    Self::on_accept(stream, addr, move_proxy.clone(), active_session.clone());
+                    if accept_shutdown.load(Ordering::Acquire) {
+                        return;
+                    }
+                    let handle = Self::on_accept(
+                        stream,
+                        addr,
+                        move_proxy.clone(),
+                        active_session.clone(),
+                        frame_limits,
+                    );
+                    accept_connections.lock().unwrap().push(handle);
                 }
             })
             .unwrap();
-        Server {}
+        Server {
+            shutdown,
+            local_addr,
+            accept_thread: Mutex::new(Some(accept_thread)),
+            connections,
+        }
+    }
+
+    /// Signals the accept loop to drain and exit, then blocks until it (and
+    /// every session thread already spawned) has.
+    ///
+    /// Connects to ourselves to unblock the accept loop's blocking
+    /// `accept()` call, then joins the accept thread and every in-flight
+    /// session thread. See the caveat on [`Server::new`] about sessions
+    /// blocked on a synchronous read. Safe to call more than once.
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Release);
+        let _ = TcpStream::connect(self.local_addr);
+        if let Some(handle) = self.accept_thread.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+        for handle in self.connections.lock().unwrap().drain(..) {
+            let _ = handle.join();
+        }
     }
 
     fn on_accept(
@@ -618,17 +914,105 @@ impl Server {
         addr: std::net::SocketAddr,
         proxy: Arc<Mutex<TransitProxy>>,
         sessions: Arc<Mutex<Option<MessageQueue>>>,
-    ) {
+        frame_limits: WebsocketFrameLimits,
+    ) -> std::thread::JoinHandle<()> {
         //start a new thread to handle the connection
         eprintln!("http: Accepted connection from {}", addr);
 
         std::thread::Builder::new()
             .name(format!("exfiltrate-server-{}", addr))
             .spawn(move || {
-                let mut session = Session::new(stream, proxy, sessions);
+                let mut session = Session::new(stream, proxy, sessions, frame_limits);
                 session.run();
             })
-            .unwrap();
+            .unwrap()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// A WebSocket frame's opcode (RFC 6455 section 5.2), restricted to the
+/// variants this implementation understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WebsocketOpcode {
+    /// `0x0` - continues a fragmented message started by a prior frame.
+    Continuation,
+    /// `0x2` - a complete (or initial) binary data frame.
+    Binary,
+    /// `0x8` - begins the closing handshake.
+    Close,
+    /// `0x9` - a liveness check; must be answered with a [`Pong`](Self::Pong).
+    Ping,
+    /// `0xA` - answers a [`Ping`](Self::Ping), echoing its payload.
+    Pong,
+}
+
+impl WebsocketOpcode {
+    fn as_byte(self) -> u8 {
+        match self {
+            Self::Continuation => 0x0,
+            Self::Binary => 0x2,
+            Self::Close => 0x8,
+            Self::Ping => 0x9,
+            Self::Pong => 0xA,
+        }
+    }
+
+    /// Control frames (close/ping/pong) carry protocol signaling rather than
+    /// application data, and RFC 6455 section 5.5 forbids fragmenting them.
+    fn is_control(self) -> bool {
+        matches!(self, Self::Close | Self::Ping | Self::Pong)
+    }
+}
+
+/// Status codes sent in a close frame's payload, mirroring RFC 6455 section
+/// 7.4.1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CloseCode {
+    /// Normal, expected closure.
+    Normal = 1000,
+    /// The peer violated the WebSocket protocol (e.g. a bad opcode, or a
+    /// fragmented/oversized control frame).
+    ProtocolError = 1002,
+    /// The peer sent data it can't accept (e.g. non-binary data this
+    /// implementation doesn't support).
+    InvalidData = 1003,
+    /// The peer sent a message that violates policy, without a more
+    /// specific code applying.
+    PolicyViolation = 1008,
+    /// A message or frame exceeded a configured size limit; see
+    /// [`WebsocketFrameLimits`].
+    MessageTooBig = 1009,
+    /// Catch-all for unexpected conditions preventing the connection from
+    /// continuing.
+    Unexpected = 1011,
+}
+
+/// Limits enforced while parsing incoming WebSocket frames, to bound how
+/// much memory a malicious or buggy peer can make the server buffer.
+///
+/// Defaults to 64 KiB per frame and 1 MiB per reassembled message, in line
+/// with the limits comparable WebSocket implementations ship with. Pass a
+/// custom value to [`Server::with_frame_limits`] to override them.
+#[derive(Debug, Clone, Copy)]
+pub struct WebsocketFrameLimits {
+    /// Maximum declared payload length of a single wire frame.
+    pub max_frame_size: usize,
+    /// Maximum total size of a message reassembled from fragments (see
+    /// [`WebsocketOpcode::Continuation`]).
+    pub max_message_size: usize,
+}
+
+impl Default for WebsocketFrameLimits {
+    fn default() -> Self {
+        Self {
+            max_frame_size: 64 * 1024,
+            max_message_size: 1024 * 1024,
+        }
     }
 }
 
@@ -636,45 +1020,108 @@ struct WebsocketFrame {
     data: Vec<u8>,
     //this is required for frames sent from client to server, but forbidden from server to client.
     mask: bool,
+    /// Whether this was the final frame of a (possibly fragmented) message.
+    fin: bool,
+    opcode: WebsocketOpcode,
 }
 
 enum WebsocketFrameError {
     FrameTooShort,
-    Rejected(String),
+    Rejected(CloseCode, String),
+    /// The frame's declared payload length exceeds `max_frame_size`.
+    TooLarge,
+}
+
+/// A frame's header and payload, kept apart so the payload can be written
+/// straight from the caller's buffer; see [`WebsocketFrame::to_parts`].
+struct FrameParts<'a> {
+    header: Vec<u8>,
+    payload: &'a [u8],
 }
 impl WebsocketFrame {
-    fn new(data: Vec<u8>, mask: bool) -> Self {
-        WebsocketFrame { data, mask }
+    /// Builds a server-to-client control frame (close/ping/pong); always
+    /// unmasked, since only clients are required to mask frames.
+    fn control(opcode: WebsocketOpcode, data: Vec<u8>) -> Self {
+        WebsocketFrame {
+            data,
+            mask: false,
+            fin: true,
+            opcode,
+        }
     }
 
-    fn to_bytes(&self) -> Vec<u8> {
-        let mut frame = Vec::new();
-        //https://datatracker.ietf.org/doc/html/rfc6455#section-5.2
-        //effectively first byte is the opcode,
-        const BINARY: u8 = 0b1000_0010; //binary frame, FIN
-        frame.push(BINARY); // us
-        //second byte is the payload length
+    /// Builds the FIN/opcode byte and the length prefix
+    /// (https://datatracker.ietf.org/doc/html/rfc6455#section-5.2) for a
+    /// frame carrying `len` bytes of payload. Shared by [`Self::to_parts`]
+    /// and [`Self::to_bytes`] so the two stay in sync.
+    fn encode_header_for(len: usize, mask: bool, fin: bool, opcode: WebsocketOpcode) -> Vec<u8> {
+        let mut header = Vec::new();
+        let fin_bit = if fin { 0b1000_0000 } else { 0 };
+        header.push(fin_bit | opcode.as_byte());
         const MASK_ON: u8 = 0b10000000;
         const MASK_OFF: u8 = 0b0000000;
-        let mask_current = if self.mask { MASK_ON } else { MASK_OFF };
-        if self.data.len() <= 125 {
-            frame.push(self.data.len() as u8 | mask_current);
-        } else if self.data.len() <= 65535 {
-            frame.push(126 | mask_current);
-            frame.extend_from_slice(&(self.data.len() as u16).to_be_bytes());
+        let mask_current = if mask { MASK_ON } else { MASK_OFF };
+        if len <= 125 {
+            header.push(len as u8 | mask_current);
+        } else if len <= 65535 {
+            header.push(126 | mask_current);
+            header.extend_from_slice(&(len as u16).to_be_bytes());
         } else {
-            frame.push(127 | mask_current);
-            frame.extend_from_slice(&(self.data.len() as u64).to_be_bytes());
+            header.push(127 | mask_current);
+            header.extend_from_slice(&(len as u64).to_be_bytes());
+        }
+        header
+    }
+
+    /// Splits this frame into its header and a borrow of its payload,
+    /// instead of concatenating them into one owned buffer the way
+    /// [`Self::to_bytes`] does. Lets a caller that already owns the payload
+    /// buffer (e.g. forwarding a proxied response) issue a header write
+    /// followed by writing the original buffer, with no extra payload copy.
+    ///
+    /// Only supports unmasked frames: masking XORs the payload, which needs
+    /// an owned copy regardless, so masked frames should use `to_bytes`.
+    fn to_parts(&self) -> FrameParts<'_> {
+        debug_assert!(!self.mask, "to_parts doesn't support masked frames");
+        FrameParts {
+            header: Self::encode_header_for(self.data.len(), self.mask, self.fin, self.opcode),
+            payload: &self.data,
+        }
+    }
+
+    /// Like [`Self::to_parts`], but for a caller that has a payload buffer
+    /// on hand without wanting to build a whole [`WebsocketFrame`] around
+    /// it first (the hot path for forwarding proxied data). Always
+    /// unmasked, single-frame, [`WebsocketOpcode::Binary`].
+    fn binary_parts(payload: &[u8]) -> FrameParts<'_> {
+        FrameParts {
+            header: Self::encode_header_for(payload.len(), false, true, WebsocketOpcode::Binary),
+            payload,
         }
-        if self.mask {
-            todo!()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        if !self.mask {
+            let parts = self.to_parts();
+            let mut frame = parts.header;
+            frame.extend_from_slice(parts.payload);
+            return frame;
         }
-        //add the payload
-        frame.extend_from_slice(&self.data);
+        let mut frame = Self::encode_header_for(self.data.len(), self.mask, self.fin, self.opcode);
+        //add the payload, masked (with a fresh key appended right before it)
+        //since this is a client->server frame
+        let key = random_mask_key();
+        frame.extend_from_slice(&key);
+        let mut data = self.data.clone();
+        apply_mask(&mut data, key);
+        frame.extend_from_slice(&data);
         frame
     }
 
-    fn from_bytes(bytes: &[u8]) -> Result<(Self, usize), WebsocketFrameError> {
+    fn from_bytes(
+        bytes: &[u8],
+        max_frame_size: usize,
+    ) -> Result<(Self, usize), WebsocketFrameError> {
         if bytes.len() == 0 {
             return Err(WebsocketFrameError::FrameTooShort);
         }
@@ -682,20 +1129,34 @@ impl WebsocketFrame {
         if bytes.len() < 2 {
             return Err(WebsocketFrameError::FrameTooShort);
         }
-        if bytes[0] & 0b1000_0000 == 0 {
-            todo!("FIN bit not handled");
-        }
-        let opcode = bytes[0] & 0b0111_1111;
-        if opcode != 0x2 {
-            //binary frame
-            return Err(WebsocketFrameError::Rejected(format!(
-                "Invalid opcode: {}",
-                opcode
-            )));
-        }
+        let fin = bytes[0] & 0b1000_0000 != 0;
+        //low nibble is the opcode; the high nibble (besides FIN) is FIN/RSV1-3
+        let raw_opcode = bytes[0] & 0b0000_1111;
+        let opcode = match raw_opcode {
+            0x0 => WebsocketOpcode::Continuation,
+            0x2 => WebsocketOpcode::Binary,
+            0x8 => WebsocketOpcode::Close,
+            0x9 => WebsocketOpcode::Ping,
+            0xA => WebsocketOpcode::Pong,
+            _ => {
+                return Err(WebsocketFrameError::Rejected(
+                    CloseCode::ProtocolError,
+                    format!("Invalid opcode: {}", raw_opcode),
+                ));
+            }
+        };
         //second byte is the payload length
         let payload_length = bytes[1] & 0b0111_1111; //mask bit is ignored here
         let mask = bytes[1] & 0b1000_0000 != 0;
+        //RFC 6455 section 5.5: control frames are never fragmented and
+        //never carry an extended (>125 byte) payload length.
+        if opcode.is_control() && (!fin || payload_length > 125) {
+            return Err(WebsocketFrameError::Rejected(
+                CloseCode::ProtocolError,
+                "Control frames must not be fragmented and must carry at most 125 bytes of payload"
+                    .to_string(),
+            ));
+        }
         let mask_begin;
         let len;
         if payload_length < 126 {
@@ -716,6 +1177,9 @@ impl WebsocketFrame {
             len = u64::from_be_bytes(len_bytes.try_into().unwrap()) as usize;
             mask_begin = 10;
         }
+        if len > max_frame_size {
+            return Err(WebsocketFrameError::TooLarge);
+        }
         let mask_bytes = if mask { 4 } else { 0 };
         let data_begin = mask_begin + mask_bytes;
         if bytes.len() < data_begin + len {
@@ -724,13 +1188,40 @@ impl WebsocketFrame {
         let mut data = bytes[data_begin..data_begin + len].to_vec();
         //unmask the data
         if mask {
-            let masking_key = &bytes[mask_begin..mask_begin + 4];
-            for i in 0..data.len() {
-                data[i] ^= masking_key[i % 4];
-            }
+            let masking_key: [u8; 4] = bytes[mask_begin..mask_begin + 4].try_into().unwrap();
+            apply_mask(&mut data, masking_key);
         }
         // eprintln!("data: {:?} length: {:?}", &data, data.len());
-        let frame = WebsocketFrame { data, mask };
+        let frame = WebsocketFrame {
+            data,
+            mask,
+            fin,
+            opcode,
+        };
         Ok((frame, data_begin + len))
     }
 }
+
+/// XORs `data` in place with `key`, cycling through its four bytes.
+///
+/// This is its own inverse, so it's shared by both `to_bytes` (masking an
+/// outgoing client frame) and `from_bytes` (unmasking an incoming one); see
+/// RFC 6455 section 5.3.
+fn apply_mask(data: &mut [u8], key: [u8; 4]) {
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte ^= key[i % 4];
+    }
+}
+
+/// Generates a fresh 4-byte masking key for one outgoing client frame.
+///
+/// RFC 6455 section 5.3 just requires this be "unpredictable", not
+/// cryptographically secure, so rather than pull in a dedicated RNG crate
+/// this reuses the OS-seeded random keys [`RandomState`](std::collections::hash_map::RandomState)
+/// already draws (the same source `HashMap` uses to resist hash-flooding).
+fn random_mask_key() -> [u8; 4] {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    let value = RandomState::new().build_hasher().finish();
+    value.to_ne_bytes()[..4].try_into().unwrap()
+}
@@ -1,14 +1,103 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, LazyLock, Mutex};
 use serde_json::Value;
+use crate::sys::time::Instant;
 use crate::tools::{Argument, InputSchema, Tool, ToolCallError, ToolCallResponse};
 
+/// Default number of log lines [`LogProxy`] retains before evicting the
+/// oldest; see [`set_capacity`].
+const DEFAULT_CAPACITY: usize = 10_000;
+/// Default token-bucket refill rate (lines/sec) for [`LogProxy::add_log`];
+/// see [`set_rate_limit`].
+const DEFAULT_RATE_LIMIT_PER_SEC: usize = 1_000;
+/// Default token-bucket burst size; see [`set_rate_limit`].
+const DEFAULT_RATE_LIMIT_BURST: usize = 2_000;
+
+static CAPACITY: AtomicUsize = AtomicUsize::new(DEFAULT_CAPACITY);
+static RATE_LIMIT_PER_SEC: AtomicUsize = AtomicUsize::new(DEFAULT_RATE_LIMIT_PER_SEC);
+static RATE_LIMIT_BURST: AtomicUsize = AtomicUsize::new(DEFAULT_RATE_LIMIT_BURST);
+
+/// Overrides how many log lines [`LogProxy`] retains before evicting the
+/// oldest (default [`DEFAULT_CAPACITY`]).
+///
+/// Must be called before [`LogProxy::current`] is first accessed; like
+/// `set_max_connections` in the wire server, this is read once when the
+/// singleton is constructed.
+pub fn set_capacity(capacity: usize) {
+    CAPACITY.store(capacity, Ordering::Release);
+}
+
+/// Overrides [`LogProxy::add_log`]'s token-bucket rate limit (default
+/// [`DEFAULT_RATE_LIMIT_PER_SEC`] lines/sec, burst
+/// [`DEFAULT_RATE_LIMIT_BURST`]).
+///
+/// Must be called before [`LogProxy::current`] is first accessed.
+pub fn set_rate_limit(lines_per_sec: usize, burst: usize) {
+    RATE_LIMIT_PER_SEC.store(lines_per_sec, Ordering::Release);
+    RATE_LIMIT_BURST.store(burst, Ordering::Release);
+}
+
 static CURRENT_LOGPROXY: LazyLock<LogProxy> = LazyLock::new(|| {
     LogProxy::new()
 });
 
+/// A token-bucket rate limiter, refilled based on elapsed wall-clock time
+/// rather than a background timer, so it costs nothing when idle.
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    rate_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate_per_sec: usize, burst: usize) -> Self {
+        TokenBucket {
+            capacity: burst as f64,
+            tokens: burst as f64,
+            rate_per_sec: rate_per_sec as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes one token if available, refilling first based on time elapsed
+    /// since the last refill. Returns `false` (taking nothing) if the
+    /// bucket is empty.
+    fn try_take(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate_per_sec).min(self.capacity);
+        self.last_refill = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// [`LogProxy`]'s backing store: a fixed-capacity ring buffer of log lines.
+///
+/// Lines are addressed by a global, ever-increasing position
+/// (`base_offset + index into buffer`) rather than a raw `Vec` index, so
+/// that positions handed out to a caller stay meaningful even after older
+/// lines have been evicted to make room for new ones.
+struct LogProxyState {
+    buffer: VecDeque<String>,
+    capacity: usize,
+    /// The global position of `buffer[0]`; incremented every time an old
+    /// line is evicted to make room for a new one.
+    base_offset: usize,
+    /// Total lines rejected by the rate limiter so far (not lines evicted
+    /// by rotation, which are implied by `base_offset`).
+    dropped: usize,
+    rate_limiter: TokenBucket,
+}
+
 pub struct LogProxy {
-    logs: Arc<Mutex<Vec<String>>>,
+    state: Arc<Mutex<LogProxyState>>,
 }
 
 impl LogProxy {
@@ -16,16 +105,42 @@ impl LogProxy {
         &CURRENT_LOGPROXY
     }
     fn new() -> LogProxy {
-        LogProxy{
-            logs: Arc::new(Mutex::new(Vec::new())),
+        let capacity = CAPACITY.load(Ordering::Acquire);
+        let rate = RATE_LIMIT_PER_SEC.load(Ordering::Acquire);
+        let burst = RATE_LIMIT_BURST.load(Ordering::Acquire);
+        LogProxy {
+            state: Arc::new(Mutex::new(LogProxyState {
+                buffer: VecDeque::with_capacity(capacity),
+                capacity,
+                base_offset: 0,
+                dropped: 0,
+                rate_limiter: TokenBucket::new(rate, burst),
+            })),
         }
     }
 
     pub fn reset(&self) {
-        self.logs.lock().unwrap().clear();
+        let mut state = self.state.lock().unwrap();
+        state.buffer.clear();
+        state.base_offset = 0;
+        state.dropped = 0;
     }
+
+    /// Appends `log`, evicting the oldest line if the buffer is at
+    /// capacity, unless the token-bucket rate limiter is currently empty —
+    /// in which case the line is dropped and counted in `dropped` instead,
+    /// so a log storm can't starve the lock or grow the buffer unbounded.
     pub fn add_log(&self, log: String) {
-        self.logs.lock().unwrap().push(log);
+        let mut state = self.state.lock().unwrap();
+        if !state.rate_limiter.try_take() {
+            state.dropped += 1;
+            return;
+        }
+        if state.buffer.len() >= state.capacity {
+            state.buffer.pop_front();
+            state.base_offset += 1;
+        }
+        state.buffer.push_back(log);
     }
 }
 
@@ -35,6 +150,13 @@ struct LogResponse {
     start_pos: usize,
     end_pos: usize,
     all_logs: usize,
+    /// Total lines dropped so far by the rate limiter (distinct from lines
+    /// evicted by buffer rotation; see `evicted`).
+    dropped: usize,
+    /// `true` if part or all of `[start_pos, end_pos)` had already been
+    /// evicted by buffer rotation, so `logs` covers less than
+    /// `end_pos - start_pos`.
+    evicted: bool,
 }
 pub struct LogwiseRead;
 
@@ -52,7 +174,9 @@ impl Tool for LogwiseRead {
 
         Limitations: in order for logs to be available from this tool, the target application must
         a) log with logwise, and b) call `exfiltrate::logwise::begin_capture()` to begin redirecting
-        logs into this tool.  Logs made before this call will not be available.
+        logs into this tool.  Logs made before this call will not be available.  Logs are kept in a
+        fixed-size buffer, so old logs (and logs exceeding the configured rate limit) may have been
+        evicted or dropped; check the `evicted`/`dropped` fields of the response.
         "
     }
 
@@ -64,12 +188,13 @@ impl Tool for LogwiseRead {
     }
 
     fn call(&self, params: HashMap<String, Value>) -> Result<ToolCallResponse, ToolCallError> {
-        let log_proxy = LogProxy::current().logs.lock().unwrap();
+        let state = LogProxy::current().state.lock().unwrap();
         let length = params.get("length")
             .and_then(|v| v.as_i64())
             .unwrap_or(10) as usize;
 
-        let default_start_pos = log_proxy.len().saturating_sub(length);
+        let all_logs = state.base_offset + state.buffer.len();
+        let default_start_pos = all_logs.saturating_sub(length);
 
         let start_pos = params.get("start_pos")
             .and_then(|v| v.as_i64())
@@ -77,14 +202,25 @@ impl Tool for LogwiseRead {
             .unwrap_or(default_start_pos);
 
         //adjust to make in bounds
-        let start_pos = start_pos.min(log_proxy.len()).max(0);
-        let end_pos = (start_pos + length).min(log_proxy.len());
-        let logs = log_proxy[start_pos..end_pos].to_vec();
+        let start_pos = start_pos.min(all_logs);
+        let end_pos = (start_pos + length).min(all_logs);
+
+        let evicted = start_pos < state.base_offset;
+        let local_start = start_pos.saturating_sub(state.base_offset).min(state.buffer.len());
+        let local_end = end_pos.saturating_sub(state.base_offset).min(state.buffer.len());
+        let logs: Vec<String> = state.buffer.iter()
+            .skip(local_start)
+            .take(local_end.saturating_sub(local_start))
+            .cloned()
+            .collect();
+
         let response = LogResponse {
             logs,
             start_pos,
             end_pos,
-            all_logs: log_proxy.len(),
+            all_logs,
+            dropped: state.dropped,
+            evicted,
         };
         let response_text = serde_json::to_string(&response).unwrap();
         Ok(ToolCallResponse::new(vec![response_text.into()]))
@@ -100,6 +236,7 @@ struct MatchedLog {
 #[derive(Debug,serde::Serialize)]
 struct LogwiseGrepResponse {
     all_logs: usize,
+    dropped: usize,
     matched_logs: Vec<MatchedLog>,
 }
 
@@ -145,16 +282,16 @@ impl Tool for LogwiseGrep {
             .ok_or_else(|| ToolCallError::new(vec!["No pattern".into()]))?;
 
         let regex = regex::Regex::new(pattern).map_err(|_| ToolCallError::new(vec!["Invalid regex".into()]))?;
-        let log_proxy = LogProxy::current().logs.lock().unwrap();
+        let state = LogProxy::current().state.lock().unwrap();
 
 
-        let logs: Vec<MatchedLog> = log_proxy.iter()
+        let logs: Vec<MatchedLog> = state.buffer.iter()
             .enumerate()
             .filter_map(|(i, log)| {
                 if regex.is_match(log) {
                     Some(MatchedLog {
                         log: log.clone(),
-                        position: i,
+                        position: state.base_offset + i,
                     })
                 } else {
                     None
@@ -163,7 +300,8 @@ impl Tool for LogwiseGrep {
             .collect();
 
         let response = LogwiseGrepResponse {
-            all_logs: log_proxy.len(),
+            all_logs: state.base_offset + state.buffer.len(),
+            dropped: state.dropped,
             matched_logs: logs,
         };
         let res = serde_json::to_string(&response).map_err(|e| ToolCallError::new(vec![e.to_string().into()]))?;
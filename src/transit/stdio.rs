@@ -7,6 +7,8 @@
 
 use crate::transit::transit_proxy::TransitProxy;
 use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 /// Standard I/O server for the transit proxy system.
 ///
@@ -28,7 +30,14 @@ use std::io::Write;
 /// // Server now processes stdin/stdout in background thread
 /// # }
 /// ```
-pub struct Server {}
+pub struct Server {
+    /// Checked before each `read_line`, so [`Server::shutdown`] stops the
+    /// loop at the next line boundary. Does not interrupt a read already
+    /// blocked waiting for stdin, since the standard library gives us no way
+    /// to do that short of closing the file descriptor out from under it.
+    shutdown: Arc<AtomicBool>,
+    thread: std::sync::Mutex<Option<std::thread::JoinHandle<()>>>,
+}
 
 impl Server {
     /// Creates a new stdio server for the transit proxy.
@@ -59,6 +68,11 @@ impl Server {
     ///
     /// - Input: JSON-RPC messages on stdin, one per line
     /// - Output: JSON-RPC responses/notifications on stdout, one per line
+    ///
+    /// Dropping the returned `Server` (or calling [`Server::shutdown`])
+    /// signals the background thread to stop after its current line, then
+    /// joins it. See the caveat on that method about a read already blocked
+    /// on stdin.
     pub fn new(mut proxy: TransitProxy) -> Self {
         proxy.bind(move |msg| {
             let mut stdout = std::io::stdout();
@@ -67,11 +81,16 @@ impl Server {
             stdout.write_all(b"\n").unwrap();
             stdout.flush().unwrap();
         });
-        std::thread::Builder::new()
+        let shutdown = Arc::new(AtomicBool::new(false));
+        let loop_shutdown = shutdown.clone();
+        let thread = std::thread::Builder::new()
             .name("exfiltrate::stdio".to_string())
             .spawn(move || {
                 let stdin = std::io::stdin();
                 loop {
+                    if loop_shutdown.load(Ordering::Acquire) {
+                        break;
+                    }
                     let mut buffer = String::new();
                     if stdin.read_line(&mut buffer).is_err() {
                         eprintln!("Failed to read from stdin, exiting...");
@@ -80,8 +99,7 @@ impl Server {
                     eprintln!("Received data from stdin: {}", buffer);
                     let buffer = buffer.trim().as_bytes();
                     match proxy.received_data(buffer) {
-                        Some(response) => {
-                            let as_bytes = serde_json::to_vec(&response).unwrap();
+                        Some(as_bytes) => {
                             let mut stdout = std::io::stdout();
                             stdout.write_all(&as_bytes).unwrap();
                             stdout.write_all(b"\n").unwrap();
@@ -95,6 +113,29 @@ impl Server {
             })
             .unwrap();
         eprintln!("Proxy started on stdin/stdout");
-        Server {}
+        Server {
+            shutdown,
+            thread: std::sync::Mutex::new(Some(thread)),
+        }
+    }
+
+    /// Signals the background thread to stop after its current line (or
+    /// immediately, if it's idle between lines) and joins it.
+    ///
+    /// If the thread is currently blocked inside `read_line` waiting for
+    /// input, this will not return until stdin produces a line or is closed;
+    /// the standard library offers no way to cancel a blocking stdin read.
+    /// Safe to call more than once.
+    pub fn shutdown(&self) {
+        self.shutdown.store(true, Ordering::Release);
+        if let Some(handle) = self.thread.lock().unwrap().take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.shutdown();
     }
 }
@@ -2,8 +2,9 @@ use crate::jrpc::{Request, Response};
 use crate::tools::{ToolCallParams, ToolCallResponse, ToolList};
 use crate::transit::http::{ReadWebSocketOrStream, WriteWebSocketOrStream};
 use crate::transit::log_proxy::LogProxy;
+use crate::transit::transport::{TcpTransport, Transport};
 use std::collections::HashMap;
-use std::net::TcpStream;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -35,13 +36,263 @@ impl Accept {
     }
 }
 
+/// Identifies one of potentially several simultaneously-connected target
+/// applications (see [`SharedAccept::accepts`]). `tools/list` prefixes each
+/// target's tool names with its id (`{id}::{name}`, see
+/// [`TOOL_NAMESPACE_SEPARATOR`]) since two targets may expose a tool with
+/// the same name, and `tools/call` parses that prefix back off to route the
+/// call to the connection that owns it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConnectionId(u64);
+
+impl std::fmt::Display for ConnectionId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Separates a [`ConnectionId`] prefix from a tool's real name in the
+/// namespaced tool names `tools/list` reports for every connected target;
+/// see [`ConnectionId`].
+const TOOL_NAMESPACE_SEPARATOR: &str = "::";
+
+/// Splits a namespaced tool name (`{connection_id}::{name}`, as produced by
+/// [`TransitProxy::aggregate_tools_list`]) back into the [`ConnectionId`] it
+/// names and the target's real tool name, so `tools/call` can route
+/// straight to the connection that owns it. Returns `None` for a name with
+/// no separator, or whose prefix isn't a valid id -- either just means an
+/// ordinary, non-namespaced tool name.
+fn parse_namespaced_tool_name(name: &str) -> Option<(ConnectionId, &str)> {
+    let (prefix, rest) = name.split_once(TOOL_NAMESPACE_SEPARATOR)?;
+    let id: u64 = prefix.parse().ok()?;
+    Some((ConnectionId(id), rest))
+}
+
+/// Merges [`crate::transit::builtin_tools::proxy_only_tools`] into a target's
+/// response to a `latest_tools` call, returning the merged `result` value for
+/// [`TransitProxy::send_request`] to install on the response it forwards to
+/// the client.
+///
+/// The target's response is untrusted wire data, not something this proxy
+/// controls the shape of -- a target that errored the request, returned more
+/// or less than one content item, or returned non-text content yields
+/// [`Error::MalformedTargetResponse`] instead of panicking the thread
+/// handling this client request.
+fn merge_latest_tools_response(
+    msg: &crate::jrpc::Response<serde_json::Value>,
+) -> Result<serde_json::Value, Error> {
+    let result = msg.result.clone().ok_or_else(|| {
+        Error::MalformedTargetResponse(format!(
+            "target returned no result for latest_tools: {:?}",
+            msg.error
+        ))
+    })?;
+    let mut target_response: ToolCallResponse = serde_json::from_value(result)
+        .map_err(|e| Error::MalformedTargetResponse(format!("invalid ToolCallResponse: {e}")))?;
+    if target_response.content.len() != 1 {
+        return Err(Error::MalformedTargetResponse(format!(
+            "expected exactly one tool in response, got: {:?}",
+            target_response.content
+        )));
+    }
+    let tool_info = target_response.content.remove(0);
+    let tool_info = tool_info.as_str().ok_or_else(|| {
+        Error::MalformedTargetResponse("latest_tools content wasn't text".to_string())
+    })?;
+    let mut target_tool_list: ToolList = serde_json::from_str(tool_info)
+        .map_err(|e| Error::MalformedTargetResponse(format!("invalid ToolList: {e}")))?;
+
+    let mut additional_tools = crate::transit::builtin_tools::proxy_only_tools();
+    target_tool_list.tools.append(&mut additional_tools.tools);
+    let as_json = serde_json::to_string(&target_tool_list)
+        .map_err(|e| Error::MalformedTargetResponse(format!("failed to serialize merged tool list: {e}")))?;
+    let tool_call_response = ToolCallResponse::new(vec![as_json.into()]);
+    serde_json::to_value(tool_call_response)
+        .map_err(|e| Error::MalformedTargetResponse(format!("failed to serialize merged response: {e}")))
+}
+
+/// Namespaces a tool name reported by [`TransitProxy::upstream`] in
+/// `tools/list` (`upstream::{name}`, mirroring [`TOOL_NAMESPACE_SEPARATOR`]
+/// for [`ConnectionId`]-namespaced names), so `tools/call` can route it back
+/// upstream instead of to a local target.
+const UPSTREAM_TOOL_NAMESPACE_PREFIX: &str = "upstream::";
+
 /// Thread-safe container for managing accepted connections and notification handling.
 ///
 /// This struct is shared across threads to coordinate connection state
 /// and notification processing.
 pub struct SharedAccept {
-    latest_accept: Option<Accept>,
+    /// Every target application currently connected, keyed by the
+    /// [`ConnectionId`] assigned when it connected.
+    accepts: HashMap<ConnectionId, Accept>,
+    /// The [`ConnectionId`] to route to, for calls that don't name one
+    /// explicitly; pinned by the `select_target` proxy-only tool, and
+    /// defaults to whichever target connected first.
+    selected: Option<ConnectionId>,
+    /// Counter handing out fresh [`ConnectionId`]s as targets connect.
+    next_connection_id: u64,
     process_notifications: Box<dyn Fn(crate::jrpc::Notification) + Send + Sync>,
+    /// Delivers a raw, already-serialized JSON-RPC message to the
+    /// connected client, bound via [`TransitProxy::bind_client`]. Used to
+    /// push server-initiated requests (see [`TransitProxy::call_client`])
+    /// over whichever transport (SSE or WebSocket) the client is using.
+    send_to_client: Box<dyn Fn(&[u8]) + Send + Sync>,
+}
+
+impl SharedAccept {
+    /// Reserves a fresh [`ConnectionId`] for a target that's in the process
+    /// of connecting, without yet registering an [`Accept`] for it.
+    ///
+    /// Needed because `bidi_fn`'s closure has to know its own connection's
+    /// id (so it can reap itself on a fatal framing error) before the
+    /// [`crate::bidirectional_proxy::BidirectionalProxy`] -- and thus the
+    /// [`Accept`] it will belong to -- is constructed.
+    fn reserve_connection_id(&mut self) -> ConnectionId {
+        let id = ConnectionId(self.next_connection_id);
+        self.next_connection_id += 1;
+        id
+    }
+
+    /// Registers `accept` under `id` (previously reserved with
+    /// [`Self::reserve_connection_id`]), selecting it if it's the first
+    /// target connected.
+    fn insert_accept(&mut self, id: ConnectionId, accept: Accept) {
+        if self.selected.is_none() {
+            self.selected = Some(id);
+        }
+        self.accepts.insert(id, accept);
+    }
+
+    /// Tears down a dead connection: removes it from [`Self::accepts`],
+    /// falling back to another connected target (in unspecified order) --
+    /// or `None`, if it was the last one -- for [`Self::selected`] if it was
+    /// the one selected. Returns whether `id` was actually present.
+    fn reap(&mut self, id: ConnectionId) -> bool {
+        let removed = self.accepts.remove(&id).is_some();
+        if removed && self.selected == Some(id) {
+            self.selected = self.accepts.keys().next().copied();
+        }
+        removed
+    }
+}
+
+/// Tracks JSON-RPC requests the proxy has sent to the client (sampling,
+/// `roots/list`, elicitation, ...) so the matching response -- received
+/// back through [`TransitProxy::received_data`] like any other client
+/// message -- can be routed to the call that's waiting on it instead of
+/// being dispatched as a fresh inbound request.
+#[derive(Default)]
+struct PendingRequests {
+    next_id: AtomicU64,
+    waiters: Mutex<HashMap<String, std::sync::mpsc::Sender<Response<serde_json::Value>>>>,
+}
+
+impl PendingRequests {
+    /// Reserves a fresh request id and registers a waiter for its response.
+    fn register(&self) -> (serde_json::Value, std::sync::mpsc::Receiver<Response<serde_json::Value>>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let id = serde_json::Value::from(format!("proxy-{id}"));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.waiters.lock().unwrap().insert(id.to_string(), sender);
+        (id, receiver)
+    }
+
+    /// Routes `response` to its waiter, if one is registered for its id.
+    /// Returns whether a waiter was found; the caller treats a miss as an
+    /// ordinary, unrelated message instead.
+    fn resolve(&self, response: Response<serde_json::Value>) -> bool {
+        let waiter = self.waiters.lock().unwrap().remove(&response.id.to_string());
+        match waiter {
+            Some(sender) => {
+                let _ = sender.send(response);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// How long [`TransitProxy::send_request`] waits for the target to answer a
+/// proxied request before giving up, so a dead or hung target connection
+/// doesn't block the caller forever.
+const TARGET_RESPONSE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Tracks JSON-RPC requests the proxy has forwarded to the target
+/// application (via [`TransitProxy::send_request`]) so a response -- read
+/// back in [`bidi_fn`] off a single shared connection, and which may arrive
+/// in any order relative to other requests the target is still working on
+/// -- is routed to the call that's actually waiting on it, rather than
+/// assumed to belong to whichever call is blocked at the time.
+#[derive(Default)]
+struct TargetRequests {
+    /// Keyed by the request id's string form; each entry also carries the
+    /// [`ConnectionId`] it was sent to (so [`Self::reap`] can find every
+    /// waiter belonging to a connection that just died) and the original
+    /// `id` value (so a reaped waiter's response carries the right id back).
+    waiters: Mutex<
+        HashMap<String, (ConnectionId, serde_json::Value, std::sync::mpsc::Sender<Response<serde_json::Value>>)>,
+    >,
+}
+
+impl TargetRequests {
+    /// Registers a waiter for `id`'s response, sent to connection
+    /// `connection`. Callers must do this before the request is actually
+    /// sent, so a response that races ahead of the caller reaching its
+    /// `recv` always has somewhere to land.
+    fn register(
+        &self,
+        id: &serde_json::Value,
+        connection: ConnectionId,
+    ) -> std::sync::mpsc::Receiver<Response<serde_json::Value>> {
+        let (sender, receiver) = std::sync::mpsc::channel();
+        self.waiters
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), (connection, id.clone(), sender));
+        receiver
+    }
+
+    /// Routes `response` to its waiter, if one is registered for its id.
+    /// Returns whether a waiter was found; a miss means the response
+    /// answers an id this proxy no longer recognizes (most likely one
+    /// [`Self::expire`] or [`Self::reap`] already gave up on), logged and
+    /// dropped rather than panicking.
+    fn resolve(&self, response: Response<serde_json::Value>) -> bool {
+        let waiter = self.waiters.lock().unwrap().remove(&response.id.to_string());
+        match waiter {
+            Some((_, _, sender)) => {
+                let _ = sender.send(response);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes the waiter for `id` without resolving it. Called by
+    /// [`TransitProxy::send_request`] after it gives up waiting, so a
+    /// response that never arrives (or arrives after the timeout) doesn't
+    /// leak its entry forever.
+    fn expire(&self, id: &serde_json::Value) {
+        self.waiters.lock().unwrap().remove(&id.to_string());
+    }
+
+    /// Resolves and removes every waiter registered against `connection`
+    /// with a [`Error::NotConnected`] error, so a request already in flight
+    /// to a connection that just died doesn't hang until
+    /// [`TARGET_RESPONSE_TIMEOUT`]. Called by [`reap_connection`] once
+    /// [`SharedAccept::reap`] has torn the connection down.
+    fn reap(&self, connection: ConnectionId) {
+        self.waiters.lock().unwrap().retain(|_, (owner, id, sender)| {
+            if *owner != connection {
+                return true;
+            }
+            let _ = sender.send(Response::err(
+                crate::jrpc::Error::from_error(Error::NotConnected),
+                id.clone(),
+            ));
+            false
+        });
+    }
 }
 
 /// Core proxy component that manages connections and routes JSON-RPC messages.
@@ -69,8 +320,15 @@ pub struct SharedAccept {
 /// ```
 pub struct TransitProxy {
     shared_accept: Arc<Mutex<SharedAccept>>,
-    message_receiver: std::sync::mpsc::Receiver<crate::jrpc::Response<serde_json::Value>>,
-    message_sender: std::sync::mpsc::Sender<crate::jrpc::Response<serde_json::Value>>,
+    target_requests: Arc<TargetRequests>,
+    pending_requests: Arc<PendingRequests>,
+    /// The protocol version negotiated with the client in `initialize`
+    /// (see [`Self::initialize`]), once negotiation has happened.
+    negotiated_protocol_version: Option<String>,
+    /// Another `TransitProxy` to forward otherwise-unhandled requests to
+    /// (see [`Self::with_upstream`]), for chaining a proxy placed at a
+    /// network boundary to one that actually holds the target connection.
+    upstream: Option<crate::transit::upstream::UpstreamClient>,
 }
 
 /// Errors that can occur during transit proxy operations.
@@ -79,16 +337,36 @@ pub enum Error {
     /// No target application is currently connected to the proxy
     #[error("Not connected to the exfiltrated application")]
     NotConnected,
+    /// The client disconnected while a proxy-initiated request (see
+    /// [`TransitProxy::call_client`]) was still awaiting a response.
+    #[error("Client disconnected before responding")]
+    ClientDisconnected,
     /// Failed to send message through the bidirectional proxy
     #[error("Failed to send message: {0}")]
     TransitError(#[from] crate::bidirectional_proxy::Error),
     /// Failed to parse JSON-RPC message
     #[error("Failed to parse message: {0}")]
     JRPCError(#[from] crate::jrpc::Error),
+    /// The target didn't respond to a proxied request within
+    /// [`TARGET_RESPONSE_TIMEOUT`].
+    #[error("Target did not respond within the timeout")]
+    TargetTimeout,
+    /// Forwarding to the configured upstream proxy (see
+    /// [`TransitProxy::with_upstream`]) failed.
+    #[error("Upstream proxy error: {0}")]
+    UpstreamError(#[from] crate::transit::upstream::Error),
+    /// The target's response to a proxied request wasn't shaped the way
+    /// the proxy expected (e.g. a `latest_tools` answer with no `result`,
+    /// more or less than one content item, or a non-string content item) --
+    /// see where [`TransitProxy::send_request`] merges in the proxy-only
+    /// tools.
+    #[error("Target sent a malformed response: {0}")]
+    MalformedTargetResponse(String),
 }
 
 fn bidi_fn(
-    message_sender: &std::sync::mpsc::Sender<crate::jrpc::Response<serde_json::Value>>,
+    id: ConnectionId,
+    target_requests: &Arc<TargetRequests>,
     per_msg_shared_accept: &Arc<Mutex<SharedAccept>>,
     msg: Box<[u8]>,
 ) -> Option<Box<[u8]>> {
@@ -101,7 +379,13 @@ fn bidi_fn(
         serde_json::from_slice(&msg);
     match response {
         Ok(response) => {
-            message_sender.send(response).unwrap();
+            let response_id = response.id.clone();
+            if !target_requests.resolve(response) {
+                eprintln!(
+                    "transit: Received response from target with no matching request: id={:?}",
+                    response_id
+                );
+            }
             None // We don't need to send a response back, just notify the receiver
         }
         Err(_) => {
@@ -117,19 +401,156 @@ fn bidi_fn(
                     None
                 }
                 Err(e) => {
-                    panic!("Failed to parse message as response or notification: {}", e);
+                    // Bytes that are neither a response nor a notification
+                    // mean this connection's framing is desynced -- reap it
+                    // rather than panicking the whole proxy.
+                    eprintln!(
+                        "transit: connection {id} sent a message that's neither a response nor a \
+                        notification ({e}); tearing it down"
+                    );
+                    reap_connection(id, per_msg_shared_accept, target_requests);
+                    None
+                }
+            }
+        }
+    }
+}
+
+/// How often [`spawn_keepalive`] pings a connected target to catch a hung or
+/// silently half-closed connection -- one that hasn't produced a transport
+/// error (see [`crate::bidirectional_proxy::ConnectionState`]) but also
+/// isn't actually answering anything. Modeled on litep2p's
+/// `PingConfig`/`PingEvent`.
+const PING_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// How long a single keepalive ping is allowed to go unanswered before it
+/// counts as a miss. Shorter than [`TARGET_RESPONSE_TIMEOUT`] since a ping is
+/// a much smaller ask of the target than an arbitrary tool call.
+const PING_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Consecutive missed pings [`spawn_keepalive`] tolerates before reaping the
+/// connection -- a single miss can just be a slow response, not necessarily
+/// a dead target.
+const PING_MISS_THRESHOLD: u32 = 3;
+
+/// Spawns a background thread that periodically pings target connection
+/// `id` (see [`PING_INTERVAL`]) to detect a connection that's gone bad
+/// without the underlying transport noticing, reaping it (see
+/// [`reap_connection`]) after [`PING_MISS_THRESHOLD`] consecutive misses or
+/// as soon as its transport reports anything other than
+/// [`crate::bidirectional_proxy::ConnectionState::Connected`]. Exits on its
+/// own once `id` is no longer in [`SharedAccept::accepts`] -- reaped by this
+/// thread, or removed some other way.
+fn spawn_keepalive(
+    id: ConnectionId,
+    shared_accept: Arc<Mutex<SharedAccept>>,
+    target_requests: Arc<TargetRequests>,
+) {
+    std::thread::Builder::new()
+        .name(format!("exfiltrate::TransitProxy::keepalive::{id}"))
+        .spawn(move || {
+            let mut misses = 0u32;
+            let mut ping_seq = 0u64;
+            loop {
+                std::thread::sleep(PING_INTERVAL);
+                let state = {
+                    let shared = shared_accept.lock().unwrap();
+                    match shared.accepts.get(&id) {
+                        Some(accept) => accept.bidirectional.state(),
+                        None => return, // already torn down
+                    }
+                };
+                if state != crate::bidirectional_proxy::ConnectionState::Connected {
+                    eprintln!("transit: connection {id} transport is {state:?}; reaping it");
+                    reap_connection(id, &shared_accept, &target_requests);
+                    return;
+                }
+                ping_seq += 1;
+                if ping_once(id, ping_seq, &shared_accept, &target_requests) {
+                    misses = 0;
+                } else {
+                    misses += 1;
+                    eprintln!("transit: connection {id} missed a keepalive ping ({misses}/{PING_MISS_THRESHOLD})");
+                    if misses >= PING_MISS_THRESHOLD {
+                        eprintln!("transit: connection {id} missed {misses} consecutive pings; reaping it");
+                        reap_connection(id, &shared_accept, &target_requests);
+                        return;
+                    }
                 }
             }
+        })
+        .unwrap();
+}
+
+/// Sends one liveness ping to connection `id` and waits up to
+/// [`PING_TIMEOUT`] for any response. Returns whether it answered in time;
+/// a miss isn't by itself fatal, see [`PING_MISS_THRESHOLD`].
+fn ping_once(
+    id: ConnectionId,
+    ping_seq: u64,
+    shared_accept: &Arc<Mutex<SharedAccept>>,
+    target_requests: &Arc<TargetRequests>,
+) -> bool {
+    let request = Request::new(
+        "ping".to_string(),
+        None,
+        serde_json::Value::from(format!("keepalive-{id}-{ping_seq}")),
+    );
+    let bytes = serde_json::to_vec(&request).unwrap();
+    let receiver = target_requests.register(&request.id, id);
+    let sent = {
+        let shared = shared_accept.lock().unwrap();
+        match shared.accepts.get(&id) {
+            Some(accept) => accept.bidirectional.send(&bytes),
+            None => {
+                target_requests.expire(&request.id);
+                return false;
+            }
+        }
+    };
+    if sent.is_err() {
+        target_requests.expire(&request.id);
+        return false;
+    }
+    match receiver.recv_timeout(PING_TIMEOUT) {
+        Ok(_) => true,
+        Err(_) => {
+            target_requests.expire(&request.id);
+            false
         }
     }
 }
 
+/// Tears down connection `id`: removes it from [`SharedAccept::accepts`]
+/// (see [`SharedAccept::reap`]) and, if that actually removed something,
+/// resolves any of its in-flight requests with a disconnect error (see
+/// [`TargetRequests::reap`]) so callers blocked in
+/// [`TransitProxy::send_request`]/[`TransitProxy::forward_to`] don't hang
+/// until [`TARGET_RESPONSE_TIMEOUT`]. A no-op if `id` was already reaped by
+/// something else (e.g. the keepalive thread and a framing error racing).
+fn reap_connection(
+    id: ConnectionId,
+    shared_accept: &Arc<Mutex<SharedAccept>>,
+    target_requests: &Arc<TargetRequests>,
+) {
+    if shared_accept.lock().unwrap().reap(id) {
+        target_requests.reap(id);
+    }
+}
+
 impl TransitProxy {
-    /// Creates a new transit proxy instance.
+    /// Creates a new transit proxy instance listening on a plain TCP socket.
+    ///
+    /// This starts a TCP listener on `127.0.0.1:1985` that accepts internal
+    /// proxy connections -- one per exfiltrated target application, so
+    /// several can be connected at once (see [`ConnectionId`]). The proxy
+    /// runs in a background thread and can handle both TCP and WebSocket
+    /// connections.
     ///
-    /// This starts a TCP listener on `127.0.0.1:1985` that waits for
-    /// internal proxy connections. The proxy runs in a background thread
-    /// and can handle both TCP and WebSocket connections.
+    /// Equivalent to `Self::with_transport(TcpTransport::bind("127.0.0.1:1985").unwrap())`;
+    /// use [`Self::with_transport`] directly for a Unix domain socket or
+    /// other [`Transport`], e.g. in a sandbox where binding a loopback TCP
+    /// port is unavailable or insecure.
     ///
     /// # Example
     /// ```
@@ -142,45 +563,91 @@ impl TransitProxy {
     /// # }
     /// ```
     pub fn new() -> Self {
-        let listener = std::net::TcpListener::bind("127.0.0.1:1985").unwrap();
-        eprintln!("transit: listening on {}", listener.local_addr().unwrap());
+        let transport = TcpTransport::bind("127.0.0.1:1985").unwrap();
+        eprintln!(
+            "transit: listening on {}",
+            transport.local_addr().unwrap()
+        );
+        Self::with_transport(transport)
+    }
+
+    /// Like [`Self::new`], but accepts target connections from any
+    /// [`Transport`] (e.g. [`crate::transit::transport::UnixTransport`])
+    /// instead of hardcoding a TCP listener on `127.0.0.1:1985`.
+    ///
+    /// # Example
+    /// ```
+    /// # #[cfg(all(feature = "transit", unix))]
+    /// # {
+    /// use exfiltrate::transit::transit_proxy::TransitProxy;
+    /// use exfiltrate::transit::transport::UnixTransport;
+    ///
+    /// let transport = UnixTransport::bind("/tmp/exfiltrate.sock").unwrap();
+    /// let proxy = TransitProxy::with_transport(transport);
+    /// # }
+    /// ```
+    pub fn with_transport<T: Transport>(mut transport: T) -> Self {
         let shared_accept = Arc::new(Mutex::new(SharedAccept::new()));
-        let per_msg_shared_accept = shared_accept.clone();
-        let per_thread_shared_accept = shared_accept.clone();
+        let per_conn_shared_accept = shared_accept.clone();
+        let insert_shared_accept = shared_accept.clone();
 
-        let (message_sender, message_receiver) = std::sync::mpsc::channel();
-        let per_msg_message_sender = message_sender.clone();
+        let target_requests = Arc::new(TargetRequests::default());
+        let per_conn_target_requests = target_requests.clone();
         std::thread::Builder::new()
             .name("exfiltrate::TransitProxy".to_string())
             .spawn(move || {
-                let stream = listener.accept().unwrap();
-                eprintln!(
-                    "transit_proxy accepted internal_proxy from {}",
-                    stream.0.peer_addr().unwrap()
-                );
-                let split = (stream.0.try_clone().unwrap(), stream.0);
-                let write_stream = WriteWebSocketOrStream::Stream(split.0);
-                let read_stream = ReadWebSocketOrStream::Stream(split.1);
-
-                let bidirectional_proxy = crate::bidirectional_proxy::BidirectionalProxy::new(
-                    write_stream,
-                    read_stream,
-                    move |msg| bidi_fn(&per_msg_message_sender, &per_msg_shared_accept, msg),
-                );
-                let peer_string = format!("{}", stream.1);
-                per_thread_shared_accept.lock().unwrap().latest_accept = Some(Accept {
-                    bidirectional: bidirectional_proxy,
-                    addr: peer_string,
-                });
+                loop {
+                    let (write_stream, read_stream, addr) = match transport.accept() {
+                        Ok(accepted) => accepted,
+                        Err(e) => {
+                            eprintln!("transit: Failed to accept internal_proxy connection: {e}");
+                            continue;
+                        }
+                    };
+                    eprintln!("transit_proxy accepted internal_proxy from {addr}");
+
+                    let id = insert_shared_accept.lock().unwrap().reserve_connection_id();
+                    let target_requests = per_conn_target_requests.clone();
+                    let bidi_shared_accept = per_conn_shared_accept.clone();
+                    let bidirectional_proxy = crate::bidirectional_proxy::BidirectionalProxy::new(
+                        write_stream,
+                        read_stream,
+                        move |msg| bidi_fn(id, &target_requests, &bidi_shared_accept, msg),
+                    );
+                    insert_shared_accept
+                        .lock()
+                        .unwrap()
+                        .insert_accept(id, Accept::new(bidirectional_proxy, addr.clone()));
+                    eprintln!("transit: target connection {id} established from {addr}");
+                    spawn_keepalive(
+                        id,
+                        insert_shared_accept.clone(),
+                        per_conn_target_requests.clone(),
+                    );
+                }
             })
             .unwrap();
         TransitProxy {
             shared_accept,
-            message_receiver,
-            message_sender,
+            target_requests,
+            pending_requests: Arc::new(PendingRequests::default()),
+            negotiated_protocol_version: None,
+            upstream: None,
         }
     }
 
+    /// Configures an upstream `TransitProxy` (reached over its HTTP
+    /// address, e.g. `"127.0.0.1:1984"`) to forward requests to once this
+    /// proxy has exhausted every other way of answering them -- no
+    /// connected target and no matching [`Self::local_fallback`] method --
+    /// instead of failing with [`Error::NotConnected`]. Lets a proxy placed
+    /// at a network boundary chain to another proxy that actually holds the
+    /// target connection; see [`crate::transit::upstream`].
+    pub fn with_upstream(mut self, addr: impl Into<String>) -> Self {
+        self.upstream = Some(crate::transit::upstream::UpstreamClient::new(addr));
+        self
+    }
+
     /// Binds a notification handler to process incoming notifications.
     ///
     /// The handler will be called for each notification received from
@@ -198,55 +665,136 @@ impl TransitProxy {
         shared.process_notifications = Box::new(process_notifications);
     }
 
-    /// Changes the current accepted connection.
+    /// Binds the function used to deliver a raw, already-serialized
+    /// JSON-RPC message to the connected client -- the transport-level
+    /// counterpart to [`Self::bind`], used by [`Self::call_client`] to push
+    /// server-initiated requests.
     ///
-    /// This is used internally to upgrade connections from TCP to WebSocket
-    /// or to replace the current connection with a new one.
+    /// # Arguments
+    ///
+    /// * `send_to_client` - Function that writes `data` to the client over
+    ///   whichever transport (SSE or WebSocket) is currently active
+    pub(crate) fn bind_client<F>(&self, send_to_client: F)
+    where
+        F: Fn(&[u8]) + Send + Sync + 'static,
+    {
+        let mut shared = self.shared_accept.lock().unwrap();
+        shared.send_to_client = Box::new(send_to_client);
+    }
+
+    /// Registers a target connection that upgraded from plain TCP to
+    /// WebSocket framing mid-handshake (see [`crate::transit::http`]).
+    ///
+    /// Unlike the raw TCP connections accepted in [`Self::new`]'s listener
+    /// loop, a WebSocket-upgraded target never had an [`Accept`] of its own
+    /// to replace -- the upgrade happens before one is created -- so this
+    /// always adds a new connection rather than replacing one, same as any
+    /// other target connecting. Returns the newly assigned [`ConnectionId`].
     ///
     /// # Arguments
     ///
-    /// * `new_accept` - Optional tuple of write and read transports
+    /// * `new_accept` - The write and read transports for the upgraded connection
     pub(crate) fn change_accept(
         &self,
-        new_accept: Option<(WriteWebSocketOrStream, ReadWebSocketOrStream)>,
-    ) {
-        let bidi = match new_accept {
-            Some(ws) => {
-                let move_sender = self.message_sender.clone();
-                let move_shared_accept = self.shared_accept.clone();
-                let bidirectional =
-                    crate::bidirectional_proxy::BidirectionalProxy::new(ws.0, ws.1, move |msg| {
-                        let move_sender = move_sender.clone();
-                        bidi_fn(&move_sender, &move_shared_accept, msg)
-                    });
-                Some(Accept::new(bidirectional, "WebSocket".to_string()))
-            }
-            None => None,
-        };
-        let mut shared = self.shared_accept.lock().unwrap();
-        shared.latest_accept = bidi;
-        eprintln!("transit: Changed accept to {:?}", shared.latest_accept);
+        new_accept: (WriteWebSocketOrStream, ReadWebSocketOrStream),
+    ) -> ConnectionId {
+        let id = self.shared_accept.lock().unwrap().reserve_connection_id();
+        let target_requests = self.target_requests.clone();
+        let bidi_shared_accept = self.shared_accept.clone();
+        let bidirectional =
+            crate::bidirectional_proxy::BidirectionalProxy::new(new_accept.0, new_accept.1, move |msg| {
+                bidi_fn(id, &target_requests, &bidi_shared_accept, msg)
+            });
+        self.shared_accept
+            .lock()
+            .unwrap()
+            .insert_accept(id, Accept::new(bidirectional, "WebSocket".to_string()));
+        eprintln!("transit: Added WebSocket-upgraded target connection {id}");
+        spawn_keepalive(id, self.shared_accept.clone(), self.target_requests.clone());
+        id
     }
 }
 
 impl TransitProxy {
     /// Processes incoming data from a client.
     ///
-    /// Parses the data as either a JSON-RPC request or notification.
-    /// Requests are forwarded to the target (if connected) or handled
-    /// locally for certain methods. Returns a response for requests,
-    /// or `None` for notifications.
+    /// Parses `data` as a JSON-RPC response, request, notification, or --
+    /// per the JSON-RPC 2.0 spec -- a batch (a top-level array of any of
+    /// the above). Each element is handled as [`Self::received_single`]
+    /// describes; a batch's responses are collected into a single JSON
+    /// array, and an all-notification batch yields no response body at
+    /// all, same as a lone notification would.
     ///
     /// # Arguments
     ///
-    /// * `data` - Raw bytes containing JSON-RPC message
+    /// * `data` - Raw bytes containing a JSON-RPC message or batch
     ///
     /// # Returns
     ///
-    /// * `Some(Response)` for requests
-    /// * `None` for notifications
-    pub fn received_data(&mut self, data: &[u8]) -> Option<Response<serde_json::Value>> {
-        let parse_request: Result<Request, _> = serde_json::from_slice(&data);
+    /// * `Some(bytes)` - the serialized response to send back: a single
+    ///   object for a lone request, or an array for a batch with at least
+    ///   one member expecting a response
+    /// * `None` - nothing to send back (a notification, a response to a
+    ///   proxy-initiated request, or a batch made up entirely of those)
+    pub fn received_data(&mut self, data: &[u8]) -> Option<Vec<u8>> {
+        let value: serde_json::Value = match serde_json::from_slice(data) {
+            Ok(value) => value,
+            Err(e) => {
+                eprintln!("transit: Failed to parse message as JSON: {}", e);
+                return None;
+            }
+        };
+        match value {
+            serde_json::Value::Array(items) => self.received_batch(items),
+            single => self
+                .received_single(single)
+                .map(|response| serde_json::to_vec(&response).unwrap()),
+        }
+    }
+
+    /// Dispatches every element of a JSON-RPC batch; see
+    /// [`Self::received_data`] for the rules this follows.
+    fn received_batch(&mut self, items: Vec<serde_json::Value>) -> Option<Vec<u8>> {
+        let responses: Vec<Response<serde_json::Value>> = items
+            .into_iter()
+            .filter_map(|item| self.received_single(item))
+            .collect();
+        if responses.is_empty() {
+            None
+        } else {
+            Some(serde_json::to_vec(&responses).unwrap())
+        }
+    }
+
+    /// Parses a single JSON-RPC value as a response, request, or
+    /// notification, in that order. Responses are only routed as such if
+    /// they're answering a request the proxy itself sent via
+    /// [`Self::call_client`]; anything else parses as a request or
+    /// notification as before. Requests are forwarded to the target (if
+    /// connected) or handled locally for certain methods. Returns a
+    /// response for requests, or `None` for notifications and client
+    /// responses.
+    fn received_single(
+        &mut self,
+        value: serde_json::Value,
+    ) -> Option<Response<serde_json::Value>> {
+        //a JSON-RPC response always carries a `result` or `error`; a request
+        //has neither, so this can't misfire on an ordinary inbound request
+        let parse_response: Result<Response<serde_json::Value>, _> =
+            serde_json::from_value(value.clone());
+        if let Ok(response) = parse_response
+            && (response.result.is_some() || response.error.is_some())
+        {
+            let id = response.id.clone();
+            if !self.pending_requests.resolve(response) {
+                eprintln!(
+                    "transit: Received response with no matching proxy-initiated request: id={:?}",
+                    id
+                );
+            }
+            return None;
+        }
+        let parse_request: Result<Request, _> = serde_json::from_value(value.clone());
         match parse_request {
             Ok(request) => {
                 let request_id = request.id.clone();
@@ -263,21 +811,232 @@ impl TransitProxy {
             }
             Err(_) => {
                 //try parsing as a notification
-                let parse_notification: crate::jrpc::Notification =
-                    serde_json::from_slice(&data).expect("Failed to parse JSON-RPC notification");
-                eprintln!("transit: Parsed notification: {:?}", parse_notification);
-                if parse_notification.method == "notifications/initialized" {
-                    self.initial_setup();
+                let parse_notification: Result<crate::jrpc::Notification, _> =
+                    serde_json::from_value(value);
+                match parse_notification {
+                    Ok(notification) => {
+                        eprintln!("transit: Parsed notification: {:?}", notification);
+                        if notification.method == "notifications/initialized" {
+                            self.initial_setup();
+                        } else {
+                            //anything else the client sends (e.g.
+                            //`notifications/cancelled`, `notifications/roots/list_changed`)
+                            //is the target's business, not ours
+                            self.send_notification(notification);
+                        }
+                    }
+                    Err(e) => {
+                        eprintln!("transit: Failed to parse message as request or notification: {}", e);
+                    }
                 }
                 None
             }
         }
     }
+
+    /// Sends a JSON-RPC request to the connected client and blocks until
+    /// its response arrives, routed back by [`Self::received_data`] via
+    /// [`PendingRequests`].
+    ///
+    /// This is how the proxy issues server-initiated MCP requests --
+    /// sampling, `roots/list`, elicitation -- which only the *client* (not
+    /// the target application) can answer.
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The JSON-RPC method to invoke on the client
+    /// * `params` - Optional parameters for the call
+    pub fn call_client(
+        &self,
+        method: String,
+        params: Option<serde_json::Value>,
+    ) -> Result<Response<serde_json::Value>, Error> {
+        let (id, receiver) = self.pending_requests.register();
+        let request = Request::new(method, params, id);
+        let as_bytes = serde_json::to_vec(&request).unwrap();
+        (self.shared_accept.lock().unwrap().send_to_client)(&as_bytes);
+        receiver.recv().map_err(|_| Error::ClientDisconnected)
+    }
+
+    /// Forwards `message` straight to the target connection `id` and blocks
+    /// for its response, routed back by [`bidi_fn`] via [`TargetRequests`].
+    /// Used both for namespaced `tools/call` routing and for fanning
+    /// `tools/list` out to every connected target.
+    fn forward_to(
+        &self,
+        id: ConnectionId,
+        message: &Request,
+    ) -> Result<Response<serde_json::Value>, Error> {
+        let bytes = serde_json::to_vec(message).unwrap();
+        let receiver = self.target_requests.register(&message.id, id);
+        let send_result = {
+            let shared = self.shared_accept.lock().unwrap();
+            match shared.accepts.get(&id) {
+                Some(accept) => accept.bidirectional.send(&bytes),
+                None => {
+                    drop(shared);
+                    self.target_requests.expire(&message.id);
+                    return Err(Error::NotConnected);
+                }
+            }
+        };
+        if let Err(err) = send_result {
+            self.target_requests.expire(&message.id);
+            return Err(err.into());
+        }
+        match receiver.recv_timeout(TARGET_RESPONSE_TIMEOUT) {
+            Ok(response) => Ok(response),
+            Err(_) => {
+                self.target_requests.expire(&message.id);
+                Err(Error::TargetTimeout)
+            }
+        }
+    }
+
+    /// Implements the `tools/list` handling of [`Self::send_request`]:
+    /// queries every connected target in turn via [`Self::forward_to`],
+    /// namespaces each target's tool names with its [`ConnectionId`] (see
+    /// [`TOOL_NAMESPACE_SEPARATOR`]), merges in [`Self::upstream`]'s own
+    /// `tools/list` (namespaced with [`UPSTREAM_TOOL_NAMESPACE_PREFIX`]) if
+    /// one is configured, after the proxy-only tools, which lead the list. A target or
+    /// upstream that errors, times out, or answers with something
+    /// unparsable is logged and simply contributes no tools, rather than
+    /// failing the whole aggregate list.
+    fn aggregate_tools_list(&self, message: &Request) -> Response<serde_json::Value> {
+        let connection_ids: Vec<ConnectionId> = {
+            let shared = self.shared_accept.lock().unwrap();
+            shared.accepts.keys().copied().collect()
+        };
+        let mut tools = crate::transit::builtin_tools::proxy_only_tools().tools;
+        for id in connection_ids {
+            let per_target_request = Request::new(
+                message.method.clone(),
+                message.params.clone(),
+                message.id.clone(),
+            );
+            let response = match self.forward_to(id, &per_target_request) {
+                Ok(response) => response,
+                Err(e) => {
+                    eprintln!("transit: failed to list tools from target {id}: {e}");
+                    continue;
+                }
+            };
+            let Some(result) = response.result else {
+                eprintln!(
+                    "transit: target {id} returned an error for tools/list: {:?}",
+                    response.error
+                );
+                continue;
+            };
+            let target_tool_list: ToolList = match serde_json::from_value(result) {
+                Ok(list) => list,
+                Err(e) => {
+                    eprintln!("transit: target {id} returned an unparsable tools/list response: {e}");
+                    continue;
+                }
+            };
+            for tool in target_tool_list.tools {
+                let namespaced = format!("{id}{TOOL_NAMESPACE_SEPARATOR}{}", tool.name());
+                tools.push(tool.renamed(namespaced));
+            }
+        }
+        if let Some(upstream) = &self.upstream {
+            let upstream_request = Request::new(
+                message.method.clone(),
+                message.params.clone(),
+                message.id.clone(),
+            );
+            match upstream.forward(&upstream_request) {
+                Ok(response) => match response.result {
+                    Some(result) => match serde_json::from_value::<ToolList>(result) {
+                        Ok(upstream_tool_list) => {
+                            for tool in upstream_tool_list.tools {
+                                let namespaced =
+                                    format!("{UPSTREAM_TOOL_NAMESPACE_PREFIX}{}", tool.name());
+                                tools.push(tool.renamed(namespaced));
+                            }
+                        }
+                        Err(e) => {
+                            eprintln!("transit: upstream returned an unparsable tools/list response: {e}")
+                        }
+                    },
+                    None => eprintln!(
+                        "transit: upstream returned an error for tools/list: {:?}",
+                        response.error
+                    ),
+                },
+                Err(e) => eprintln!("transit: failed to list tools from upstream: {e}"),
+            }
+        }
+        let list = ToolList {
+            tools,
+            revision: crate::tools::current_revision(),
+        };
+        Response::new(list, message.id.clone()).erase()
+    }
+
+    /// Implements the `list_targets` proxy-only tool: reports every
+    /// connected target's [`ConnectionId`] and address, and which one is
+    /// selected for unprefixed `tools/call` routing (see
+    /// [`Self::select_target`]).
+    fn list_targets(&self) -> ToolCallResponse {
+        let shared = self.shared_accept.lock().unwrap();
+        let mut targets: Vec<serde_json::Value> = shared
+            .accepts
+            .iter()
+            .map(|(id, accept)| {
+                serde_json::json!({
+                    "connection_id": id.to_string(),
+                    "addr": accept.addr,
+                    "selected": shared.selected == Some(*id),
+                })
+            })
+            .collect();
+        targets.sort_by(|a, b| a["connection_id"].as_str().cmp(&b["connection_id"].as_str()));
+        ToolCallResponse::new(vec![serde_json::Value::Array(targets).into()])
+    }
+
+    /// Implements the `select_target` proxy-only tool: pins the connection
+    /// that unprefixed `tools/call` invocations route to, to the one named
+    /// by the `connection_id` argument. A missing or unrecognized id is
+    /// reported as a tool error (not an `Err`, matching the MCP convention
+    /// every other proxy-only tool uses) rather than failing the JSON-RPC
+    /// call outright.
+    fn select_target(&self, params: &ToolCallParams) -> ToolCallResponse {
+        let Some(requested) = params.arguments.get("connection_id").and_then(|v| v.as_str())
+        else {
+            return crate::tools::ToolCallError::new(vec![
+                "missing required argument `connection_id`".into(),
+            ])
+            .into_response();
+        };
+        let Ok(raw_id) = requested.parse::<u64>() else {
+            return crate::tools::ToolCallError::new(vec![
+                format!("invalid connection_id: {requested}").into(),
+            ])
+            .into_response();
+        };
+        let id = ConnectionId(raw_id);
+        let mut shared = self.shared_accept.lock().unwrap();
+        if !shared.accepts.contains_key(&id) {
+            return crate::tools::ToolCallError::new(vec![
+                format!("no target connected with id {id}").into(),
+            ])
+            .into_response();
+        }
+        shared.selected = Some(id);
+        ToolCallResponse::new(vec![format!("selected target {id}").into()])
+    }
+
     /// Sends a JSON-RPC request to the target application.
     ///
-    /// Some requests are handled locally (like "initialize"), while others
-    /// are forwarded to the connected target. If no target is connected,
-    /// falls back to local handling for supported methods.
+    /// Some requests are handled locally (like "initialize", and
+    /// `list_targets`/`select_target`), `tools/list` is aggregated across
+    /// every connected target (see [`Self::aggregate_tools_list`]), and a
+    /// namespaced `tools/call` (`{connection_id}::{name}`) routes straight
+    /// to the connection that owns it. Everything else forwards to the
+    /// selected target (see `select_target`); if none is connected, falls
+    /// back to local handling for supported methods.
     ///
     /// This method also handles tool injection, adding proxy-only tools
     /// to the responses from the target.
@@ -296,169 +1055,227 @@ impl TransitProxy {
     ) -> Result<crate::jrpc::Response<serde_json::Value>, Error> {
         // some things we do locally always
         match message.method.as_str() {
-            "initialize" => return Ok(initialize(message).erase()),
+            "initialize" => return Ok(self.initialize(message)),
             _ => {}
         }
-        let mut shared = self.shared_accept.lock().unwrap();
+
+        let tool_call_params: Option<ToolCallParams> = if message.method == "tools/call" {
+            message
+                .params
+                .as_ref()
+                .and_then(|p| serde_json::from_value(p.clone()).ok())
+        } else {
+            None
+        };
+
+        // list_targets/select_target need live access to this proxy's
+        // connection set, which a stateless Tool::call can't reach --
+        // intercept them directly, the same way "initialize" is.
+        if let Some(params) = &tool_call_params {
+            match params.name.as_str() {
+                "list_targets" => {
+                    return Ok(Response::new(self.list_targets(), message.id).erase());
+                }
+                "select_target" => {
+                    return Ok(Response::new(self.select_target(params), message.id).erase());
+                }
+                _ => {}
+            }
+        }
+
+        if message.method == "tools/list" {
+            return Ok(self.aggregate_tools_list(&message));
+        }
+
+        // A namespaced tool name routes straight to the connection that
+        // owns it, regardless of which target is currently selected.
+        if let Some(params) = &tool_call_params
+            && let Some((id, real_name)) = parse_namespaced_tool_name(&params.name)
+        {
+            let rewritten = ToolCallParams::new(real_name.to_string(), params.arguments.clone());
+            let rewritten_request = Request::new(
+                message.method.clone(),
+                Some(serde_json::to_value(rewritten).unwrap()),
+                message.id.clone(),
+            );
+            return self.forward_to(id, &rewritten_request);
+        }
+
+        // Likewise, a tool name namespaced for the upstream proxy (see
+        // `aggregate_tools_list`) routes straight there.
+        if let Some(params) = &tool_call_params
+            && let Some(real_name) = params.name.strip_prefix(UPSTREAM_TOOL_NAMESPACE_PREFIX)
+        {
+            let Some(upstream) = &self.upstream else {
+                return Err(Error::NotConnected);
+            };
+            let rewritten = ToolCallParams::new(real_name.to_string(), params.arguments.clone());
+            let rewritten_request = Request::new(
+                message.method.clone(),
+                Some(serde_json::to_value(rewritten).unwrap()),
+                message.id.clone(),
+            );
+            return Ok(upstream.forward(&rewritten_request)?);
+        }
+
+        let selected = self.shared_accept.lock().unwrap().selected;
+        let Some(id) = selected else {
+            return self.local_fallback(message);
+        };
         let request = serde_json::to_vec(&message).unwrap();
-        //some things we do locally IF there's no connection
-        match &mut shared.latest_accept {
-            Some(accept) => {
-                //handle proxy_only_tools
-                match message.method.as_str() {
-                    "tools/call" => {
-                        //try proxy_only tools first
-                        let tool_call_params: ToolCallParams =
-                            serde_json::from_value(message.params.as_ref().unwrap().clone())
-                                .unwrap();
-                        let r = crate::transit::builtin_tools::call_proxy_only_tool(
-                            tool_call_params.clone(),
-                        );
-                        match r {
-                            Ok(response) => {
-                                let response = Response::new(response, message.id).erase();
-                                eprintln!(
-                                    "transit: Sending response to proxy-only tool call: {:?}",
-                                    response
-                                );
-                                accept
-                                    .bidirectional
-                                    .send(&serde_json::to_vec(&response).unwrap())?;
-                                return Ok(response);
-                            }
-                            Err(_) => {
-                                //fallthrough to remote call
-                            }
-                        }
-                        //check specific tools
-                        match tool_call_params.name.as_str() {
-                            "run_latest_tool" => {
-                                //here we need to get the inner tool params
-                                let tool_name = tool_call_params
-                                    .arguments
-                                    .get("tool_name")
-                                    .unwrap()
-                                    .as_str()
-                                    .unwrap()
-                                    .to_string();
-                                let tool_arguments = tool_call_params
-                                    .arguments
-                                    .get("params")
-                                    .and_then(|v| v.as_object())
-                                    .cloned()
-                                    .unwrap_or_default();
-                                //convert to hashmap
-                                let tool_arguments: HashMap<String, serde_json::Value> =
-                                    tool_arguments.into_iter().map(|(k, v)| (k, v)).collect();
-                                let inner_tool_call_params =
-                                    ToolCallParams::new(tool_name, tool_arguments);
-
-                                let proxy_result =
-                                    crate::transit::builtin_tools::call_proxy_only_tool(
-                                        inner_tool_call_params,
-                                    );
-                                eprintln!(
-                                    "transit: proxy_result for run_latest_tool: {:?}",
-                                    proxy_result
-                                );
-                                match proxy_result {
-                                    Ok(response) => {
-                                        let response = Response::new(response, message.id).erase();
-                                        return Ok(response);
-                                    }
-                                    Err(e) => {
-                                        eprintln!("transit: Failed to call proxy-only tool: {}", e);
-                                        //fallthrough to remote call
-                                    }
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                    _ => {
-                        //fallthrough to remote call
+        //handle proxy_only_tools
+        if let Some(tool_call_params) = &tool_call_params {
+            let r = crate::transit::builtin_tools::call_proxy_only_tool(tool_call_params.clone());
+            match r {
+                Ok(response) => {
+                    let response = Response::new(response, message.id).erase();
+                    eprintln!(
+                        "transit: Sending response to proxy-only tool call: {:?}",
+                        response
+                    );
+                    let shared = self.shared_accept.lock().unwrap();
+                    if let Some(accept) = shared.accepts.get(&id) {
+                        accept
+                            .bidirectional
+                            .send(&serde_json::to_vec(&response).unwrap())?;
                     }
+                    return Ok(response);
                 }
-                accept.bidirectional.send(&request)?;
+                Err(_) => {
+                    //fallthrough to remote call
+                }
+            }
+            //check specific tools
+            if tool_call_params.name == "run_latest_tool" {
+                //here we need to get the inner tool params
+                let tool_name = tool_call_params
+                    .arguments
+                    .get("tool_name")
+                    .unwrap()
+                    .as_str()
+                    .unwrap()
+                    .to_string();
+                let tool_arguments = tool_call_params
+                    .arguments
+                    .get("params")
+                    .and_then(|v| v.as_object())
+                    .cloned()
+                    .unwrap_or_default();
+                //convert to hashmap
+                let tool_arguments: HashMap<String, serde_json::Value> =
+                    tool_arguments.into_iter().map(|(k, v)| (k, v)).collect();
+                let inner_tool_call_params = ToolCallParams::new(tool_name, tool_arguments);
+
+                let proxy_result =
+                    crate::transit::builtin_tools::call_proxy_only_tool(inner_tool_call_params);
                 eprintln!(
-                    "transit: Request sent to remote accept: {:?} {:?}",
-                    accept.addr,
-                    String::from_utf8_lossy(&request)
-                );
-                drop(shared);
-                eprintln!("transit: Waiting for response to request: {:?}", message);
-                let mut msg = self.message_receiver.recv().unwrap();
-                assert!(
-                    msg.id == message.id,
-                    "Received response with mismatched ID: expected {:?}, got {:?}",
-                    message.id,
-                    msg.id
+                    "transit: proxy_result for run_latest_tool: {:?}",
+                    proxy_result
                 );
-                eprintln!("transit: Received response: {:?}", msg);
-                //some tools we merge local and remote behaviors
-                match message.method.as_str() {
-                    "tools/list" => {
-                        //we want to merge this with the builtin_only tools
-                        let mut additional_tools =
-                            crate::transit::builtin_tools::proxy_only_tools();
-                        //parse tool list
-                        let mut target_tool_list: ToolList =
-                            serde_json::from_value(msg.result.unwrap()).unwrap();
-                        target_tool_list.tools.append(&mut additional_tools.tools);
-                        msg.result = Some(serde_json::to_value(target_tool_list).unwrap());
-                        eprintln!("transit injected proxy-only tools into response: {:?}", msg);
+                match proxy_result {
+                    Ok(response) => {
+                        let response = Response::new(response, message.id).erase();
+                        return Ok(response);
                     }
-                    "tools/call" => {
-                        let params = message.params.as_ref().unwrap();
-                        let tool_call_params: ToolCallParams =
-                            serde_json::from_value(params.clone()).unwrap();
-                        match tool_call_params.name.as_str() {
-                            "latest_tools" => {
-                                //we want to merge this with the builtin_only tools
-                                let mut additional_tools =
-                                    crate::transit::builtin_tools::proxy_only_tools();
-                                //parse tool list
-                                eprintln!("msg result before: {:?}", msg.result);
-                                let mut target_response: ToolCallResponse =
-                                    serde_json::from_value(msg.result.unwrap()).unwrap();
-                                assert_eq!(
-                                    target_response.content.len(),
-                                    1,
-                                    "Expected exactly one tool in response, got: {:?}",
-                                    target_response.content
-                                );
-                                let tool_info = target_response.content.remove(0);
-
-                                let mut target_tool_list: ToolList =
-                                    serde_json::from_str(tool_info.as_str().unwrap()).unwrap();
-                                target_tool_list.tools.append(&mut additional_tools.tools);
-                                let as_json = serde_json::to_string(&target_tool_list).unwrap();
-                                let tool_call_response =
-                                    ToolCallResponse::new(vec![as_json.into()]);
-                                msg.result =
-                                    Some(serde_json::to_value(tool_call_response).unwrap());
-                                eprintln!(
-                                    "transit injected proxy-only tools into response: {:?}",
-                                    msg
-                                );
-                            }
-                            _ => {
-                                //we don't do anything special for other tools
-                            }
-                        }
+                    Err(e) => {
+                        eprintln!("transit: Failed to call proxy-only tool: {}", e);
+                        //fallthrough to remote call
                     }
-                    _ => {}
                 }
-
-                Ok(msg)
             }
-            None => return Self::local_fallback(message),
         }
+        let receiver = self.target_requests.register(&message.id, id);
+        {
+            let shared = self.shared_accept.lock().unwrap();
+            let accept = shared.accepts.get(&id).ok_or(Error::NotConnected)?;
+            accept.bidirectional.send(&request)?;
+            eprintln!(
+                "transit: Request sent to remote accept: {:?} {:?}",
+                accept.addr,
+                String::from_utf8_lossy(&request)
+            );
+        }
+        eprintln!("transit: Waiting for response to request: {:?}", message);
+        let mut msg = match receiver.recv_timeout(TARGET_RESPONSE_TIMEOUT) {
+            Ok(msg) => msg,
+            Err(_) => {
+                self.target_requests.expire(&message.id);
+                return Err(Error::TargetTimeout);
+            }
+        };
+        eprintln!("transit: Received response: {:?}", msg);
+        //some tools we merge local and remote behaviors
+        if message.method == "tools/call"
+            && let Some(tool_call_params) = &tool_call_params
+            && tool_call_params.name == "latest_tools"
+        {
+            match merge_latest_tools_response(&msg) {
+                Ok(merged) => msg.result = Some(merged),
+                Err(e) => {
+                    eprintln!("transit: target sent a malformed latest_tools response: {e}");
+                    return Err(e);
+                }
+            }
+            eprintln!(
+                "transit injected proxy-only tools into response: {:?}",
+                msg
+            );
+        }
+
+        Ok(msg)
+    }
+
+    /// Handles `initialize`: negotiates the protocol version and
+    /// capabilities with the client (see [`negotiate_protocol_version`])
+    /// and records the negotiated version on this proxy (see
+    /// [`Self::negotiated_protocol_version`]) so later message handling can
+    /// branch on it.
+    ///
+    /// Returns a `-32602` Invalid params error, rather than silently
+    /// reporting a version of the proxy's own choosing, if the client
+    /// didn't send a `protocolVersion` or asked for one this proxy doesn't
+    /// speak.
+    fn initialize(&mut self, request: Request) -> Response<serde_json::Value> {
+        let params: InitializeParams = request
+            .params
+            .as_ref()
+            .and_then(|p| serde_json::from_value(p.clone()).ok())
+            .unwrap_or_default();
+        let Some(requested) = params.protocol_version else {
+            return Response::err(
+                crate::jrpc::Error::invalid_params(
+                    "missing required parameter `protocolVersion`".to_string(),
+                ),
+                request.id,
+            );
+        };
+        let Some(negotiated) = negotiate_protocol_version(&requested) else {
+            return Response::err(
+                crate::jrpc::Error::invalid_params(format!(
+                    "unsupported protocolVersion {requested:?}; this proxy supports {SUPPORTED_PROTOCOL_VERSIONS:?}"
+                )),
+                request.id,
+            );
+        };
+        self.negotiated_protocol_version = Some(negotiated.to_string());
+        let capabilities = params
+            .capabilities
+            .keys()
+            .filter(|k| SUPPORTED_CAPABILITIES.contains(&k.as_str()))
+            .cloned()
+            .collect();
+        Response::new(InitializeResult::new(negotiated, capabilities), request.id).erase()
     }
 
     fn initial_setup(&mut self) {}
 
+    /// Handles a request locally once no target is connected: a few
+    /// methods (`tools/list`, `tools/call`) have a proxy-only answer, and
+    /// anything else is forwarded to [`Self::upstream`] if one is
+    /// configured (see [`Self::with_upstream`]) rather than failing
+    /// outright.
     fn local_fallback(
+        &self,
         message: crate::jrpc::Request,
     ) -> Result<crate::jrpc::Response<serde_json::Value>, Error> {
         eprintln!("transit: local fallback for request: {:?}", &message);
@@ -477,26 +1294,45 @@ impl TransitProxy {
                 }
             }
             _ => {
-                eprintln!(
-                    "transit: No connection available, cannot send request: {:?}",
-                    message
-                );
-                return Err(Error::NotConnected);
+                let Some(upstream) = &self.upstream else {
+                    eprintln!(
+                        "transit: No connection available, cannot send request: {:?}",
+                        message
+                    );
+                    return Err(Error::NotConnected);
+                };
+                eprintln!("transit: forwarding unhandled request upstream: {:?}", message);
+                Ok(upstream.forward(&message)?)
             }
         }
     }
 
-    /// Sends a JSON-RPC notification to the target application.
+    /// Sends a JSON-RPC notification to the selected target application
+    /// (see `select_target`), mirroring how [`Self::send_request`] routes an
+    /// unprefixed `tools/call`. A notification has no response to wait for,
+    /// so unlike [`Self::forward_to`] this doesn't register with
+    /// [`TargetRequests`] -- it's fire-and-forget, and simply does nothing
+    /// if no target is connected.
     ///
     /// # Arguments
     ///
     /// * `message` - The notification to send
-    ///
-    /// # Note
-    ///
-    /// This method is not yet implemented.
-    pub fn send_notification(&mut self, _message: crate::jrpc::Notification) {
-        todo!();
+    pub fn send_notification(&mut self, message: crate::jrpc::Notification) {
+        let selected = self.shared_accept.lock().unwrap().selected;
+        let Some(id) = selected else {
+            eprintln!(
+                "transit: No connection available, dropping notification: {:?}",
+                message
+            );
+            return;
+        };
+        let bytes = serde_json::to_vec(&message).unwrap();
+        let shared = self.shared_accept.lock().unwrap();
+        if let Some(accept) = shared.accepts.get(&id) {
+            if let Err(e) = accept.bidirectional.send(&bytes) {
+                eprintln!("transit: Failed to send notification to target {id}: {e}");
+            }
+        }
     }
 }
 
@@ -518,16 +1354,50 @@ impl SharedAccept {
 
     fn new() -> Self {
         SharedAccept {
-            latest_accept: None,
+            accepts: HashMap::new(),
+            selected: None,
+            next_connection_id: 0,
             process_notifications: Box::new(|_notification| {
                 panic!("Notification arrived to unbound accept")
             }),
+            send_to_client: Box::new(|_data| {
+                panic!("Proxy-initiated request sent to unbound accept")
+            }),
         }
     }
 }
 
-fn initialize(request: Request) -> Response<InitializeResult> {
-    Response::new(InitializeResult::new(), request.id)
+/// Protocol versions this proxy understands, newest first. [`TransitProxy::initialize`]
+/// accepts the client's requested version as-is if it's in this list; see
+/// [`negotiate_protocol_version`].
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Capability keys [`TransitProxy::initialize`] recognizes well enough to
+/// report support for in [`InitializeResult::new`]'s response. A client
+/// capability outside this list is silently left out of the response,
+/// rather than echoed back unsupported.
+const SUPPORTED_CAPABILITIES: &[&str] = &["tools"];
+
+/// The `params` of an `initialize` request, as parsed by
+/// [`TransitProxy::initialize`].
+#[derive(Debug, Default, serde::Deserialize)]
+struct InitializeParams {
+    #[serde(rename = "protocolVersion")]
+    protocol_version: Option<String>,
+    #[serde(default)]
+    capabilities: HashMap<String, serde_json::Value>,
+}
+
+/// Picks the version [`TransitProxy::initialize`] reports back from the
+/// client's requested `protocolVersion`: accepted as-is if this proxy
+/// supports it, `None` otherwise. The proxy never substitutes a different
+/// version of its own choosing -- a `None` becomes a JSON-RPC error
+/// instead.
+fn negotiate_protocol_version(requested: &str) -> Option<&'static str> {
+    SUPPORTED_PROTOCOL_VERSIONS
+        .iter()
+        .find(|&&supported| supported == requested)
+        .copied()
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -540,17 +1410,24 @@ struct InitializeResult {
 }
 
 impl InitializeResult {
-    fn new() -> Self {
+    /// Builds the `initialize` result for a negotiated `protocol_version`,
+    /// reporting capabilities only for the entries of `capability_keys`
+    /// (the client's requested capabilities, filtered down to
+    /// [`SUPPORTED_CAPABILITIES`] by [`TransitProxy::initialize`]) that this
+    /// proxy actually supports.
+    fn new(protocol_version: &str, capability_keys: Vec<String>) -> Self {
         let mut server_info = HashMap::new();
         server_info.insert("name".to_string(), "exfiltrate".into());
         server_info.insert("version".to_string(), "0.1.0".into());
 
         let mut capabilities = HashMap::new();
-        let mut tool_capabilities = HashMap::new();
-        tool_capabilities.insert("listChanged".to_string(), true.into());
-        capabilities.insert("tools".to_string(), tool_capabilities);
+        if capability_keys.iter().any(|key| key == "tools") {
+            let mut tool_capabilities = HashMap::new();
+            tool_capabilities.insert("listChanged".to_string(), true.into());
+            capabilities.insert("tools".to_string(), tool_capabilities);
+        }
         InitializeResult {
-            protocol_version: "2025-06-18".to_string(),
+            protocol_version: protocol_version.to_string(),
             capabilities,
             server_info,
         }
@@ -0,0 +1,100 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Pluggable listening transports for [`TransitProxy::with_transport`](crate::transit::transit_proxy::TransitProxy::with_transport).
+//!
+//! `TransitProxy::new` binds a TCP listener on `127.0.0.1:1985`, which is
+//! unavailable (or undesirable) in sandboxed environments that can't open a
+//! loopback port. The [`Transport`] trait abstracts the "accept a target
+//! connection" step behind the same split halves
+//! ([`WriteWebSocketOrStream`]/[`ReadWebSocketOrStream`]) the TCP path
+//! already produces, so the accept loop doesn't need to know which concrete
+//! listener it's driving -- following litep2p's transport abstraction and
+//! garage_net's transport-agnostic design.
+
+use crate::transit::http::{ReadWebSocketOrStream, WriteWebSocketOrStream};
+use std::net::{TcpListener, ToSocketAddrs};
+
+/// A listener `TransitProxy` can accept target connections from.
+///
+/// Implementations own a bound listener and block in [`Self::accept`] until
+/// a connection arrives. [`TcpTransport`] and (on Unix) [`UnixTransport`]
+/// cover same-host IPC without a TCP port; adding another transport (e.g.
+/// QUIC) just means a third impl of this trait.
+pub trait Transport: Send + 'static {
+    /// Blocks until a target connects, returning its write/read halves and a
+    /// human-readable address (reported by the `list_targets` proxy-only
+    /// tool) for diagnostics.
+    fn accept(&mut self) -> std::io::Result<(WriteWebSocketOrStream, ReadWebSocketOrStream, String)>;
+}
+
+/// The default transport: a plain TCP listener, matching what
+/// `TransitProxy::new` bound directly before [`Transport`] existed.
+pub struct TcpTransport {
+    listener: TcpListener,
+}
+
+impl TcpTransport {
+    /// Binds a TCP listener on `addr`.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> std::io::Result<Self> {
+        Ok(TcpTransport {
+            listener: TcpListener::bind(addr)?,
+        })
+    }
+
+    /// The address actually bound, e.g. to discover the OS-assigned port
+    /// after binding `"127.0.0.1:0"`.
+    pub fn local_addr(&self) -> std::io::Result<std::net::SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+impl Transport for TcpTransport {
+    fn accept(&mut self) -> std::io::Result<(WriteWebSocketOrStream, ReadWebSocketOrStream, String)> {
+        let (stream, addr) = self.listener.accept()?;
+        let read_stream = stream.try_clone()?;
+        Ok((
+            WriteWebSocketOrStream::Stream(stream),
+            ReadWebSocketOrStream::Stream(read_stream),
+            addr.to_string(),
+        ))
+    }
+}
+
+/// A Unix domain socket listener, for same-host IPC without a TCP port --
+/// useful in sandboxed environments where binding `127.0.0.1` is unavailable
+/// or undesirable.
+#[cfg(unix)]
+pub struct UnixTransport {
+    listener: std::os::unix::net::UnixListener,
+}
+
+#[cfg(unix)]
+impl UnixTransport {
+    /// Binds a Unix domain socket listener at `path`. Fails if `path`
+    /// already exists; callers that want to rebind a stale socket path
+    /// should remove it first.
+    pub fn bind<P: AsRef<std::path::Path>>(path: P) -> std::io::Result<Self> {
+        Ok(UnixTransport {
+            listener: std::os::unix::net::UnixListener::bind(path)?,
+        })
+    }
+}
+
+#[cfg(unix)]
+impl Transport for UnixTransport {
+    fn accept(&mut self) -> std::io::Result<(WriteWebSocketOrStream, ReadWebSocketOrStream, String)> {
+        let (stream, addr) = self.listener.accept()?;
+        let read_stream = stream.try_clone()?;
+        //`SocketAddr::as_pathname` is `None` for an unnamed/abstract
+        //socket -- fall back to a fixed label rather than failing the
+        //accept over a cosmetic address string.
+        let addr = addr
+            .as_pathname()
+            .map(|p| p.display().to_string())
+            .unwrap_or_else(|| "unix:<unnamed>".to_string());
+        Ok((
+            WriteWebSocketOrStream::Unix(stream),
+            ReadWebSocketOrStream::Unix(read_stream),
+            addr,
+        ))
+    }
+}
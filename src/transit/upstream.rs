@@ -0,0 +1,147 @@
+// SPDX-License-Identifier: MIT OR Apache-2.0
+//! Multi-hop chaining: forwarding requests to an upstream `TransitProxy`.
+//!
+//! Mirrors how the Overnet router forwards datagrams on behalf of other
+//! nodes across multiple links to reach a destination that isn't directly
+//! connected: a proxy placed at a network boundary with no target of its
+//! own can still answer by handing the request to another proxy instance
+//! -- the upstream -- that does. [`UpstreamClient`] speaks the same plain
+//! HTTP POST protocol [`crate::transit::http::Server`] serves (see
+//! `Session::handle_body`), one TCP connection per call.
+
+use crate::jrpc::{Request, Response};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+
+/// How many times a request may hop between chained proxies before
+/// [`UpstreamClient::forward`] refuses to forward it further, so a
+/// misconfigured ring of upstreams can't loop a request forever.
+pub const MAX_HOPS: u64 = 8;
+
+/// The `_meta` key [`hop_count`]/`with_incremented_hop_count` read and
+/// write on a forwarded request's `params`, namespaced the same way the
+/// proxy's internal notifications are (`exfiltrate/...`) to avoid colliding
+/// with a client's own `_meta` entries.
+const HOP_COUNT_KEY: &str = "exfiltrate/hopCount";
+
+/// Reads the hop counter already present on `params` (see
+/// [`with_incremented_hop_count`]), or `0` if this request hasn't been
+/// forwarded by another proxy yet.
+pub fn hop_count(params: &Option<serde_json::Value>) -> u64 {
+    params
+        .as_ref()
+        .and_then(|p| p.get("_meta"))
+        .and_then(|m| m.get(HOP_COUNT_KEY))
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+}
+
+/// Returns `params` with its hop counter incremented by one, creating the
+/// `_meta` object if `params` doesn't have one yet. A non-object `params`
+/// (no proxied method sends one) is discarded rather than preserved.
+fn with_incremented_hop_count(
+    params: Option<serde_json::Value>,
+    current: u64,
+) -> serde_json::Value {
+    let mut params = match params {
+        Some(serde_json::Value::Object(obj)) => obj,
+        _ => serde_json::Map::new(),
+    };
+    let mut meta = params
+        .get("_meta")
+        .and_then(|m| m.as_object())
+        .cloned()
+        .unwrap_or_default();
+    meta.insert(HOP_COUNT_KEY.to_string(), (current + 1).into());
+    params.insert("_meta".to_string(), serde_json::Value::Object(meta));
+    serde_json::Value::Object(params)
+}
+
+/// Errors [`UpstreamClient::forward`] can produce, distinct from
+/// [`crate::transit::transit_proxy::Error`] since they're specific to the
+/// upstream hop rather than a local target connection.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// `params._meta["exfiltrate/hopCount"]` already reached [`MAX_HOPS`];
+    /// forwarding further would risk looping the request around a
+    /// misconfigured ring of chained proxies.
+    #[error("request already hopped {0} times; refusing to forward further (possible proxy loop)")]
+    TooManyHops(u64),
+    /// Failed to connect to, write to, or read from the upstream proxy.
+    #[error("upstream proxy I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The upstream's HTTP response couldn't be parsed.
+    #[error("malformed response from upstream proxy: {0}")]
+    MalformedResponse(String),
+}
+
+/// A connection to another `TransitProxy`'s HTTP server, used to forward
+/// requests this proxy can't otherwise answer (see
+/// [`crate::transit::transit_proxy::TransitProxy::with_upstream`]) to a
+/// proxy further from the network boundary that actually holds a target
+/// connection.
+#[derive(Debug, Clone)]
+pub struct UpstreamClient {
+    /// The upstream proxy's HTTP address (e.g. `"127.0.0.1:1984"`),
+    /// resolved fresh on every [`Self::forward`] call rather than cached,
+    /// so the upstream can move without restarting this proxy.
+    addr: String,
+}
+
+impl UpstreamClient {
+    /// Points at an upstream proxy's HTTP address.
+    pub fn new(addr: impl Into<String>) -> Self {
+        UpstreamClient { addr: addr.into() }
+    }
+
+    /// Forwards `request` to the upstream proxy and blocks for its
+    /// response, incrementing the hop counter in its `params._meta` first
+    /// and refusing to forward at all once it's already reached
+    /// [`MAX_HOPS`].
+    pub fn forward(&self, request: &Request) -> Result<Response<serde_json::Value>, Error> {
+        let hops = hop_count(&request.params);
+        if hops >= MAX_HOPS {
+            return Err(Error::TooManyHops(hops));
+        }
+        let forwarded = Request {
+            jsonrpc: request.jsonrpc.clone(),
+            method: request.method.clone(),
+            params: Some(with_incremented_hop_count(request.params.clone(), hops)),
+            id: request.id.clone(),
+        };
+        let body = serde_json::to_vec(&forwarded).unwrap();
+        let mut stream = TcpStream::connect(&self.addr)?;
+        let header = format!(
+            "POST / HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.addr,
+            body.len()
+        );
+        stream.write_all(header.as_bytes())?;
+        stream.write_all(&body)?;
+        stream.flush()?;
+        let mut raw = Vec::new();
+        stream.read_to_end(&mut raw)?;
+        parse_http_response(&raw, &forwarded.id)
+    }
+}
+
+/// Parses a plain (non-chunked) HTTP response as produced by
+/// `Session::handle_body`: a status line, headers terminated by a blank
+/// line, and the JSON body. A forwarded request always carries an id, so
+/// the upstream always answers with a `200 OK` and a body, never the `202
+/// Accepted` with no body it'd send for a bare notification.
+fn parse_http_response(
+    raw: &[u8],
+    id: &serde_json::Value,
+) -> Result<Response<serde_json::Value>, Error> {
+    let header_end = raw
+        .windows(4)
+        .position(|w| w == b"\r\n\r\n")
+        .ok_or_else(|| Error::MalformedResponse("no end of headers found".to_string()))?;
+    let body = &raw[header_end + 4..];
+    if body.is_empty() {
+        return Ok(Response::new(serde_json::Value::Null, id.clone()).erase());
+    }
+    serde_json::from_slice(body)
+        .map_err(|e| Error::MalformedResponse(format!("invalid JSON body: {e}")))
+}